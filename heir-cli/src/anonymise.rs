@@ -0,0 +1,87 @@
+use crate::convert::{write_output, Format};
+use crate::{read_input, Args};
+use heir::anonymise::Anonymiser;
+use std::io::Write;
+
+/// `heir anonymise [--from FORMAT] [--to FORMAT] --key KEY [--scrub-tables] [--hide-cards]
+/// [--keep-notes] INPUT OUTPUT`
+pub fn run(args: &[String], out: &mut dyn Write) -> Result<bool, String> {
+    let args = Args::parse_with_flags(
+        args,
+        &["--from", "--to", "--key"],
+        &["--scrub-tables", "--hide-cards", "--keep-notes"],
+    )?;
+    let [input, output] = args.positional[..] else {
+        return Err("anonymise takes an input and an output.".to_string());
+    };
+    let key = args
+        .option("--key")
+        .ok_or("anonymise needs a --key to derive pseudonyms from.")?;
+    let format = match args.option("--to") {
+        Some(name) => {
+            Format::from_name(name).ok_or_else(|| format!("Unknown output format {:?}.", name))?
+        }
+        None => Format::from_path(output)
+            .ok_or_else(|| format!("Cannot tell the format of {:?}, name it with --to.", output))?,
+    };
+    let (_, import) = read_input(input, args.option("--from"))?;
+    for warning in &import.warnings {
+        eprintln!("warning: {}", warning);
+    }
+    let mut anonymiser = Anonymiser::new(key);
+    anonymiser.scrub_tables = args.flag("--scrub-tables");
+    anonymiser.hide_cards = args.flag("--hide-cards");
+    anonymiser.scrub_notes = !args.flag("--keep-notes");
+    write_output(format, output, out, &anonymiser.anonymise(&import.session))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{args, temp_file, SESSION};
+    use std::fs;
+
+    #[test]
+    fn test_anonymise() -> Result<(), String> {
+        let session = SESSION.replace(
+            "- Hero: 1001\n",
+            "- Hero: 1001\n- Player 1002: red\n> Bluffs Alice a lot.\n",
+        );
+        let input = temp_file("anonymise", "session.heir.md", &session);
+        let input = input.to_string_lossy().into_owned();
+        let anonymise = |options: &[&str]| -> Result<String, String> {
+            let mut out = Vec::new();
+            let mut arguments = vec!["--to", "heir.md"];
+            arguments.extend_from_slice(options);
+            arguments.extend_from_slice(&[&input, "-"]);
+            run(&args(&arguments), &mut out)?;
+            Ok(String::from_utf8(out).expect("UTF-8"))
+        };
+
+        let text = anonymise(&["--key", "secret"])?;
+        let anonymiser = Anonymiser::new("secret");
+        assert!(
+            !text.contains("Alice") && !text.contains("1001"),
+            "{}",
+            text
+        );
+        assert!(text.contains(&format!("- Hero: {}\n", anonymiser.id(1001))));
+        assert!(text.contains("- Location: Home\n"));
+        assert!(text.contains("- Hole cards: AcAs 2c2s\n"));
+        assert!(text.contains(&format!("- Player {}: red\n\n", anonymiser.id(1002))));
+        assert_eq!(anonymise(&["--key", "secret"])?, text);
+        let text = anonymise(&["--key", "secret", "--keep-notes"])?;
+        assert!(text.contains("> Bluffs Alice a lot.\n"), "{}", text);
+
+        let text = anonymise(&["--key", "secret", "--scrub-tables", "--hide-cards"])?;
+        assert!(text.contains("## Table 1\n"), "{}", text);
+        assert!(text.contains("- Location: \n"), "{}", text);
+        assert!(text.contains("- Hole cards: AcAs ????\n"), "{}", text);
+
+        let missing = anonymise(&[]);
+        assert!(missing.unwrap_err().contains("--key"));
+        fs::remove_dir_all(std::path::Path::new(&input).parent().expect("Folder"))
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{read_input, Args};
+use heir::formats::{acpc, bin, json, md, phh, pokerstars};
+use heir::game::Session;
+use heir::perspective::Perspective;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// A format hand histories can be written in.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum Format {
+    Bin,
+    Md,
+    Json,
+    PokerStars,
+    Phh,
+    Acpc,
+}
+
+impl Format {
+    pub(crate) fn from_name(name: &str) -> Option<Format> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "heir.bin" | "bin" => Format::Bin,
+            "heir.md" | "md" => Format::Md,
+            "json" => Format::Json,
+            "pokerstars" => Format::PokerStars,
+            "phh" => Format::Phh,
+            "acpc" => Format::Acpc,
+            _ => return None,
+        })
+    }
+
+    pub(crate) fn from_path(path: &str) -> Option<Format> {
+        let path = path.to_ascii_lowercase();
+        [
+            (".heir.bin", Format::Bin),
+            (".heir.md", Format::Md),
+            (".json", Format::Json),
+            (".txt", Format::PokerStars),
+            (".phh", Format::Phh),
+            (".phhs", Format::Phh),
+            (".log", Format::Acpc),
+        ]
+        .into_iter()
+        .find(|(extension, _)| path.ends_with(extension))
+        .map(|(_, format)| format)
+    }
+
+    fn write<W: Write>(self, writer: &mut W, session: &Session) -> io::Result<()> {
+        match self {
+            Format::Bin => bin::write_session(writer, session),
+            Format::Md => md::write_session(writer, session),
+            Format::Json => json::write_session(writer, session),
+            Format::PokerStars => pokerstars::write_session(writer, session),
+            Format::Phh => phh::write_session(writer, session),
+            Format::Acpc => match &session.tables[..] {
+                [table] => acpc::write_log(writer, table),
+                _ => Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "ACPC logs hold a single table.",
+                )),
+            },
+        }
+    }
+}
+
+/// `heir convert [--from FORMAT] [--to FORMAT] [--hero] INPUT OUTPUT`
+pub fn run(args: &[String], out: &mut dyn Write) -> Result<bool, String> {
+    let args = Args::parse_with_flags(args, &["--from", "--to"], &["--hero"])?;
+    let [input, output] = args.positional[..] else {
+        return Err("convert takes an input and an output.".to_string());
+    };
+    let format = match args.option("--to") {
+        Some(name) => {
+            Format::from_name(name).ok_or_else(|| format!("Unknown output format {:?}.", name))?
+        }
+        None => Format::from_path(output)
+            .ok_or_else(|| format!("Cannot tell the format of {:?}, name it with --to.", output))?,
+    };
+    let (_, import) = read_input(input, args.option("--from"))?;
+    for warning in &import.warnings {
+        eprintln!("warning: {}", warning);
+    }
+    if args.flag("--hero") {
+        let perspective = Perspective::new(&import.session);
+        write_output(format, output, out, &perspective.session)?;
+    } else {
+        write_output(format, output, out, &import.session)?;
+    }
+    Ok(true)
+}
+
+/// Writes a session to a file, or to `out` when the output is `-`.
+pub(crate) fn write_output(
+    format: Format,
+    output: &str,
+    out: &mut dyn Write,
+    session: &Session,
+) -> Result<(), String> {
+    let error = |e: io::Error| format!("{}: {}", output, e);
+    if output == "-" {
+        let mut writer = BufWriter::new(out);
+        format.write(&mut writer, session).map_err(error)?;
+        writer.flush().map_err(error)
+    } else {
+        let mut writer = BufWriter::new(File::create(output).map_err(error)?);
+        format.write(&mut writer, session).map_err(error)?;
+        writer.flush().map_err(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{args, temp_file, SESSION};
+    use std::fs;
+
+    #[test]
+    fn test_convert() -> Result<(), String> {
+        let input = temp_file("convert", "session.heir.md", SESSION);
+        let dir = input.parent().expect("Folder");
+        let path = |name: &str| dir.join(name).to_string_lossy().into_owned();
+        let input = input.to_string_lossy().into_owned();
+
+        let mut out = Vec::new();
+        run(&args(&[&input, &path("a.heir.bin")]), &mut out)?;
+        run(&args(&[&path("a.heir.bin"), &path("b.json")]), &mut out)?;
+        run(&args(&["--to", "heir.md", &path("b.json"), "-"]), &mut out)?;
+        assert_eq!(String::from_utf8(out).expect("UTF-8"), SESSION);
+
+        let mut out = Vec::new();
+        run(&args(&[&input, "--to", "pokerstars", "-"]), &mut out)?;
+        let text = String::from_utf8(out).expect("UTF-8");
+        assert!(text.contains("Bob: raises $2 to $3"), "{}", text);
+
+        let mut out = Vec::new();
+        run(&args(&["--hero", &input, "--to", "heir.md", "-"]), &mut out)?;
+        let text = String::from_utf8(out).expect("UTF-8");
+        assert!(text.contains("- Hole cards: AcAs ????\n"), "{}", text);
+
+        let unknown = run(&args(&[&input, &path("c.xyz")]), &mut Vec::new());
+        assert!(unknown.unwrap_err().contains("name it with --to"));
+        fs::remove_dir_all(dir).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{read_input, Args};
+use heir::formats::Import;
+use heir::game::TableEvent;
+use std::io::{self, Write};
+
+/// `heir inspect [--from FORMAT] INPUT...`
+pub fn run(args: &[String], out: &mut dyn Write) -> Result<bool, String> {
+    let args = Args::parse(args, &["--from"])?;
+    if args.positional.is_empty() {
+        return Err("inspect takes at least one input.".to_string());
+    }
+    for (i, path) in args.positional.iter().enumerate() {
+        let (format, import) = read_input(path, args.option("--from"))?;
+        if i > 0 {
+            writeln!(out).map_err(|e| e.to_string())?;
+        }
+        print(out, path, &format, &import).map_err(|e| e.to_string())?;
+    }
+    Ok(true)
+}
+
+fn print(out: &mut dyn Write, path: &str, format: &str, import: &Import) -> io::Result<()> {
+    let session = &import.session;
+    let count =
+        |events: &[TableEvent], f: fn(&TableEvent) -> bool| events.iter().filter(|e| f(e)).count();
+    let hands: usize = session
+        .tables
+        .iter()
+        .map(|t| count(&t.events, |e| matches!(e, TableEvent::Hand(_))))
+        .sum();
+    writeln!(out, "File: {}", path)?;
+    writeln!(out, "Format: {}", format)?;
+    writeln!(
+        out,
+        "Version: {}.{}",
+        session.version.major(),
+        session.version.minor()
+    )?;
+    writeln!(out, "Session: {} (id {})", session.name, session.id)?;
+    writeln!(out, "Hero: {}", session.hero_id)?;
+    writeln!(out, "Tables: {}", session.tables.len())?;
+    writeln!(out, "Hands: {}", hands)?;
+    if !import.warnings.is_empty() {
+        writeln!(out, "Warnings: {}", import.warnings.len())?;
+        for warning in &import.warnings {
+            writeln!(out, "  {}", warning)?;
+        }
+    }
+    for table in &session.tables {
+        let blinds: Vec<String> = table.blinds.iter().map(u64::to_string).collect();
+        writeln!(out)?;
+        writeln!(out, "Table {} (id {})", table.name, table.id)?;
+        writeln!(out, "  Location: {}", table.location)?;
+        writeln!(out, "  Size: {}", table.table_size)?;
+        writeln!(out, "  Blinds: {}", blinds.join("/"))?;
+        writeln!(
+            out,
+            "  Rake: {}% capped at {}",
+            table.rake_percentage, table.rake_cap
+        )?;
+        writeln!(out, "  Seated: {}", table.initial_context.len())?;
+        writeln!(
+            out,
+            "  Hands: {}",
+            count(&table.events, |e| matches!(e, TableEvent::Hand(_)))
+        )?;
+        writeln!(
+            out,
+            "  Stack updates: {}",
+            count(&table.events, |e| matches!(e, TableEvent::StackUpdate(_)))
+        )?;
+        writeln!(
+            out,
+            "  Seat updates: {}",
+            count(&table.events, |e| matches!(e, TableEvent::SeatUpdate(_)))
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{args, temp_file, SESSION};
+    use std::fs;
+
+    #[test]
+    fn test_inspect() -> Result<(), String> {
+        let path = temp_file("inspect", "session.heir.md", SESSION);
+        let mut out = Vec::new();
+        let result = run(&args(&[&path.to_string_lossy()]), &mut out);
+        fs::remove_dir_all(path.parent().expect("Folder")).map_err(|e| e.to_string())?;
+        assert!(result?);
+
+        let text = String::from_utf8(out).expect("UTF-8");
+        let expected = "\
+Format: heir.md
+Version: 0.1
+Session: Test Session (id 7)
+Hero: 1001
+Tables: 1
+Hands: 1
+
+Table Main (id 1)
+  Location: Home
+  Size: 2
+  Blinds: 50/100
+  Rake: 0% capped at 0
+  Seated: 2
+  Hands: 1
+  Stack updates: 1
+  Seat updates: 0
+";
+        assert!(text.ends_with(expected), "{}", text);
+        Ok(())
+    }
+}
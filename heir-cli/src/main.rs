@@ -0,0 +1,203 @@
+mod anonymise;
+mod convert;
+mod inspect;
+mod query;
+mod validate;
+
+use heir::formats::registry::Registry;
+use heir::formats::Import;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: heir <command> [options]
+
+Commands:
+  convert [--from FORMAT] [--to FORMAT] [--hero] INPUT OUTPUT
+      Converts hand histories. INPUT may be a folder, and either may be - for standard
+      input or output. FORMAT is taken from the file name when not given. --hero keeps
+      only the hero's hands, hiding the hole cards they did not see.
+  validate [--from FORMAT] INPUT...
+      Replays every hand, reporting any that break the rules.
+  inspect [--from FORMAT] INPUT...
+      Prints a summary of each file's session, tables and hands.
+  query [--from FORMAT] [--to FORMAT] EXPRESSION INPUT [OUTPUT]
+      Writes the hands matching EXPRESSION, such as \"hero 3bet and showdown\", to OUTPUT
+      or, in heir.md by default, to standard output. Fails if no hand matches.
+  anonymise [--from FORMAT] [--to FORMAT] --key KEY [--scrub-tables] [--hide-cards]
+            [--keep-notes] INPUT OUTPUT
+      Replaces player ids and names with pseudonyms derived from KEY, keeping the hero,
+      and empties annotation notes unless --keep-notes is given. Tags and labels are kept.
+      --scrub-tables also renames tables and clears their locations, and --hide-cards
+      hides hole cards not shown at showdown, other than the hero's.
+
+Input formats: heir.bin, heir.md, JSON and every supported site.
+Output formats: heir.bin, heir.md, json, pokerstars, phh and acpc.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut out = io::stdout().lock();
+    let result = match args.first().map(String::as_str) {
+        Some("convert") => convert::run(&args[1..], &mut out),
+        Some("validate") => validate::run(&args[1..], &mut out),
+        Some("inspect") => inspect::run(&args[1..], &mut out),
+        Some("query") => query::run(&args[1..], &mut out),
+        Some("anonymise") => anonymise::run(&args[1..], &mut out),
+        Some("help" | "--help" | "-h") => writeln!(out, "{}", USAGE)
+            .map(|_| true)
+            .map_err(|e| e.to_string()),
+        Some(command) => Err(format!("Unknown command {:?}.\n\n{}", command, USAGE)),
+        None => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("heir: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Command-line arguments split into positional arguments, `--name value` options and
+/// `--name` flags.
+struct Args<'a> {
+    positional: Vec<&'a str>,
+    options: HashMap<&'a str, &'a str>,
+    flags: Vec<&'a str>,
+}
+
+impl<'a> Args<'a> {
+    fn parse(args: &'a [String], names: &[&str]) -> Result<Self, String> {
+        Args::parse_with_flags(args, names, &[])
+    }
+
+    fn parse_with_flags(
+        args: &'a [String],
+        names: &[&str],
+        flags: &[&str],
+    ) -> Result<Self, String> {
+        let mut parsed = Args {
+            positional: Vec::new(),
+            options: HashMap::new(),
+            flags: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if flags.contains(&arg.as_str()) {
+                parsed.flags.push(arg);
+            } else if arg.starts_with("--") {
+                if !names.contains(&arg.as_str()) {
+                    return Err(format!("Unknown option {:?}.", arg));
+                }
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Option {} needs a value.", arg))?;
+                parsed.options.insert(arg, value);
+            } else {
+                parsed.positional.push(arg);
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn option(&self, name: &str) -> Option<&'a str> {
+        self.options.get(name).copied()
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+}
+
+/// Reads a file, folder or standard input (`-`), detecting its format unless one is named.
+///
+/// Returns the name of the format with the import.
+fn read_input(path: &str, from: Option<&str>) -> Result<(String, Import), String> {
+    let registry = Registry::default();
+    let error = |e: io::Error| format!("{}: {}", path, e);
+    if from.is_none() && Path::new(path).is_dir() {
+        let import = registry.import_dir(Path::new(path)).map_err(error)?;
+        return Ok(("folder".to_string(), import));
+    }
+    let bytes = if path == "-" {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes).map_err(error)?;
+        bytes
+    } else {
+        fs::read(path).map_err(error)?
+    };
+    let importer = match from {
+        Some(name) => registry
+            .importers()
+            .find(|i| i.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("Unknown input format {:?}.", name))?,
+        None => registry
+            .detect(&bytes)
+            .ok_or_else(|| format!("{}: Unrecognised hand history format.", path))?,
+    };
+    let import = importer.import(&mut bytes.as_slice()).map_err(error)?;
+    Ok((importer.name().to_string(), import))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// A session with one hand, in `.heir.md`.
+    pub(crate) const SESSION: &str = "\
+# Test Session
+
+- Version: 0.1
+- Session: 7
+- Hero: 1001
+
+## Main
+
+- Table: 1
+- Location: Home
+- Size: 2
+- Rake: 0% capped at 0
+- Blinds: 50/100
+
+### Seats
+
+- Seat 0: Alice (1001) 10000
+- Seat 1: Bob (1002) 10000
+
+### Hand 1
+
+- Timestamp: 1724293476
+- Button: 1
+- Hole cards: AcAs 2c2s
+- Board: 3c3hKc
+
+1. raise 300
+2. call 300
+3. bet 600
+4. raise 1800
+5. fold 600
+
+### Stack update
+
+- Seat 1: 20000
+";
+
+    /// Writes a file in a fresh temporary folder for the named test.
+    pub(crate) fn temp_file(test: &str, name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("heir-cli-{}-{}", test, std::process::id()));
+        fs::create_dir_all(&dir).expect("Temporary folder");
+        let path = dir.join(name);
+        fs::write(&path, contents).expect("Temporary file");
+        path
+    }
+
+    pub(crate) fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+}
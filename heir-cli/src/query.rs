@@ -0,0 +1,70 @@
+use crate::convert::{write_output, Format};
+use crate::{read_input, Args};
+use heir::game::TableEvent;
+use heir::query::Query;
+use std::io::Write;
+
+/// `heir query [--from FORMAT] [--to FORMAT] EXPRESSION INPUT [OUTPUT]`
+///
+/// Succeeds only if some hand matches, like `grep`.
+pub fn run(args: &[String], out: &mut dyn Write) -> Result<bool, String> {
+    let args = Args::parse(args, &["--from", "--to"])?;
+    let (expression, input, output) = match args.positional[..] {
+        [expression, input] => (expression, input, "-"),
+        [expression, input, output] => (expression, input, output),
+        _ => return Err("query takes an expression, an input and an optional output.".to_string()),
+    };
+    let query: Query = expression
+        .parse()
+        .map_err(|e| format!("Invalid query: {}", e))?;
+    let format = match (args.option("--to"), output) {
+        (Some(name), _) => {
+            Format::from_name(name).ok_or_else(|| format!("Unknown output format {:?}.", name))?
+        }
+        (None, "-") => Format::Md,
+        (None, _) => Format::from_path(output)
+            .ok_or_else(|| format!("Cannot tell the format of {:?}, name it with --to.", output))?,
+    };
+    let (_, import) = read_input(input, args.option("--from"))?;
+    for warning in &import.warnings {
+        eprintln!("warning: {}", warning);
+    }
+    let session = query.filter(&import.session);
+    write_output(format, output, out, &session)?;
+    Ok(session
+        .tables
+        .iter()
+        .any(|t| t.events.iter().any(|e| matches!(e, TableEvent::Hand(_)))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{args, temp_file, SESSION};
+    use std::fs;
+
+    #[test]
+    fn test_query() -> Result<(), String> {
+        let input = temp_file("query", "session.heir.md", SESSION);
+        let input = input.to_string_lossy().into_owned();
+        let query = |expression: &str| {
+            let mut out = Vec::new();
+            let matched = run(&args(&[expression, &input]), &mut out);
+            (matched, String::from_utf8(out).expect("UTF-8"))
+        };
+
+        let (matched, text) = query("hero call preflop and flop ~ \"brf\"");
+        assert!(matched?);
+        assert!(text.contains("### Hand 1"), "{}", text);
+        let (matched, text) = query("showdown");
+        assert!(!matched?);
+        assert!(!text.contains("### Hand"), "{}", text);
+        let (matched, _) = query("pot > ");
+        assert!(matched
+            .unwrap_err()
+            .contains("Expected an amount at character 7."));
+
+        fs::remove_dir_all(std::path::Path::new(&input).parent().expect("Folder"))
+            .map_err(|e| e.to_string())
+    }
+}
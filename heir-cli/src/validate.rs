@@ -0,0 +1,93 @@
+use crate::{read_input, Args};
+use heir::engine::state::{Inconsistency, Tracker};
+use heir::game::Session;
+use std::io::Write;
+
+/// `heir validate [--from FORMAT] INPUT...`
+///
+/// Succeeds only if every file imports without warnings and every hand replays.
+pub fn run(args: &[String], out: &mut dyn Write) -> Result<bool, String> {
+    let args = Args::parse(args, &["--from"])?;
+    if args.positional.is_empty() {
+        return Err("validate takes at least one input.".to_string());
+    }
+    let mut valid = true;
+    for path in &args.positional {
+        let (_, import) = read_input(path, args.option("--from"))?;
+        let mut problems = import.warnings;
+        let hands = check(&import.session, &mut problems);
+        let write = |out: &mut dyn Write, line: String| {
+            writeln!(out, "{}: {}", path, line).map_err(|e| e.to_string())
+        };
+        for problem in &problems {
+            write(out, problem.clone())?;
+        }
+        let result = match problems.len() {
+            0 => format!("{} hands are valid.", hands),
+            n => format!("{} hands, {} problems.", hands, n),
+        };
+        write(out, result)?;
+        valid &= problems.is_empty();
+    }
+    Ok(valid)
+}
+
+/// Replays every table, adding a problem for each hand that does not replay and each
+/// event that does not fit the seats before it.
+///
+/// Returns the number of hands.
+fn check(session: &Session, problems: &mut Vec<String>) -> usize {
+    let mut hands = 0;
+    for table in &session.tables {
+        let mut tracker = Tracker::new(table);
+        hands += tracker.by_ref().count();
+        for inconsistency in tracker.inconsistencies() {
+            let problem = match (inconsistency, inconsistency.event()) {
+                (Inconsistency::Replay { hand, .. }, _) => {
+                    format!("Table {}, hand {}: {}", table.name, hand, inconsistency)
+                }
+                (_, Some(event)) => {
+                    format!("Table {}, event {}: {}", table.name, event, inconsistency)
+                }
+                (_, None) => format!("Table {}: {}", table.name, inconsistency),
+            };
+            problems.push(problem);
+        }
+    }
+    hands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{args, temp_file, SESSION};
+    use std::fs;
+
+    #[test]
+    fn test_validate() -> Result<(), String> {
+        let valid = temp_file("validate", "valid.heir.md", SESSION);
+        let invalid = temp_file(
+            "validate",
+            "invalid.heir.md",
+            &SESSION
+                .replace("2. call 300", "2. call 250")
+                .replace("- Seat 1: 20000", "- Seat 2: 20000"),
+        );
+        let mut out = Vec::new();
+        let result = run(
+            &args(&[&valid.to_string_lossy(), &invalid.to_string_lossy()]),
+            &mut out,
+        );
+        fs::remove_dir_all(valid.parent().expect("Folder")).map_err(|e| e.to_string())?;
+
+        assert!(!result?);
+        let text = String::from_utf8(out).expect("UTF-8");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4, "{}", text);
+        assert!(lines[0].ends_with("valid.heir.md: 1 hands are valid."));
+        assert!(lines[1].contains("invalid.heir.md: Table Main, hand 1: "));
+        assert!(lines[2].ends_with("Table Main, event 1: Stack update to empty seat 2."));
+        assert!(lines[3].ends_with("invalid.heir.md: 1 hands, 2 problems."));
+        Ok(())
+    }
+}
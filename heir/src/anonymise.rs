@@ -0,0 +1,214 @@
+use crate::game::{Id, Player, Session, TableEvent};
+use crate::perspective::hide_unshown;
+
+/// Replaces the identities in a [`Session`] with pseudonyms derived from a secret key.
+///
+/// The same key always gives a player the same id and name, in every session, so that
+/// anonymised sessions can still be studied together. Without the key, pseudonyms cannot be
+/// traced back to players. Annotation notes are free text that may name players, so they are
+/// emptied unless [`Anonymiser::scrub_notes`] is turned off. Tags and player labels are kept.
+#[derive(Clone, Debug)]
+pub struct Anonymiser {
+    key: [u64; 2],
+    /// Whether tables lose their location and are renamed by their order in the session.
+    pub scrub_tables: bool,
+    /// Whether hole cards not shown at showdown are hidden, except for the hero's, as by
+    /// [`hide_unshown`].
+    pub hide_cards: bool,
+    /// Whether the notes of hand annotations and player notes are emptied. On by default.
+    pub scrub_notes: bool,
+}
+
+impl Anonymiser {
+    pub fn new(key: &str) -> Self {
+        Anonymiser {
+            key: [
+                sip_hash([0, 0], key.as_bytes()),
+                sip_hash([0, 1], key.as_bytes()),
+            ],
+            scrub_tables: false,
+            hide_cards: false,
+            scrub_notes: true,
+        }
+    }
+
+    /// The pseudonym of a player id.
+    pub fn id(&self, id: Id) -> Id {
+        self.hash(b"id", &id.to_le_bytes())
+    }
+
+    /// The pseudonym of a player name (e.g. "Player 3fa9c21b").
+    pub fn name(&self, name: &str) -> String {
+        format!("Player {:08x}", self.hash(b"name", name.as_bytes()) as u32)
+    }
+
+    /// A copy of the session with every player id and name replaced by its pseudonym,
+    /// including the hero, tournament finishes and player notes.
+    pub fn anonymise(&self, session: &Session) -> Session {
+        let mut session = session.clone();
+        for (i, table) in session.tables.iter_mut().enumerate() {
+            if self.hide_cards {
+                hide_unshown(table, session.hero_id);
+            }
+            if self.scrub_tables {
+                table.name = format!("Table {}", i + 1);
+                table.location = String::new();
+            }
+            for player in &mut table.initial_context {
+                self.player(player);
+            }
+            for event in &mut table.events {
+                if let TableEvent::SeatUpdate(update) = event {
+                    if let Some(player) = &mut update.player {
+                        self.player(player);
+                    }
+                }
+            }
+        }
+        session.hero_id = self.id(session.hero_id);
+        if let Some(tournament) = &mut session.tournament {
+            for finish in &mut tournament.finishes {
+                finish.player_id = self.id(finish.player_id);
+            }
+        }
+        for note in &mut session.annotations.players {
+            note.player_id = self.id(note.player_id);
+            if self.scrub_notes {
+                note.note.clear();
+            }
+        }
+        if self.scrub_notes {
+            for annotation in &mut session.annotations.hands {
+                annotation.note.clear();
+            }
+        }
+        session
+    }
+
+    fn player(&self, player: &mut Player) {
+        player.id = self.id(player.id);
+        player.name = self.name(&player.name);
+    }
+
+    fn hash(&self, domain: &[u8], data: &[u8]) -> u64 {
+        sip_hash(self.key, &[domain, b":", data].concat())
+    }
+}
+
+/// The SipHash-2-4 of some bytes under a 128-bit key, a keyed hash that cannot be reversed
+/// or forged without the key.
+fn sip_hash(key: [u64; 2], data: &[u8]) -> u64 {
+    let mut v = [
+        key[0] ^ 0x736f_6d65_7073_6575,
+        key[1] ^ 0x646f_7261_6e64_6f6d,
+        key[0] ^ 0x6c79_6765_6e65_7261,
+        key[1] ^ 0x7465_6462_7974_6573,
+    ];
+    let round = |v: &mut [u64; 4]| {
+        v[0] = v[0].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(13) ^ v[0];
+        v[0] = v[0].rotate_left(32);
+        v[2] = v[2].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(16) ^ v[2];
+        v[0] = v[0].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(21) ^ v[0];
+        v[2] = v[2].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(17) ^ v[2];
+        v[2] = v[2].rotate_left(32);
+    };
+    let mut compress = |m: u64| {
+        v[3] ^= m;
+        round(&mut v);
+        round(&mut v);
+        v[0] ^= m;
+    };
+    let mut chunks = data.chunks_exact(8);
+    for chunk in &mut chunks {
+        compress(u64::from_le_bytes(chunk.try_into().expect("8 bytes")));
+    }
+    let mut last = [0u8; 8];
+    last[..chunks.remainder().len()].copy_from_slice(chunks.remainder());
+    last[7] = data.len() as u8;
+    compress(u64::from_le_bytes(last));
+    v[2] ^= 0xff;
+    for _ in 0..4 {
+        round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Annotations, Label};
+    use crate::types::card::Card;
+
+    fn hand(session: &Session, index: usize) -> &crate::game::Hand {
+        let mut hands = session.tables[0].events.iter().filter_map(|e| match e {
+            TableEvent::Hand(hand) => Some(hand),
+            _ => None,
+        });
+        hands.nth(index).expect("Hand")
+    }
+
+    #[test]
+    fn test_sip_hash() {
+        // The first test vector of the SipHash reference implementation.
+        let key = [0x0706_0504_0302_0100, 0x0f0e_0d0c_0b0a_0908];
+        assert_eq!(sip_hash(key, &[]), 0x726f_db47_dd0e_0e31);
+        let data: Vec<u8> = (0..15).collect();
+        assert_eq!(sip_hash(key, &data), 0xa129_ca61_49be_45e5);
+    }
+
+    #[test]
+    fn test_anonymise() {
+        let mut session = Session::exhaustive();
+        session.hero_id = 1001;
+        session.annotations = Annotations::example();
+        let mut anonymiser = Anonymiser::new("secret");
+        let anonymised = anonymiser.anonymise(&session);
+
+        let table = &anonymised.tables[0];
+        let hero = &table.initial_context[0];
+        assert_eq!(hero.id, anonymiser.id(1001));
+        assert_eq!(anonymised.hero_id, hero.id);
+        assert_eq!(hero.name, anonymiser.name("Player 1001"));
+        assert!(hero.name.starts_with("Player ") && hero.name != "Player 1001");
+        assert_ne!(hero.id, table.initial_context[1].id);
+        let note = &anonymised.annotations.players[0];
+        assert_eq!(note.player_id, anonymiser.id(1002));
+        assert_eq!((note.label, note.note.as_str()), (Some(Label::Red), ""));
+        let annotation = &anonymised.annotations.hands[0];
+        assert_eq!((annotation.tags.len(), annotation.note.as_str()), (2, ""));
+        assert_eq!(table.location, session.tables[0].location);
+        assert_eq!(hand(&anonymised, 0), hand(&session, 0));
+
+        // The same key gives the same pseudonyms, another key different ones.
+        assert_eq!(Anonymiser::new("secret").anonymise(&session), anonymised);
+        assert_ne!(Anonymiser::new("other").id(1001), hero.id);
+
+        anonymiser.scrub_tables = true;
+        anonymiser.hide_cards = true;
+        let scrubbed = anonymiser.anonymise(&session);
+        assert_eq!(scrubbed.tables[0].name, "Table 1");
+        assert_eq!(scrubbed.tables[0].location, "");
+        // The villain folds the first hand, so only the hero's cards stay.
+        assert_eq!(
+            hand(&scrubbed, 0).hole_cards,
+            vec![
+                vec![Card::AceClubs, Card::AceSpades],
+                vec![Card::Unknown, Card::Unknown]
+            ]
+        );
+        // The second hand's aces are shown at showdown.
+        assert_eq!(hand(&scrubbed, 1).hole_cards, hand(&session, 1).hole_cards);
+
+        anonymiser.scrub_notes = false;
+        let kept = anonymiser.anonymise(&session);
+        assert_eq!(kept.annotations.hands, session.annotations.hands);
+        assert_eq!(
+            kept.annotations.players[0].note,
+            session.annotations.players[0].note
+        );
+    }
+}
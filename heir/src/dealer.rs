@@ -0,0 +1,369 @@
+use crate::engine::deck::{Deck, Rng};
+use crate::engine::replay::Replay;
+use crate::formats::acpc::{self, AcpcAction, Game, MatchState};
+use crate::formats::builder::{BuiltHand, SessionBuilder};
+use crate::game::{Id, Session, TableSize, Timestamp};
+use crate::types::card::Card;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SITE: &str = "ACPC";
+
+/// The protocol version bots must announce when they connect.
+const VERSION: &str = "VERSION:2.";
+
+/// A match between bots played over TCP with the ACPC protocol, dealt and checked by heir.
+///
+/// Bots connect, send `VERSION:2.0.0`, and are sent a `MATCHSTATE:` line after every action.
+/// The bot to act replies with that line followed by `:` and its action. Positions rotate
+/// each hand and every hand starts from the [`Game`]'s stacks, as with the ACPC dealer.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Match {
+    /// Names the recorded [`Session`] and its table.
+    pub name: String,
+    pub game: Game,
+    /// The number of hands to play.
+    pub hands: u64,
+    /// The seed the deck is shuffled from, so that a match can be dealt again.
+    pub seed: u64,
+    /// The name of each bot, in the order they connect.
+    pub players: Vec<String>,
+}
+
+/// The hands of a [`Match`], along with the invalid actions that were replaced.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MatchRecord {
+    pub session: Session,
+    pub warnings: Vec<String>,
+}
+
+/// A bot's connection to the dealer.
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Connection {
+    fn accept(listener: &TcpListener) -> io::Result<Self> {
+        let (stream, _) = listener.accept()?;
+        // States are short lines that must not wait to be coalesced.
+        stream.set_nodelay(true)?;
+        let mut connection = Connection {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        };
+        let line = connection.read_line()?;
+        if !line.starts_with(VERSION) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Expected {}x.x, found {:?}.", VERSION, line),
+            ));
+        }
+        Ok(connection)
+    }
+
+    fn send(&mut self, state: &str) -> io::Result<()> {
+        write!(self.writer, "{}\r\n", state)?;
+        self.writer.flush()
+    }
+
+    /// The next line that is not a comment.
+    fn read_line(&mut self) -> io::Result<String> {
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "A bot disconnected.",
+                ));
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            if !line.is_empty() && !line.starts_with(['#', ';']) {
+                return Ok(line.to_string());
+            }
+        }
+    }
+
+    /// The action replied to a state, ignoring replies to any other state.
+    fn receive(&mut self, state: &str) -> io::Result<String> {
+        loop {
+            let line = self.read_line()?;
+            if let Some(action) = line.strip_prefix(state).and_then(|l| l.strip_prefix(':')) {
+                return Ok(action.to_string());
+            }
+        }
+    }
+}
+
+impl Match {
+    /// Accepts a connection from each bot and plays the match.
+    pub fn run(&self, listener: &TcpListener) -> io::Result<MatchRecord> {
+        if self.players.len() != self.game.players() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "The game is for {} players, but {} are named.",
+                    self.game.players(),
+                    self.players.len()
+                ),
+            ));
+        }
+        let mut connections = (0..self.players.len())
+            .map(|_| Connection::accept(listener))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut session = SessionBuilder::new(SITE);
+        let mut rng = Rng::new(self.seed);
+        for hand_number in 0..self.hands {
+            let deck = Deck::shuffled(&mut rng);
+            self.play_hand(&mut session, &mut connections, hand_number, deck)?;
+        }
+        let import = session.finish();
+        Ok(MatchRecord {
+            session: Session {
+                name: self.name.clone(),
+                ..import.session
+            },
+            warnings: import.warnings,
+        })
+    }
+
+    fn play_hand(
+        &self,
+        session: &mut SessionBuilder,
+        connections: &mut [Connection],
+        hand_number: Id,
+        mut deck: Deck,
+    ) -> io::Result<()> {
+        let error = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+        let n = self.players.len();
+        // Bot `i` sits in seat `i` and plays position `(i + hand_number) % n`.
+        let rotation = (hand_number % n as u64) as usize;
+        let positions: Vec<u8> = (0..n).map(|p| ((p + n - rotation) % n) as u8).collect();
+        let mut seats = vec![None; n];
+        for (p, &seat) in positions.iter().enumerate() {
+            let name = &self.players[seat as usize];
+            seats[seat as usize] = Some(session.player(name, self.game.stacks[p]));
+        }
+        let mut deal = || deck.deal().expect("The deck holds enough cards");
+        let hole_cards: Vec<Vec<Card>> = (0..n).map(|_| vec![deal(), deal()]).collect();
+        let board: Vec<Card> = (0..5).map(|_| deal()).collect();
+
+        let timestamp: Timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let mut hand = acpc::build(
+            &self.game,
+            hand_number,
+            timestamp,
+            &[],
+            &hole_cards,
+            &[],
+            seats,
+            &positions,
+        )
+        .map_err(error)?;
+        loop {
+            let replay = hand.replay();
+            let states: Vec<String> = (0..n)
+                .map(|p| view(replay, hand_number, &positions, &hole_cards, &board, p).to_string())
+                .collect();
+            for (p, state) in states.iter().enumerate() {
+                connections[positions[p] as usize].send(state)?;
+            }
+            let Some(seat) = replay.to_act() else {
+                break;
+            };
+            let p = positions.iter().position(|&s| s == seat).unwrap_or(0);
+            let reply = connections[seat as usize].receive(&states[p])?;
+            let action = parse_action(&reply);
+            if let Err(e) = action.and_then(|a| acpc::act(&mut hand, a)) {
+                session.warn(format!(
+                    "Hand {}: {}'s action {:?} is invalid, so they check or call. {}",
+                    hand_number, self.players[seat as usize], reply, e
+                ));
+                acpc::act(&mut hand, AcpcAction::Call).map_err(error)?;
+            }
+        }
+
+        let dealt = hand.replay().street.board_len();
+        hand.board(&board[..dealt]);
+        let table_blinds = self.game.table_blinds()?;
+        let BuiltHand { seats, hand, .. } = hand.finish().map_err(error)?;
+        session.push(&self.name, n as TableSize, &table_blinds, seats, hand);
+        Ok(())
+    }
+}
+
+/// A single action such as "r300", "c" or "f".
+fn parse_action(text: &str) -> Result<AcpcAction, String> {
+    let rounds = acpc::parse_betting(text).map_err(|e| e.to_string())?;
+    match rounds[..] {
+        [ref round] if round.len() == 1 => Ok(round[0]),
+        _ => Err("Expected a single action.".to_string()),
+    }
+}
+
+/// The hand as seen by a position. Hole cards of players still in the hand are revealed
+/// once betting is over.
+fn view(
+    replay: &Replay,
+    hand_number: Id,
+    positions: &[u8],
+    hole_cards: &[Vec<Card>],
+    board: &[Card],
+    position: usize,
+) -> MatchState {
+    let in_hand = |seat: u8| replay.seat(seat).is_some_and(|s| !s.folded);
+    let showdown =
+        replay.is_betting_over() && positions.iter().filter(|&&s| in_hand(s)).count() > 1;
+    let hole_cards = positions
+        .iter()
+        .enumerate()
+        .map(|(p, &seat)| {
+            if p == position || (showdown && in_hand(seat)) {
+                hole_cards[p].clone()
+            } else {
+                Vec::new()
+            }
+        })
+        .collect();
+    MatchState {
+        position: position as u8,
+        hand_number,
+        betting: acpc::replay_betting(replay),
+        hole_cards,
+        board: board[..replay.street.board_len()].to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::state::TableState;
+    use crate::formats::bin;
+    use crate::game::TableEvent;
+    use std::net::SocketAddr;
+    use std::thread::{self, JoinHandle};
+
+    /// Connects a stand-in bot that replies to its turns with `strategy`.
+    fn bot(
+        address: SocketAddr,
+        game: Game,
+        strategy: fn(&MatchState) -> String,
+    ) -> JoinHandle<io::Result<u64>> {
+        thread::spawn(move || {
+            let mut stream = TcpStream::connect(address)?;
+            stream.set_nodelay(true)?;
+            stream.write_all(b"VERSION:2.0.0\r\n")?;
+            let mut turns = 0;
+            for line in BufReader::new(stream.try_clone()?).lines() {
+                let line = line?;
+                let state: MatchState = line.parse()?;
+                if state.to_act(&game)? == Some(state.position) {
+                    write!(stream, "# thinking\r\n{}:{}\r\n", line, strategy(&state))?;
+                    turns += 1;
+                }
+            }
+            Ok(turns)
+        })
+    }
+
+    fn play(game: Game, hands: u64, strategies: &[fn(&MatchState) -> String]) -> MatchRecord {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Bind to localhost");
+        let address = listener.local_addr().expect("Bound address");
+        let bots: Vec<_> = strategies
+            .iter()
+            .map(|&strategy| bot(address, game.clone(), strategy))
+            .collect();
+        let game_match = Match {
+            name: "Test match".to_string(),
+            players: (0..strategies.len())
+                .map(|i| format!("Bot {}", i))
+                .collect(),
+            game,
+            hands,
+            seed: 42,
+        };
+        let record = game_match.run(&listener).expect("The match is played");
+        for bot in bots {
+            let turns = bot.join().expect("The bot does not panic");
+            assert!(turns.expect("The bot plays") > 0);
+        }
+        record
+    }
+
+    fn hands(session: &Session) -> Vec<(TableState, &crate::game::Hand)> {
+        let table = &session.tables[0];
+        let mut state = TableState::new(table);
+        let mut hands = Vec::new();
+        for event in &table.events {
+            if let TableEvent::Hand(hand) = event {
+                hands.push((state.clone(), hand));
+            }
+            state.apply(table, event).expect("Every hand replays");
+        }
+        hands
+    }
+
+    #[test]
+    fn test_heads_up() -> io::Result<()> {
+        let record = play(
+            Game::heads_up_no_limit(),
+            6,
+            &[|_| "r20000".to_string(), |_| "r1".to_string()],
+        );
+        // Bot 1's raises are too small, so they limp and call the all-ins instead.
+        assert_eq!(record.warnings.len(), 9);
+        assert!(record.warnings[0].contains("Bot 1's action \"r1\" is invalid"));
+
+        let session = record.session;
+        assert_eq!(session.name, "Test match");
+        let table = &session.tables[0];
+        assert_eq!(table.blinds, vec![50, 100]);
+        let hands = hands(&session);
+        assert_eq!(hands.len(), 6);
+        for (i, (state, hand)) in hands.iter().enumerate() {
+            assert_eq!(hand.id, i as Id);
+            // Positions rotate, so the button alternates.
+            assert_eq!(hand.button_position as usize, 1 - i % 2);
+            assert_eq!(hand.hole_cards.len(), 2);
+            assert!(hand.board.iter().all(|c| c.is_known()));
+            let replay = Replay::hand(table, &state.seats, hand).expect("The hand replays");
+            assert_eq!(replay.net(0) + replay.net(1), 0);
+        }
+
+        let mut bytes = Vec::new();
+        bin::write_session(&mut bytes, &session)?;
+        assert_eq!(bin::read_session(bytes.as_slice())?, session);
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiway() {
+        let game = Game {
+            stacks: vec![1_000; 3],
+            blinds: vec![5, 10, 0],
+        };
+        let record = play(
+            game,
+            3,
+            &[
+                |_| "c".to_string(),
+                |state| match state.betting.len() {
+                    1 => "r40".to_string(),
+                    _ => "c".to_string(),
+                },
+                |_| "f".to_string(),
+            ],
+        );
+        assert!(record.warnings.is_empty(), "{:?}", record.warnings);
+        let hands = hands(&record.session);
+        let buttons: Vec<u8> = hands.iter().map(|(_, h)| h.button_position).collect();
+        assert_eq!(buttons, vec![2, 1, 0]);
+        for (_, hand) in &hands {
+            assert!(hand.actions.iter().any(|a| a.bet_amount == 40));
+        }
+    }
+}
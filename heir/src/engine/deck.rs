@@ -0,0 +1,120 @@
+use crate::game::Variant;
+use crate::types::card::Card;
+
+/// A small seeded pseudo-random number generator (SplitMix64), so that dealt hands can be
+/// reproduced from their seed.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly distributed number in `0..bound`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "Bound must be positive.");
+        // Reject the top values that would favour small results.
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
+
+/// The known [`Card`]s of a deck, dealt from the top.
+#[derive(Clone, Debug)]
+pub struct Deck {
+    cards: Vec<Card>,
+}
+
+impl Deck {
+    /// A 52 card deck in [`Card::deck`] order.
+    pub fn new() -> Self {
+        Deck::with_variant(Variant::Holdem)
+    }
+
+    /// The cards a [`Variant`] deals, in [`Card::deck`] order.
+    pub fn with_variant(variant: Variant) -> Self {
+        let mut cards: Vec<Card> = Card::deck().filter(|&c| variant.deals(c)).collect();
+        cards.reverse();
+        Deck { cards }
+    }
+
+    /// A 52 card deck shuffled with a Fisher-Yates shuffle.
+    pub fn shuffled(rng: &mut Rng) -> Self {
+        Deck::shuffled_with(Variant::Holdem, rng)
+    }
+
+    /// The cards a [`Variant`] deals, shuffled with a Fisher-Yates shuffle.
+    pub fn shuffled_with(variant: Variant, rng: &mut Rng) -> Self {
+        let mut deck = Deck::with_variant(variant);
+        for i in (1..deck.cards.len()).rev() {
+            let j = rng.below(i as u64 + 1) as usize;
+            deck.cards.swap(i, j);
+        }
+        deck
+    }
+
+    /// Deals the top card, or `None` once the deck is empty.
+    pub fn deal(&mut self) -> Option<Card> {
+        self.cards.pop()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Deck::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::card::Rank;
+
+    #[test]
+    fn test_shuffled() {
+        let mut deck = Deck::new();
+        assert_eq!(deck.deal(), Card::deck().next());
+
+        let mut rng = Rng::new(7);
+        let mut dealt: Vec<Card> = std::iter::from_fn(|| Deck::shuffled(&mut rng).deal())
+            .take(2)
+            .collect();
+        assert_ne!(dealt[0], dealt[1], "Each shuffle differs");
+
+        let mut deck = Deck::shuffled(&mut Rng::new(7));
+        assert_eq!(deck.len(), 52);
+        assert_eq!(deck.deal(), Some(dealt[0]), "Shuffles repeat from a seed");
+        dealt = std::iter::from_fn(|| deck.deal()).collect();
+        dealt.sort_by_key(|c| c.to_u8());
+        assert_eq!(dealt.len(), 51);
+        dealt.dedup();
+        assert_eq!(dealt.len(), 51);
+        assert!(deck.is_empty());
+
+        let mut deck = Deck::shuffled_with(Variant::ShortDeck, &mut Rng::new(7));
+        assert_eq!(deck.len(), 36);
+        assert!(std::iter::from_fn(|| deck.deal()).all(|c| c.rank() >= Some(Rank::Six)));
+    }
+}
@@ -0,0 +1,266 @@
+use crate::engine::deck::Rng;
+use crate::engine::eval::{self, HandRank};
+use crate::engine::replay::{Replay, Street};
+use crate::types::card::Card;
+
+/// Runouts are enumerated when there are at most this many, and sampled otherwise.
+const EXACT_RUNOUTS: u64 = 50_000;
+const SAMPLED_RUNOUTS: usize = 10_000;
+/// Sampling is seeded so that the same hand always gets the same EV.
+const SEED: u64 = 0x6865_6972;
+
+/// A seat's all-in adjusted expected result over a hand, next to its actual result.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SeatEv {
+    pub seat: u8,
+    pub net: i64,
+    /// The seat's result with each pot awarded by its share of the pot's equity when
+    /// betting ended. Equal to `net` unless [`all_in_street`] applies.
+    pub ev: f64,
+}
+
+/// The street on which betting ended with players all in and cards left to come, when every
+/// player at showdown showed their hand.
+pub fn all_in_street(replay: &Replay) -> Option<Street> {
+    let street = replay.steps.last().map_or(Street::Preflop, |s| s.street);
+    let known = replay.showdown.iter().all(|(seat, _)| {
+        replay
+            .seat(*seat)
+            .is_some_and(|s| s.hole_cards.iter().all(|c| c.is_known()))
+    });
+    (replay.showdown.len() > 1 && known && street < replay.street && !replay.pots.is_empty())
+        .then_some(street)
+}
+
+/// Every dealt seat's expected and actual result over a finished hand.
+///
+/// Each pot, after rake, is shared between its eligible seats by their equity over every
+/// runout of the board from the all-in street, or a fixed sample of runouts when there are
+/// too many to enumerate. Fees other than rake count at their actual amount.
+pub fn expected_values(replay: &Replay) -> Vec<SeatEv> {
+    let mut ev: Vec<SeatEv> = replay
+        .seats
+        .iter()
+        .map(|s| SeatEv {
+            seat: s.seat,
+            net: replay.net(s.seat),
+            ev: replay.net(s.seat) as f64,
+        })
+        .collect();
+    let Some(street) = all_in_street(replay) else {
+        return ev;
+    };
+    let board = &replay.board[..street.board_len()];
+    let dead: Vec<Card> = replay
+        .seats
+        .iter()
+        .flat_map(|s| s.hole_cards.iter().copied())
+        .chain(board.iter().copied())
+        .filter(|c| c.is_known())
+        .collect();
+    let deck: Vec<Card> = Card::deck()
+        .filter(|&c| replay.variant.deals(c) && !dead.contains(&c))
+        .collect();
+    let missing = 5 - board.len();
+
+    let mut shares = vec![0.0; replay.seats.len()];
+    let mut runouts = 0;
+    let mut score = |runout: &[Card]| {
+        let ranks: Vec<(u8, Option<HandRank>)> = replay
+            .showdown
+            .iter()
+            .map(|&(seat, _)| {
+                let hole_cards = &replay.seat(seat).expect("Dealt").hole_cards;
+                let mut cards = board.to_vec();
+                cards.extend_from_slice(runout);
+                (
+                    seat,
+                    eval::evaluate_hand(replay.variant, hole_cards, &cards),
+                )
+            })
+            .collect();
+        for pot in &replay.pots {
+            let ranked: Vec<(u8, Option<HandRank>)> = ranks
+                .iter()
+                .filter(|(seat, _)| pot.eligible.contains(seat))
+                .copied()
+                .collect();
+            let winners: Vec<u8> = match ranked.iter().map(|(_, r)| *r).max() {
+                Some(best) if pot.eligible.len() > 1 => ranked
+                    .iter()
+                    .filter(|(_, r)| *r == best)
+                    .map(|(seat, _)| *seat)
+                    .collect(),
+                _ => pot.eligible.clone(),
+            };
+            for seat in &winners {
+                let i = replay.seats.iter().position(|s| s.seat == *seat);
+                shares[i.expect("Dealt")] += pot.amount as f64 / winners.len() as f64;
+            }
+        }
+        runouts += 1;
+    };
+    if combinations(deck.len() as u64, missing as u64) <= EXACT_RUNOUTS {
+        each_combination(&deck, missing, &mut Vec::new(), &mut score);
+    } else {
+        let mut rng = Rng::new(SEED);
+        let mut deck = deck;
+        for _ in 0..SAMPLED_RUNOUTS {
+            // A partial Fisher-Yates shuffle puts a random runout at the front.
+            for i in 0..missing {
+                let j = i + rng.below((deck.len() - i) as u64) as usize;
+                deck.swap(i, j);
+            }
+            score(&deck[..missing]);
+        }
+    }
+
+    for (i, seat) in ev.iter_mut().enumerate() {
+        let collected = replay.collected(seat.seat) as f64;
+        seat.ev += shares[i] / runouts as f64 - collected;
+    }
+    ev
+}
+
+fn combinations(n: u64, k: u64) -> u64 {
+    (0..k).fold(1, |c, i| c * (n - i) / (i + 1))
+}
+
+fn each_combination(cards: &[Card], k: usize, chosen: &mut Vec<Card>, f: &mut impl FnMut(&[Card])) {
+    if chosen.len() == k {
+        f(chosen);
+        return;
+    }
+    let needed = k - chosen.len();
+    for i in 0..=cards.len().saturating_sub(needed) {
+        chosen.push(cards[i]);
+        each_combination(&cards[i + 1..], k, chosen, f);
+        chosen.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Action, ActionType, Hand, Player, Session};
+
+    fn action(action_type: ActionType, bet_amount: u32) -> Action {
+        Action {
+            action_type,
+            bet_amount,
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn test_turn_all_in() {
+        let table = Session::exhaustive().tables.remove(0);
+        let seats: Vec<Option<Player>> = table.initial_context.iter().cloned().map(Some).collect();
+        let hand = Hand {
+            id: 1,
+            button_position: 1,
+            hole_cards: vec![
+                vec![Card::AceClubs, Card::AceSpades],
+                vec![Card::KingClubs, Card::KingSpades],
+            ],
+            actions: vec![
+                action(ActionType::Call, 100),
+                action(ActionType::Check, 0),
+                action(ActionType::Check, 0),
+                action(ActionType::Check, 0),
+                action(ActionType::AllIn, 9900),
+                action(ActionType::Call, 9900),
+            ],
+            timestamp: 0,
+            runouts: vec![],
+            bomb_pot: None,
+            fees: vec![],
+            clock_events: vec![],
+            board: [
+                Card::NineHearts,
+                Card::TenHearts,
+                Card::ThreeDiamonds,
+                Card::KingDiamonds,
+                Card::TwoClubs,
+            ],
+        };
+        let replay = Replay::hand(&table, &seats, &hand).expect("Valid hand");
+        assert_eq!(all_in_street(&replay), Some(Street::Turn));
+
+        // Only the two remaining aces of the 44 unseen cards save the aces.
+        let ev = expected_values(&replay);
+        assert_eq!(ev[0].net, -10_000);
+        assert!((ev[0].ev - (19_997.0 * 2.0 / 44.0 - 10_000.0)).abs() < 1e-6);
+        assert_eq!(ev[1].net, 9_997);
+        assert!((ev[1].ev - (19_997.0 * 42.0 / 44.0 - 10_000.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_side_pots() {
+        let table = Session::exhaustive().tables.remove(0);
+        let stacks = [500, 2000, 20_000];
+        let seats: Vec<Option<Player>> = (0..3)
+            .map(|i| {
+                Some(Player {
+                    id: i,
+                    name: format!("Player {}", i),
+                    stack: stacks[i as usize],
+                })
+            })
+            .collect();
+        // Everyone limps, then the two shorter stacks are all in on the flop.
+        let hand = Hand {
+            id: 1,
+            button_position: 0,
+            hole_cards: vec![
+                vec![Card::AceClubs, Card::AceSpades],
+                vec![Card::KingClubs, Card::KingSpades],
+                vec![Card::SevenDiamonds, Card::TwoHearts],
+            ],
+            actions: vec![
+                action(ActionType::Call, 100),
+                action(ActionType::Call, 100),
+                action(ActionType::Check, 0),
+                action(ActionType::AllIn, 1900),
+                action(ActionType::Call, 1900),
+                action(ActionType::AllIn, 400),
+            ],
+            timestamp: 0,
+            runouts: vec![],
+            bomb_pot: None,
+            fees: vec![],
+            clock_events: vec![],
+            board: [
+                Card::NineHearts,
+                Card::TenHearts,
+                Card::ThreeDiamonds,
+                Card::FourClubs,
+                Card::QueenSpades,
+            ],
+        };
+        let replay = Replay::hand(&table, &seats, &hand).expect("Valid hand");
+        assert_eq!(all_in_street(&replay), Some(Street::Flop));
+        assert_eq!(replay.pots.len(), 2);
+
+        let ev = expected_values(&replay);
+        let total: f64 = ev.iter().map(|s| s.ev).sum();
+        assert!((total + replay.rake as f64).abs() < 1e-6);
+        assert!(ev[0].ev < ev[0].net as f64);
+        assert!(ev[2].ev > ev[2].net as f64);
+    }
+
+    #[test]
+    fn test_no_all_in() {
+        let session = Session::exhaustive();
+        let mut state = crate::engine::state::TableState::new(&session.tables[0]);
+        let table = &session.tables[0];
+        for event in &table.events {
+            if let Some(replay) = state.apply(table, event).expect("Valid event") {
+                assert_eq!(all_in_street(&replay), None);
+                for seat in expected_values(&replay) {
+                    assert_eq!(seat.ev, seat.net as f64);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,372 @@
+use crate::game::Variant;
+use crate::types::card::{Card, Rank};
+use std::fmt;
+
+/// The category of a five card poker hand, ordered from weakest to strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+/// The strength of a five card poker hand. Stronger hands compare greater.
+///
+/// Hands compare by [`Category`], except that a short deck flush beats a full house.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandRank {
+    /// The category's place in the variant's order.
+    strength: u8,
+    category: Category,
+    /// Tie-breaking ranks, most significant first and zero padded. A straight holds its top
+    /// and low ranks.
+    ranks: [u8; 5],
+}
+
+impl HandRank {
+    pub fn category(&self) -> Category {
+        self.category
+    }
+
+    /// The significant ranks of the hand, most significant first (e.g. trips then pair for a full house,
+    /// or top then low card for a straight).
+    pub fn ranks(&self) -> impl Iterator<Item = Rank> + '_ {
+        self.ranks
+            .iter()
+            .take_while(|&&r| r != 0)
+            .map(|&r| Rank::ALL[r as usize - 2])
+    }
+
+    fn rank(&self, index: usize) -> Rank {
+        Rank::ALL[self.ranks[index] as usize - 2]
+    }
+
+    /// The lowest rank of a straight, which is the ace of a wheel.
+    fn straight_low(&self) -> Rank {
+        self.rank(1)
+    }
+}
+
+/// Describes the hand the way hand history sites do (e.g. "a full house, Sevens full of Eights").
+impl fmt::Display for HandRank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.category {
+            Category::HighCard => write!(f, "high card {}", self.rank(0).name()),
+            Category::OnePair => write!(f, "a pair of {}", self.rank(0).plural()),
+            Category::TwoPair => write!(
+                f,
+                "two pair, {} and {}",
+                self.rank(0).plural(),
+                self.rank(1).plural()
+            ),
+            Category::ThreeOfAKind => write!(f, "three of a kind, {}", self.rank(0).plural()),
+            Category::Straight => write!(
+                f,
+                "a straight, {} to {}",
+                self.straight_low().name(),
+                self.rank(0).name()
+            ),
+            Category::Flush => write!(f, "a flush, {} high", self.rank(0).name()),
+            Category::FullHouse => write!(
+                f,
+                "a full house, {} full of {}",
+                self.rank(0).plural(),
+                self.rank(1).plural()
+            ),
+            Category::FourOfAKind => write!(f, "four of a kind, {}", self.rank(0).plural()),
+            Category::StraightFlush if self.rank(0) == Rank::Ace => write!(f, "a Royal Flush"),
+            Category::StraightFlush => write!(
+                f,
+                "a straight flush, {} to {}",
+                self.straight_low().name(),
+                self.rank(0).name()
+            ),
+        }
+    }
+}
+
+/// Evaluates exactly five known [`Card`]s.
+///
+/// # Panic
+/// Panics if any card is [`Card::Unknown`] or [`Card::Xx`].
+pub fn evaluate_five(cards: [Card; 5]) -> HandRank {
+    rank_five(cards, false)
+}
+
+/// Evaluates exactly five known [`Card`]s of a short deck, where a flush beats a full house
+/// and the ace plays low below the six.
+///
+/// # Panic
+/// Panics if any card is [`Card::Unknown`] or [`Card::Xx`].
+pub fn evaluate_five_short(cards: [Card; 5]) -> HandRank {
+    rank_five(cards, true)
+}
+
+fn rank_five(cards: [Card; 5], short: bool) -> HandRank {
+    let mut ranks = cards.map(|c| c.rank().expect("Known card") as u8);
+    ranks.sort_unstable_by(|a, b| b.cmp(a));
+    let flush = cards.iter().all(|c| c.suit() == cards[0].suit());
+    let distinct = ranks.windows(2).all(|w| w[0] != w[1]);
+    let wheel = if short {
+        [14, 9, 8, 7, 6]
+    } else {
+        [14, 5, 4, 3, 2]
+    };
+    let straight = if !distinct {
+        None
+    } else if ranks[0] - ranks[4] == 4 {
+        Some((ranks[0], ranks[4]))
+    } else if ranks == wheel {
+        Some((ranks[1], 14))
+    } else {
+        None
+    };
+    let rank = |category: Category, ranks: [u8; 5]| HandRank {
+        strength: match category {
+            Category::Flush if short => Category::FullHouse as u8,
+            Category::FullHouse if short => Category::Flush as u8,
+            category => category as u8,
+        },
+        category,
+        ranks,
+    };
+
+    if let Some((top, low)) = straight {
+        let category = if flush {
+            Category::StraightFlush
+        } else {
+            Category::Straight
+        };
+        return rank(category, [top, low, 0, 0, 0]);
+    }
+    if flush {
+        return rank(Category::Flush, ranks);
+    }
+
+    // Group equal ranks, largest groups first and higher ranks breaking ties.
+    let mut groups: Vec<(u8, u8)> = Vec::with_capacity(5);
+    for rank in ranks {
+        match groups.iter_mut().find(|(r, _)| *r == rank) {
+            Some((_, count)) => *count += 1,
+            None => groups.push((rank, 1)),
+        }
+    }
+    groups.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(b.0.cmp(&a.0)));
+
+    let category = match (groups[0].1, groups.get(1).map_or(0, |g| g.1)) {
+        (4, _) => Category::FourOfAKind,
+        (3, 2) => Category::FullHouse,
+        (3, _) => Category::ThreeOfAKind,
+        (2, 2) => Category::TwoPair,
+        (2, _) => Category::OnePair,
+        _ => Category::HighCard,
+    };
+    let mut tiebreak = [0u8; 5];
+    for (slot, (rank, _)) in tiebreak.iter_mut().zip(&groups) {
+        *slot = *rank;
+    }
+    rank(category, tiebreak)
+}
+
+/// Evaluates the best five card hand that can be made from the known [`Card`]s given.
+///
+/// Returns `None` when fewer than five known cards are provided.
+pub fn evaluate(cards: &[Card]) -> Option<HandRank> {
+    best_of(cards, evaluate_five)
+}
+
+/// Evaluates the best five card hand that can be made from the known [`Card`]s given, ranked
+/// as by [`evaluate_five_short`].
+///
+/// Returns `None` when fewer than five known cards are provided.
+pub fn evaluate_short(cards: &[Card]) -> Option<HandRank> {
+    best_of(cards, evaluate_five_short)
+}
+
+fn best_of(cards: &[Card], evaluate_five: fn([Card; 5]) -> HandRank) -> Option<HandRank> {
+    let known: Vec<Card> = cards.iter().copied().filter(|c| c.is_known()).collect();
+    let n = known.len();
+    if n < 5 {
+        return None;
+    }
+    let mut best: Option<HandRank> = None;
+    for a in 0..n {
+        for b in a + 1..n {
+            for c in b + 1..n {
+                for d in c + 1..n {
+                    for e in d + 1..n {
+                        let rank =
+                            evaluate_five([known[a], known[b], known[c], known[d], known[e]]);
+                        if best.is_none_or(|best| rank > best) {
+                            best = Some(rank);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Evaluates the best hand a player can make in a [`Variant`] from their hole cards and the
+/// board.
+///
+/// Returns `None` when the known cards cannot make a hand.
+pub fn evaluate_hand(variant: Variant, hole_cards: &[Card], board: &[Card]) -> Option<HandRank> {
+    if variant.is_omaha() {
+        return evaluate_omaha(hole_cards, board);
+    }
+    let cards: Vec<Card> = hole_cards.iter().chain(board).copied().collect();
+    match variant {
+        Variant::ShortDeck => evaluate_short(&cards),
+        _ => evaluate(&cards),
+    }
+}
+
+/// Evaluates the best Omaha hand, made of exactly two known hole cards and three known board
+/// cards.
+pub fn evaluate_omaha(hole_cards: &[Card], board: &[Card]) -> Option<HandRank> {
+    let hole: Vec<Card> = hole_cards
+        .iter()
+        .copied()
+        .filter(|c| c.is_known())
+        .collect();
+    let board: Vec<Card> = board.iter().copied().filter(|c| c.is_known()).collect();
+    let mut best: Option<HandRank> = None;
+    for a in 0..hole.len() {
+        for b in a + 1..hole.len() {
+            for c in 0..board.len() {
+                for d in c + 1..board.len() {
+                    for e in d + 1..board.len() {
+                        let rank = evaluate_five([hole[a], hole[b], board[c], board[d], board[e]]);
+                        if best.is_none_or(|best| rank > best) {
+                            best = Some(rank);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(notation: &str) -> Vec<Card> {
+        notation
+            .split_whitespace()
+            .map(|c| c.parse().expect("Valid card"))
+            .collect()
+    }
+
+    fn rank(notation: &str) -> HandRank {
+        evaluate(&cards(notation)).expect("At least five cards")
+    }
+
+    #[test]
+    fn test_categories() {
+        assert_eq!(rank("Ah Kh Qh Jh Th").category(), Category::StraightFlush);
+        assert_eq!(rank("7h 7s 7c 7d 8h").category(), Category::FourOfAKind);
+        assert_eq!(rank("7h 7s 7c 8d 8h").category(), Category::FullHouse);
+        assert_eq!(rank("2h 7h 9h Jh Kh").category(), Category::Flush);
+        assert_eq!(rank("Ah 2s 3c 4d 5h").category(), Category::Straight);
+        assert_eq!(rank("7h 7s 7c 2d 8h").category(), Category::ThreeOfAKind);
+        assert_eq!(rank("7h 7s 2c 2d 8h").category(), Category::TwoPair);
+        assert_eq!(rank("7h 7s 3c 2d 8h").category(), Category::OnePair);
+        assert_eq!(rank("7h 9s 3c 2d 8h").category(), Category::HighCard);
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(rank("Ah 2s 3c 4d 5h") < rank("2s 3c 4d 5h 6h"));
+        assert!(rank("Ah Ad Kc Kd 2h") > rank("Ah Ad Qc Qd Kh"));
+        assert!(rank("Ah Ad Kc Qd 3h") > rank("Ah Ad Kc Qd 2h"));
+        assert_eq!(rank("Ah Ad Kc Qd 3h"), rank("As Ac Kh Qs 3d"));
+        assert!(rank("2h 3h 4h 5h 7h") > rank("Ah Kd Qc Js Th"));
+    }
+
+    #[test]
+    fn test_best_of_seven() {
+        // Board quads with an ace kicker from the hole.
+        let best = rank("Ac As 7h 7s 7c 7d 8h");
+        assert_eq!(best.category(), Category::FourOfAKind);
+        assert!(best > rank("2c 2s 7h 7s 7c 7d 8h"));
+        assert_eq!(
+            best.ranks().collect::<Vec<_>>(),
+            vec![Rank::Seven, Rank::Ace]
+        );
+        assert!(evaluate(&cards("Ac As 7h 7s")).is_none());
+        assert!(evaluate(&[Card::Unknown; 7]).is_none());
+    }
+
+    #[test]
+    fn test_omaha() {
+        // Four hearts in the hole make no flush without three on the board.
+        let hole = cards("Ah Kh 8h 6h");
+        let board = cards("Th 9c 2d 3s 4h");
+        let omaha = evaluate_omaha(&hole, &board).expect("A hand");
+        assert_eq!(omaha.category(), Category::HighCard);
+        let all: Vec<Card> = hole.iter().chain(&board).copied().collect();
+        assert_eq!(evaluate(&all).map(|r| r.category()), Some(Category::Flush));
+        // Board trips need a pair from the hole for a full house.
+        let board = cards("7h 7s 7c 2d 3c");
+        let full = evaluate_hand(Variant::Omaha, &cards("Ac Ad 9s 8s"), &board);
+        assert_eq!(full.map(|r| r.category()), Some(Category::FullHouse));
+        let trips = evaluate_hand(Variant::Omaha5, &cards("Ac Kd 9s 8s 4h"), &board);
+        assert_eq!(trips.map(|r| r.category()), Some(Category::ThreeOfAKind));
+        assert!(evaluate_omaha(&cards("Ac Kd 9s 8s"), &cards("7h 7s")).is_none());
+    }
+
+    #[test]
+    fn test_short_deck() {
+        let short = |notation: &str| evaluate_short(&cards(notation)).expect("A hand");
+        // A flush beats a full house only in short deck.
+        assert!(rank("Ah Jh 9h 7h 6h") < rank("7c 7d 7s 6c 6d"));
+        assert!(short("Ah Jh 9h 7h 6h") > short("7c 7d 7s 6c 6d"));
+        assert!(short("Ah Jh 9h 7h 6h") < short("7c 7d 7s 7h 6d"));
+        // The ace plays low below the six.
+        let wheel = short("Ac 6d 7h 8s 9c");
+        assert_eq!(wheel.category(), Category::Straight);
+        assert_eq!(wheel.to_string(), "a straight, Ace to Nine");
+        assert!(wheel < short("6d 7h 8s 9c Tc"));
+        assert_eq!(rank("Ac 6d 7h 8s 9c").category(), Category::HighCard);
+        assert_eq!(
+            evaluate_hand(
+                Variant::ShortDeck,
+                &cards("Ac 6d"),
+                &cards("7h 8s 9c Kd Kh")
+            ),
+            Some(wheel)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(rank("Ah Kh Qh Jh Th").to_string(), "a Royal Flush");
+        assert_eq!(
+            rank("Ah 2h 3h 4h 5h").to_string(),
+            "a straight flush, Ace to Five"
+        );
+        assert_eq!(
+            rank("7h 7s 7c 8d 8h").to_string(),
+            "a full house, Sevens full of Eights"
+        );
+        assert_eq!(
+            rank("9h Ts Jc Qd Kh").to_string(),
+            "a straight, Nine to King"
+        );
+        assert_eq!(
+            rank("7h 7s Kc Kd 8h").to_string(),
+            "two pair, Kings and Sevens"
+        );
+        assert_eq!(rank("7h 9s 3c 2d Ah").to_string(), "high card Ace");
+    }
+}
@@ -0,0 +1,272 @@
+use crate::engine::deck::Rng;
+use crate::engine::replay::Replay;
+use crate::game::{Decimal, Id, Player, Tournament};
+use std::collections::HashMap;
+
+/// Exact equities are computed when they take at most this many steps, and sampled otherwise.
+const EXACT_STEPS: u64 = 2_000_000;
+/// The exact algorithm tracks players in a 128 bit set.
+const EXACT_PLAYERS: usize = 128;
+const SAMPLED_ORDERS: usize = 20_000;
+/// Sampling is seeded so that the same stacks always get the same equities.
+const SEED: u64 = 0x6963_6d00;
+
+/// How [`equities_with`] works out the Malmuth-Harville model.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Method {
+    /// Exact when the paid places are few enough for the field, sampled otherwise.
+    Auto,
+    /// Every set of players that can fill the paid places. The work grows with the number of
+    /// players to the power of the paid places, and fields of more than 128 players with
+    /// chips are sampled regardless.
+    Exact,
+    /// A fixed number of finishing orders, sampled in time linear in the field.
+    Approximate { samples: usize },
+}
+
+/// Each player's share of the payouts under the Malmuth-Harville model, where a player
+/// finishes in the highest place left with probability proportional to their stack.
+///
+/// Payouts are by finishing position, first place first. Players without chips get
+/// nothing, and the payouts beyond the players with chips are not paid.
+pub fn equities(stacks: &[Decimal], payouts: &[Decimal]) -> Vec<f64> {
+    equities_with(stacks, payouts, Method::Auto)
+}
+
+/// [`equities`] worked out with the given [`Method`].
+pub fn equities_with(stacks: &[Decimal], payouts: &[Decimal], method: Method) -> Vec<f64> {
+    let live: Vec<usize> = (0..stacks.len()).filter(|&i| stacks[i] > 0).collect();
+    let chips: Vec<f64> = live.iter().map(|&i| stacks[i] as f64).collect();
+    let payouts: Vec<f64> = payouts.iter().take(live.len()).map(|&p| p as f64).collect();
+    let exact = live.len() <= EXACT_PLAYERS
+        && match method {
+            Method::Auto => exact_steps(live.len(), payouts.len()) <= EXACT_STEPS,
+            Method::Exact => true,
+            Method::Approximate { .. } => false,
+        };
+    let live_equities = if exact {
+        exact_equities(&chips, &payouts)
+    } else {
+        let samples = match method {
+            Method::Approximate { samples } => samples.max(1),
+            Method::Auto | Method::Exact => SAMPLED_ORDERS,
+        };
+        sampled_equities(&chips, &payouts, samples)
+    };
+    let mut equities = vec![0.0; stacks.len()];
+    for (i, equity) in live.into_iter().zip(live_equities) {
+        equities[i] = equity;
+    }
+    equities
+}
+
+/// The number of steps [`exact_equities`] takes for `n` players and `places` paid places.
+fn exact_steps(n: usize, places: usize) -> u64 {
+    let mut sets: u64 = 1;
+    let mut total: u64 = 0;
+    for k in 0..places as u64 {
+        total = total.saturating_add(sets.saturating_mul(n as u64));
+        sets = sets.saturating_mul(n as u64 - k) / (k + 1);
+    }
+    total
+}
+
+/// Works through the paid places one at a time, keeping the probability of every set of
+/// players filling the places so far. The order within a set does not matter for the
+/// places left, which keeps the sets far fewer than the finishing orders.
+fn exact_equities(stacks: &[f64], payouts: &[f64]) -> Vec<f64> {
+    let total: f64 = stacks.iter().sum();
+    let mut equities = vec![0.0; stacks.len()];
+    // Each set's probability and the chips its players held.
+    let mut sets: HashMap<u128, (f64, f64)> = HashMap::from([(0, (1.0, 0.0))]);
+    for &prize in payouts {
+        let mut next: HashMap<u128, (f64, f64)> = HashMap::with_capacity(sets.len() * 2);
+        for (&set, &(probability, taken)) in &sets {
+            let left = total - taken;
+            for (i, &stack) in stacks.iter().enumerate() {
+                if set & (1 << i) != 0 {
+                    continue;
+                }
+                let place = probability * stack / left;
+                equities[i] += place * prize;
+                next.entry(set | (1 << i)).or_insert((0.0, taken + stack)).0 += place;
+            }
+        }
+        sets = next;
+    }
+    equities
+}
+
+/// Samples finishing orders by giving each player an exponentially distributed time with a
+/// rate of their stack, and ordering the players by time. The first to finish the race takes
+/// first place with probability proportional to their stack, as in the model, and so on for
+/// every place after.
+fn sampled_equities(stacks: &[f64], payouts: &[f64], samples: usize) -> Vec<f64> {
+    let mut rng = Rng::new(SEED);
+    let mut equities = vec![0.0; stacks.len()];
+    let mut times: Vec<(f64, usize)> = Vec::with_capacity(stacks.len());
+    for _ in 0..samples {
+        times.clear();
+        for (i, &stack) in stacks.iter().enumerate() {
+            // A uniform number in (0, 1], so that its logarithm is finite.
+            let uniform = ((rng.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64;
+            times.push((-uniform.ln() / stack, i));
+        }
+        if payouts.is_empty() {
+            continue;
+        }
+        let places = payouts.len();
+        if places < times.len() {
+            times.select_nth_unstable_by(places - 1, |a, b| a.0.total_cmp(&b.0));
+        }
+        times[..places].sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+        for (&(_, i), prize) in times.iter().zip(payouts) {
+            equities[i] += prize;
+        }
+    }
+    for equity in &mut equities {
+        *equity /= samples as f64;
+    }
+    equities
+}
+
+impl Tournament {
+    /// The payouts the players left compete for, which are the places down to the number of
+    /// players left. Lower places are already paid.
+    pub fn remaining_payouts(&self, players: usize) -> &[Decimal] {
+        &self.payouts[..players.min(self.payouts.len())]
+    }
+}
+
+/// A seat's chip result over a hand with its ICM equity before and after the hand.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct IcmChange {
+    pub seat: u8,
+    pub player_id: Id,
+    pub net: i64,
+    pub before: f64,
+    pub after: f64,
+}
+
+impl IcmChange {
+    /// The equity won or lost over the hand.
+    pub fn change(&self) -> f64 {
+        self.after - self.before
+    }
+}
+
+/// Every dealt seat's ICM equity before and after a tournament hand.
+///
+/// The field is the players seated at the table as the hand was dealt, as
+/// [`TableState`](crate::engine::state::TableState) holds them, along with the stacks of the
+/// players at other tables, and competes for the first `payouts`. Players knocked out in the
+/// hand take the lowest places left, the smaller stack as dealt lower.
+pub fn hand_changes(
+    seats: &[Option<Player>],
+    replay: &Replay,
+    others: &[Decimal],
+    payouts: &[Decimal],
+    method: Method,
+) -> Vec<IcmChange> {
+    let players: Vec<(u8, &Player)> = (0..seats.len() as u8)
+        .filter_map(|s| seats[s as usize].as_ref().map(|p| (s, p)))
+        .collect();
+    let mut before: Vec<Decimal> = players.iter().map(|(_, p)| p.stack).collect();
+    let mut after: Vec<Decimal> = players
+        .iter()
+        .map(|&(seat, player)| replay.seat(seat).map_or(player.stack, |s| s.stack))
+        .collect();
+    before.extend_from_slice(others);
+    after.extend_from_slice(others);
+    let field = before.iter().filter(|&&s| s > 0).count();
+    let payouts = &payouts[..field.min(payouts.len())];
+    let equity_before = equities_with(&before, payouts, method);
+    let mut equity_after = equities_with(&after, payouts, method);
+
+    // Knocked out players, best first.
+    let mut out: Vec<usize> = (0..players.len())
+        .filter(|&i| before[i] > 0 && after[i] == 0)
+        .collect();
+    out.sort_by_key(|&i| std::cmp::Reverse(before[i]));
+    let left = field - out.len();
+    for (place, i) in out.into_iter().enumerate() {
+        equity_after[i] = payouts.get(left + place).copied().unwrap_or(0) as f64;
+    }
+
+    players
+        .iter()
+        .enumerate()
+        .filter(|(_, (seat, _))| replay.seat(*seat).is_some())
+        .map(|(i, &(seat, player))| IcmChange {
+            seat,
+            player_id: player.id,
+            net: replay.net(seat),
+            before: equity_before[i],
+            after: equity_after[i],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::state::Tracker;
+    use crate::game::Session;
+
+    fn assert_close(left: &[f64], right: &[f64], tolerance: f64) {
+        assert_eq!(left.len(), right.len());
+        for (l, r) in left.iter().zip(right) {
+            assert!((l - r).abs() < tolerance, "{:?} != {:?}", left, right);
+        }
+    }
+
+    #[test]
+    fn test_equities() {
+        // First place goes by stack, and second by stack among the rest.
+        let second = 0.3 * 50.0 / 70.0 + 0.2 * 50.0 / 80.0;
+        let exact = equities(&[50, 30, 20, 0], &[70, 30]);
+        assert_close(&exact[..1], &[0.5 * 70.0 + second * 30.0], 1e-9);
+        assert_eq!(exact[3], 0.0);
+        assert!((exact.iter().sum::<f64>() - 100.0).abs() < 1e-9);
+
+        let approximate = equities_with(
+            &[50, 30, 20, 0],
+            &[70, 30],
+            Method::Approximate { samples: 50_000 },
+        );
+        assert_close(&approximate, &exact, 0.5);
+
+        assert_close(&equities(&[100, 100], &[60, 40]), &[50.0, 50.0], 1e-9);
+        assert_close(&equities(&[100], &[60, 40]), &[60.0], 1e-9);
+    }
+
+    #[test]
+    fn test_large_field() {
+        let stacks: Vec<Decimal> = (1..=200).map(|i| 1000 + i * 10).collect();
+        let payouts: Vec<Decimal> = (0..30).map(|i| 3000 - i * 90).collect();
+        let equities = equities(&stacks, &payouts);
+        let total: f64 = equities.iter().sum();
+        assert!((total - payouts.iter().sum::<Decimal>() as f64).abs() < 1e-6);
+        assert!(equities[199] > equities[0]);
+    }
+
+    #[test]
+    fn test_hand_changes() {
+        let session = Session::sit_and_go();
+        let tournament = session.tournament.as_ref().expect("Tournament");
+        let tracked = Tracker::new(&session.tables[0])
+            .nth(1)
+            .expect("Second hand");
+        let replay = tracked.replay.expect("Valid hand");
+        let payouts = tournament.remaining_payouts(3);
+        let changes = hand_changes(&tracked.seats, &replay, &[], payouts, Method::Exact);
+
+        let nets: Vec<i64> = changes.iter().map(|c| c.net).collect();
+        assert_eq!(nets, [2970, -1490, -1480]);
+        let after: Vec<f64> = changes.iter().map(|c| c.after).collect();
+        assert_eq!(after, [2000.0, 1000.0, 0.0]);
+        let before: f64 = changes.iter().map(|c| c.before).sum();
+        assert!((before - 3000.0).abs() < 1e-9);
+        assert!(changes[0].change() > 0.0 && changes[2].change() < 0.0);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod deck;
+pub mod ev;
+pub mod eval;
+pub mod icm;
+pub mod position;
+pub mod replay;
+pub mod state;
@@ -0,0 +1,235 @@
+use crate::engine::replay::Replay;
+use crate::game::{ButtonPosition, Player};
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+/// A named position at the table, relative to the button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Position {
+    SmallBlind,
+    BigBlind,
+    UnderTheGun,
+    UnderTheGun1,
+    UnderTheGun2,
+    Middle,
+    Lojack,
+    Hijack,
+    Cutoff,
+    Button,
+}
+
+impl Position {
+    const NAMES: [&'static str; 10] = [
+        "SB", "BB", "UTG", "UTG+1", "UTG+2", "MP", "LJ", "HJ", "CO", "BTN",
+    ];
+
+    /// The positions of `n` players from the first seat left of the button round to the
+    /// button, for 2 to 10 players.
+    pub fn order(n: usize) -> Option<&'static [Position]> {
+        use Position::*;
+        Some(match n {
+            2 => &[BigBlind, Button],
+            3 => &[SmallBlind, BigBlind, Button],
+            4 => &[SmallBlind, BigBlind, Cutoff, Button],
+            5 => &[SmallBlind, BigBlind, Hijack, Cutoff, Button],
+            6 => &[SmallBlind, BigBlind, UnderTheGun, Hijack, Cutoff, Button],
+            7 => &[
+                SmallBlind,
+                BigBlind,
+                UnderTheGun,
+                Middle,
+                Hijack,
+                Cutoff,
+                Button,
+            ],
+            8 => &[
+                SmallBlind,
+                BigBlind,
+                UnderTheGun,
+                UnderTheGun1,
+                Middle,
+                Hijack,
+                Cutoff,
+                Button,
+            ],
+            9 => &[
+                SmallBlind,
+                BigBlind,
+                UnderTheGun,
+                UnderTheGun1,
+                Middle,
+                Lojack,
+                Hijack,
+                Cutoff,
+                Button,
+            ],
+            10 => &[
+                SmallBlind,
+                BigBlind,
+                UnderTheGun,
+                UnderTheGun1,
+                UnderTheGun2,
+                Middle,
+                Lojack,
+                Hijack,
+                Cutoff,
+                Button,
+            ],
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(Position::NAMES[*self as usize])
+    }
+}
+
+impl FromStr for Position {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Position::NAMES
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(s))
+            .map(|i| Position::order(10).expect("Ten players")[i])
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid position"))
+    }
+}
+
+/// The [`Position`] of every occupied seat for one hand, along with the order of play.
+///
+/// Empty seats are skipped. When the button is on an empty seat (a dead button), the
+/// occupied seat before it acts last and is named the button, matching how
+/// [`Replay`] posts blinds. Heads-up, the button posts the small blind, acts first
+/// preflop and last after the flop.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Positions {
+    /// Seats from the first left of the button round to the button.
+    seats: Vec<(u8, Position)>,
+}
+
+impl Positions {
+    /// Positions for occupied seats, given in any order, or `None` for fewer than 2 or
+    /// more than 10 players.
+    pub fn new(occupied: &[u8], button: ButtonPosition) -> Option<Self> {
+        let names = Position::order(occupied.len())?;
+        let mut seats = occupied.to_vec();
+        seats.sort_unstable();
+        seats.dedup();
+        if seats.len() != occupied.len() {
+            return None;
+        }
+        let first = seats.iter().position(|&s| s > button).unwrap_or(0);
+        seats.rotate_left(first);
+        Some(Positions {
+            seats: seats.into_iter().zip(names.iter().copied()).collect(),
+        })
+    }
+
+    /// Positions for the players seated at a table.
+    pub fn from_seats(seats: &[Option<Player>], button: ButtonPosition) -> Option<Self> {
+        let occupied: Vec<u8> = (0..seats.len() as u8)
+            .filter(|&s| seats[s as usize].is_some())
+            .collect();
+        Positions::new(&occupied, button)
+    }
+
+    /// Positions for the seats dealt into a replayed hand.
+    pub fn from_replay(replay: &Replay) -> Option<Self> {
+        let occupied: Vec<u8> = replay.seats.iter().map(|s| s.seat).collect();
+        Positions::new(&occupied, replay.button)
+    }
+
+    pub fn position(&self, seat: u8) -> Option<Position> {
+        self.seats
+            .iter()
+            .find(|(s, _)| *s == seat)
+            .map(|(_, position)| *position)
+    }
+
+    pub fn seat(&self, position: Position) -> Option<u8> {
+        self.seats
+            .iter()
+            .find(|(_, p)| *p == position)
+            .map(|(seat, _)| *seat)
+    }
+
+    /// The seat holding the button, which is also the small blind heads-up.
+    pub fn button(&self) -> u8 {
+        self.seats[self.seats.len() - 1].0
+    }
+
+    /// Seats in preflop order, starting left of the big blind and ending with it.
+    pub fn preflop_order(&self) -> Vec<u8> {
+        let big_blind = self
+            .seats
+            .iter()
+            .position(|(_, p)| *p == Position::BigBlind);
+        let mut order: Vec<u8> = self.seats.iter().map(|(seat, _)| *seat).collect();
+        order.rotate_left(big_blind.expect("There is always a big blind") + 1);
+        order
+    }
+
+    /// Seats in order after the flop, starting left of the button and ending with it.
+    pub fn postflop_order(&self) -> Vec<u8> {
+        self.seats.iter().map(|(seat, _)| *seat).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Session;
+    use Position::*;
+
+    #[test]
+    fn test_positions() {
+        let six = Positions::new(&[0, 1, 2, 3, 4, 5], 2).expect("Six players");
+        assert_eq!(six.position(3), Some(SmallBlind));
+        assert_eq!(six.position(5), Some(UnderTheGun));
+        assert_eq!(six.seat(Cutoff), Some(1));
+        assert_eq!(six.button(), 2);
+        assert_eq!(six.preflop_order(), [5, 0, 1, 2, 3, 4]);
+        assert_eq!(six.postflop_order(), [3, 4, 5, 0, 1, 2]);
+
+        // Seats 3 and 6 are empty and the button is on seat 6.
+        let dead = Positions::new(&[8, 0, 1, 2, 4, 5, 7], 6).expect("Seven players");
+        assert_eq!(dead.position(7), Some(SmallBlind));
+        assert_eq!(dead.position(8), Some(BigBlind));
+        assert_eq!(dead.position(5), Some(Button));
+        assert_eq!(dead.position(3), None);
+
+        let heads_up = Positions::new(&[3, 7], 7).expect("Two players");
+        assert_eq!(heads_up.position(7), Some(Button));
+        assert_eq!(heads_up.position(3), Some(BigBlind));
+        assert_eq!(heads_up.preflop_order(), [7, 3]);
+        assert_eq!(heads_up.postflop_order(), [3, 7]);
+
+        assert!(Positions::new(&[1], 1).is_none());
+        assert!(Positions::new(&[1, 1], 1).is_none());
+        assert!(Positions::new(&(0..11).collect::<Vec<_>>(), 0).is_none());
+        for n in 2..=10 {
+            assert_eq!(Position::order(n).map(<[_]>::len), Some(n));
+        }
+        assert_eq!("utg+1".parse::<Position>().ok(), Some(UnderTheGun1));
+        assert_eq!(Hijack.to_string(), "HJ");
+    }
+
+    #[test]
+    fn test_replay_order() {
+        let session = Session::exhaustive();
+        let table = &session.tables[0];
+        let mut state = crate::engine::state::TableState::new(table);
+        for event in &table.events {
+            let Some(replay) = state.apply(table, event).expect("Valid event") else {
+                continue;
+            };
+            let positions = Positions::from_replay(&replay).expect("Heads-up");
+            assert_eq!(replay.blinds[0].seat, positions.button());
+            assert_eq!(replay.steps[0].seat, positions.preflop_order()[0]);
+        }
+    }
+}
@@ -0,0 +1,1576 @@
+use crate::engine::eval::{self, HandRank};
+use crate::game::{
+    Action, ActionType, BettingStructure, Board, ClockEvent, ClockEventType, Decimal, Fee, FeeType,
+    Hand, Player, Table, Variant,
+};
+use crate::types::card::Card;
+use std::fmt;
+
+/// The bets a fixed-limit street allows: a bet and three raises.
+const FIXED_LIMIT_BETS: usize = 4;
+
+/// A betting round of a [`Hand`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    /// The number of board cards dealt by the time this street's betting starts.
+    pub fn board_len(self) -> usize {
+        match self {
+            Street::Preflop => 0,
+            Street::Flop => 3,
+            Street::Turn => 4,
+            Street::River => 5,
+        }
+    }
+
+    /// The street dealt after this one, if any.
+    pub fn next(self) -> Option<Street> {
+        match self {
+            Street::Preflop => Some(Street::Flop),
+            Street::Flop => Some(Street::Turn),
+            Street::Turn => Some(Street::River),
+            Street::River => None,
+        }
+    }
+}
+
+impl fmt::Display for Street {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Street::Preflop => write!(f, "Preflop"),
+            Street::Flop => write!(f, "Flop"),
+            Street::Turn => write!(f, "Turn"),
+            Street::River => write!(f, "River"),
+        }
+    }
+}
+
+/// The state of a seat dealt into a [`Hand`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct SeatState {
+    pub seat: u8,
+    /// The player as they sat down to the hand, with their starting stack.
+    pub player: Player,
+    /// As many cards as the [`Variant`] deals.
+    pub hole_cards: Vec<Card>,
+    /// Chips behind. After [`Replay::finish`] this is the stack the player leaves the hand with.
+    pub stack: Decimal,
+    /// Chips put into the pot over the whole hand, less any uncalled bet.
+    pub committed: Decimal,
+    /// Chips put into the pot on the current street.
+    pub street_committed: Decimal,
+    pub folded: bool,
+    pub all_in: bool,
+    acted: bool,
+}
+
+impl SeatState {
+    fn needs_to_act(&self, current_bet: Decimal) -> bool {
+        !self.folded && !self.all_in && (!self.acted || self.street_committed < current_bet)
+    }
+
+    fn put(&mut self, to: Decimal) -> Decimal {
+        let amount = to - self.street_committed;
+        self.stack -= amount;
+        self.committed += amount;
+        self.street_committed = to;
+        self.all_in = self.stack == 0;
+        amount
+    }
+}
+
+/// The forced bets of a hand: blinds from the small blind on, and an ante from every seat.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Stakes {
+    pub blinds: Vec<Decimal>,
+    pub ante: Decimal,
+}
+
+impl Stakes {
+    /// The [`Table`]'s blinds, without an ante.
+    pub fn table(table: &Table) -> Self {
+        Stakes {
+            blinds: table.blinds.clone(),
+            ante: 0,
+        }
+    }
+
+    /// The big blind, or the only blind when there is one.
+    pub fn big_blind(&self) -> Decimal {
+        self.blinds
+            .get(1)
+            .or(self.blinds.first())
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// A forced bet posted before the cards are dealt.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Blind {
+    pub seat: u8,
+    pub amount: Decimal,
+}
+
+/// An [`Action`] resolved to the seat that took it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Step {
+    pub street: Street,
+    pub seat: u8,
+    pub action: Action,
+    /// Chips added to the pot by this action.
+    pub amount: Decimal,
+    /// The seat's total contribution on the street after this action.
+    pub to: Decimal,
+    /// The amount by which this action raised the current bet.
+    pub raise: Decimal,
+    pub all_in: bool,
+}
+
+/// A main or side pot along with who won it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Pot {
+    /// The pot size after rake.
+    pub amount: Decimal,
+    pub eligible: Vec<u8>,
+    /// The seats awarded the pot and their share over every board. Empty when no eligible
+    /// hand is known.
+    pub winners: Vec<(u8, Decimal)>,
+    /// The seats awarded each board's share of the pot, the first board first.
+    pub boards: Vec<Vec<(u8, Decimal)>>,
+}
+
+/// A replay of a [`Hand`] that resolves who took each [`Action`], the pots and their winners.
+///
+/// Actions carry no seat, so this follows standard no-limit betting order to attribute them:
+/// blinds are posted left of the button (by the button itself heads-up) and each
+/// `bet_amount` is the acting seat's total contribution on the street after acting.
+/// Players reaching showdown without known hole cards are treated as having mucked.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Replay {
+    pub button: u8,
+    pub big_blind: Decimal,
+    pub betting_structure: BettingStructure,
+    pub variant: Variant,
+    /// Seats dealt into the hand, in seat order.
+    pub seats: Vec<SeatState>,
+    /// Antes, which count towards the pot but not towards any street's bets.
+    pub antes: Vec<Blind>,
+    pub blinds: Vec<Blind>,
+    pub steps: Vec<Step>,
+    /// Whether the antes are a bomb pot, with no blinds and no betting before the flop.
+    pub bomb_pot: bool,
+    /// The last street dealt.
+    pub street: Street,
+    pub board: Board,
+    /// Further boards the hand was run on, populated by [`Replay::finish_with`].
+    pub runouts: Vec<Board>,
+    pub uncalled: Option<(u8, Decimal)>,
+    /// The main pot followed by any side pots, populated by [`Replay::finish`].
+    pub pots: Vec<Pot>,
+    pub rake: Decimal,
+    /// Hand strengths of the seats that reached showdown on the first board, `None` for
+    /// unknown hands.
+    pub showdown: Vec<(u8, Option<HandRank>)>,
+    rake_percentage: u8,
+    rake_cap: Decimal,
+    current_bet: Decimal,
+    last_raise: Decimal,
+    to_act: Option<usize>,
+    finished: bool,
+}
+
+impl Replay {
+    /// Deals a hand at the given seats and posts the [`Table`]'s blinds.
+    pub fn start(
+        table: &Table,
+        seats: &[Option<Player>],
+        button: u8,
+        hole_cards: &[Vec<Card>],
+    ) -> Result<Self, ReplayError> {
+        Replay::start_with(table, &Stakes::table(table), seats, button, hole_cards)
+    }
+
+    /// Deals a hand at the given seats and posts the antes and blinds of the given stakes.
+    pub fn start_with(
+        table: &Table,
+        stakes: &Stakes,
+        seats: &[Option<Player>],
+        button: u8,
+        hole_cards: &[Vec<Card>],
+    ) -> Result<Self, ReplayError> {
+        let dealt: Vec<(u8, &Player)> = seats
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| p.as_ref().map(|p| (i as u8, p)))
+            .filter(|(_, p)| p.stack > 0)
+            .collect();
+        if dealt.len() < 2 {
+            return Err(ReplayError::NotEnoughPlayers);
+        }
+        if hole_cards.len() > dealt.len() {
+            return Err(ReplayError::TooManyHoleCards {
+                dealt: dealt.len(),
+                found: hole_cards.len(),
+            });
+        }
+        let count = table.variant.hole_cards();
+        if let Some(i) = hole_cards.iter().position(|cards| cards.len() != count) {
+            return Err(ReplayError::HoleCardCount {
+                seat: dealt[i].0,
+                expected: count,
+                found: hole_cards[i].len(),
+            });
+        }
+        let seats = dealt
+            .into_iter()
+            .enumerate()
+            .map(|(i, (seat, player))| SeatState {
+                seat,
+                player: player.clone(),
+                hole_cards: hole_cards
+                    .get(i)
+                    .cloned()
+                    .unwrap_or_else(|| vec![Card::Unknown; count]),
+                stack: player.stack,
+                committed: 0,
+                street_committed: 0,
+                folded: false,
+                all_in: false,
+                acted: false,
+            })
+            .collect();
+        let big_blind = stakes.big_blind();
+        let mut replay = Replay {
+            button,
+            big_blind,
+            betting_structure: table.betting_structure,
+            variant: table.variant,
+            seats,
+            antes: Vec::new(),
+            blinds: Vec::new(),
+            steps: Vec::new(),
+            bomb_pot: false,
+            street: Street::Preflop,
+            board: [Card::Xx; 5],
+            runouts: Vec::new(),
+            uncalled: None,
+            pots: Vec::new(),
+            rake: 0,
+            showdown: Vec::new(),
+            rake_percentage: table.rake_percentage,
+            rake_cap: table.rake_cap,
+            current_bet: 0,
+            last_raise: big_blind.max(1),
+            to_act: None,
+            finished: false,
+        };
+
+        let n = replay.seats.len();
+        let button_index = replay.button_index();
+        let small_blind = if n == 2 && replay.seats[button_index].seat == button {
+            button_index
+        } else {
+            (button_index + 1) % n
+        };
+        if stakes.ante > 0 {
+            for seat in &mut replay.seats {
+                let amount = stakes.ante.min(seat.stack);
+                seat.stack -= amount;
+                seat.committed += amount;
+                seat.all_in = seat.stack == 0;
+                replay.antes.push(Blind {
+                    seat: seat.seat,
+                    amount,
+                });
+            }
+        }
+        let mut last = button_index;
+        for (k, &blind) in stakes.blinds.iter().take(n).enumerate() {
+            let i = (small_blind + k) % n;
+            let seat = &mut replay.seats[i];
+            let to = blind.min(seat.stack);
+            seat.put(to);
+            replay.current_bet = replay.current_bet.max(to);
+            replay.blinds.push(Blind {
+                seat: seat.seat,
+                amount: to,
+            });
+            last = i;
+        }
+        replay.settle(last);
+        Ok(replay)
+    }
+
+    /// Deals a bomb pot at the given stakes: every seat posts `amount` in place of the antes
+    /// and blinds, and the betting starts on the flop. The big blind still sizes the bets.
+    pub fn start_bomb_pot(
+        table: &Table,
+        stakes: &Stakes,
+        seats: &[Option<Player>],
+        button: u8,
+        hole_cards: &[Vec<Card>],
+        amount: Decimal,
+    ) -> Result<Self, ReplayError> {
+        let posts = Stakes {
+            blinds: Vec::new(),
+            ante: amount,
+        };
+        let mut replay = Replay::start_with(table, &posts, seats, button, hole_cards)?;
+        replay.bomb_pot = true;
+        replay.big_blind = stakes.big_blind();
+        replay.last_raise = replay.big_blind.max(1);
+        for seat in &mut replay.seats {
+            seat.acted = true;
+        }
+        replay.settle(replay.button_index());
+        Ok(replay)
+    }
+
+    /// Replays a complete [`Hand`] dealt to the given seats at the [`Table`]'s blinds.
+    pub fn hand(table: &Table, seats: &[Option<Player>], hand: &Hand) -> Result<Self, ReplayError> {
+        Replay::hand_with(table, &Stakes::table(table), seats, hand)
+    }
+
+    /// Replays a complete [`Hand`] dealt to the given seats at the given stakes.
+    pub fn hand_with(
+        table: &Table,
+        stakes: &Stakes,
+        seats: &[Option<Player>],
+        hand: &Hand,
+    ) -> Result<Self, ReplayError> {
+        let mut replay = match hand.bomb_pot {
+            Some(amount) => Replay::start_bomb_pot(
+                table,
+                stakes,
+                seats,
+                hand.button_position,
+                &hand.hole_cards,
+                amount,
+            )?,
+            None => {
+                Replay::start_with(table, stakes, seats, hand.button_position, &hand.hole_cards)?
+            }
+        };
+        let mut previous = None;
+        for (index, action) in hand.actions.iter().enumerate() {
+            if action.timestamp.is_some() && action.timestamp < previous {
+                return Err(ReplayError::ActionTime { action: index });
+            }
+            previous = action.timestamp.or(previous);
+            replay.apply(action)?;
+        }
+        replay.check_clock_events(&hand.clock_events)?;
+        replay.finish_with(&hand.board, &hand.runouts, &hand.fees)?;
+        Ok(replay)
+    }
+
+    /// Checks that each clock event is for a dealt seat before an action of the hand, and that
+    /// time banks and timeouts belong to the seat whose action they come before.
+    fn check_clock_events(&self, events: &[ClockEvent]) -> Result<(), ReplayError> {
+        for (index, event) in events.iter().enumerate() {
+            let acting = match event.event_type {
+                ClockEventType::Disconnect => true,
+                ClockEventType::TimeBank | ClockEventType::Timeout => self
+                    .steps
+                    .get(event.action)
+                    .is_none_or(|step| step.seat == event.seat),
+            };
+            if self.seat(event.seat).is_none() || event.action > self.steps.len() || !acting {
+                return Err(ReplayError::ClockEvent { index });
+            }
+        }
+        Ok(())
+    }
+
+    /// The seat next to act, or `None` once betting is over.
+    pub fn to_act(&self) -> Option<u8> {
+        self.to_act.map(|i| self.seats[i].seat)
+    }
+
+    /// Whether no further actions can be taken.
+    pub fn is_betting_over(&self) -> bool {
+        self.finished
+    }
+
+    /// The largest street contribution so far.
+    pub fn current_bet(&self) -> Decimal {
+        self.current_bet
+    }
+
+    /// The smallest total a bet or raise may be made to (unless all-in).
+    pub fn min_raise_to(&self) -> Decimal {
+        if self.betting_structure == BettingStructure::FixedLimit {
+            self.current_bet + self.bet_size()
+        } else if self.current_bet == 0 {
+            self.big_blind.max(1)
+        } else {
+            self.current_bet + self.last_raise
+        }
+    }
+
+    /// The largest total the seat to act may bet or raise to, which is never more than its
+    /// stack and is the current bet when it may not raise at all.
+    pub fn max_raise_to(&self) -> Decimal {
+        let Some(i) = self.to_act else {
+            return self.current_bet;
+        };
+        let seat = &self.seats[i];
+        let available = seat.street_committed + seat.stack;
+        let maximum = match self.betting_structure {
+            BettingStructure::NoLimit => available,
+            // Calling first, then raising by the pot that makes.
+            BettingStructure::PotLimit => {
+                let to_call = self.current_bet.saturating_sub(seat.street_committed);
+                self.current_bet + self.total_pot() + to_call
+            }
+            BettingStructure::FixedLimit if self.bets() >= FIXED_LIMIT_BETS => self.current_bet,
+            BettingStructure::FixedLimit => self.current_bet + self.bet_size(),
+        };
+        maximum.min(available)
+    }
+
+    /// The size of a fixed-limit bet or raise on the current street: the big blind preflop
+    /// and on the flop, and twice that on the turn and river.
+    pub fn bet_size(&self) -> Decimal {
+        match self.street {
+            Street::Preflop | Street::Flop => self.big_blind.max(1),
+            Street::Turn | Street::River => 2 * self.big_blind.max(1),
+        }
+    }
+
+    /// The bets and raises made on the current street, counting the big blind as the first
+    /// bet preflop.
+    pub fn bets(&self) -> usize {
+        let raises = self
+            .steps
+            .iter()
+            .filter(|s| s.street == self.street && s.raise > 0)
+            .count();
+        raises + (self.street == Street::Preflop && !self.blinds.is_empty()) as usize
+    }
+
+    /// The state of a dealt seat.
+    pub fn seat(&self, seat: u8) -> Option<&SeatState> {
+        self.seats.iter().find(|s| s.seat == seat)
+    }
+
+    /// Total chips in the pot before rake, excluding any uncalled bet.
+    pub fn total_pot(&self) -> Decimal {
+        self.seats.iter().map(|s| s.committed).sum()
+    }
+
+    /// Chips awarded to a seat across all pots.
+    pub fn collected(&self, seat: u8) -> Decimal {
+        self.pots
+            .iter()
+            .flat_map(|p| p.winners.iter())
+            .filter(|(s, _)| *s == seat)
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+
+    /// A seat's profit or loss over the hand.
+    pub fn net(&self, seat: u8) -> i64 {
+        self.seat(seat)
+            .map_or(0, |s| s.stack as i64 - s.player.stack as i64)
+    }
+
+    /// The known board cards dealt by the last street reached.
+    pub fn dealt_board(&self) -> &[Card] {
+        &self.board[..self.street.board_len()]
+    }
+
+    /// Applies the next [`Action`] in the hand to the seat whose turn it is.
+    pub fn apply(&mut self, action: &Action) -> Result<&Step, ReplayError> {
+        let index = self.steps.len();
+        let i = self
+            .to_act
+            .ok_or(ReplayError::HandFinished { action: index })?;
+        let current_bet = self.current_bet;
+        let last_raise = self.last_raise;
+        let min_raise_to = self.min_raise_to();
+        let max_raise_to = self.max_raise_to();
+        let capped = self.betting_structure == BettingStructure::FixedLimit
+            && self.bets() >= FIXED_LIMIT_BETS;
+        let seat = &mut self.seats[i];
+        let available = seat.street_committed + seat.stack;
+        let to = action.bet_amount as Decimal;
+        let number = seat.seat;
+
+        let (amount, raise) = match action.action_type {
+            ActionType::Fold => {
+                seat.folded = true;
+                (0, 0)
+            }
+            ActionType::Check => {
+                if seat.street_committed != current_bet {
+                    return Err(ReplayError::CannotCheck {
+                        action: index,
+                        seat: number,
+                    });
+                }
+                (0, 0)
+            }
+            ActionType::Call => {
+                if current_bet <= seat.street_committed {
+                    return Err(ReplayError::CannotCall {
+                        action: index,
+                        seat: number,
+                    });
+                }
+                let expected = current_bet.min(available);
+                if to != expected {
+                    return Err(ReplayError::AmountMismatch {
+                        action: index,
+                        seat: number,
+                        expected,
+                        found: to,
+                    });
+                }
+                (seat.put(to), 0)
+            }
+            ActionType::Bet | ActionType::Raise => {
+                let is_bet = action.action_type == ActionType::Bet;
+                if is_bet && current_bet != 0 {
+                    return Err(ReplayError::CannotBet {
+                        action: index,
+                        seat: number,
+                    });
+                }
+                if !is_bet && current_bet == 0 {
+                    return Err(ReplayError::CannotRaise {
+                        action: index,
+                        seat: number,
+                    });
+                }
+                if to > available {
+                    return Err(ReplayError::ExceedsStack {
+                        action: index,
+                        seat: number,
+                        available,
+                        found: to,
+                    });
+                }
+                if capped {
+                    return Err(ReplayError::BettingCapped {
+                        action: index,
+                        seat: number,
+                    });
+                }
+                if to <= current_bet || (to < min_raise_to && to != available) {
+                    return Err(ReplayError::BelowMinimum {
+                        action: index,
+                        seat: number,
+                        minimum: min_raise_to.min(available),
+                        found: to,
+                    });
+                }
+                if to > max_raise_to {
+                    return Err(ReplayError::AboveMaximum {
+                        action: index,
+                        seat: number,
+                        maximum: max_raise_to,
+                        found: to,
+                    });
+                }
+                (seat.put(to), to - current_bet)
+            }
+            ActionType::AllIn => {
+                if to != available {
+                    return Err(ReplayError::AmountMismatch {
+                        action: index,
+                        seat: number,
+                        expected: available,
+                        found: to,
+                    });
+                }
+                if to > current_bet && capped {
+                    return Err(ReplayError::BettingCapped {
+                        action: index,
+                        seat: number,
+                    });
+                }
+                if to > current_bet && to > max_raise_to {
+                    return Err(ReplayError::AboveMaximum {
+                        action: index,
+                        seat: number,
+                        maximum: max_raise_to,
+                        found: to,
+                    });
+                }
+                (seat.put(to), to.saturating_sub(current_bet))
+            }
+        };
+        seat.acted = true;
+        let step = Step {
+            street: self.street,
+            seat: seat.seat,
+            action: action.clone(),
+            amount,
+            to: seat.street_committed,
+            raise,
+            all_in: seat.all_in && amount > 0,
+        };
+        if raise > 0 {
+            self.current_bet += raise;
+            if raise >= last_raise {
+                self.last_raise = raise;
+            }
+        }
+        self.steps.push(step);
+        self.settle(i);
+        Ok(self.steps.last().expect("Step was just pushed"))
+    }
+
+    /// Resolves the uncalled bet, pots, rake and showdown once betting is over.
+    pub fn finish(&mut self, board: &Board, fees: &[Fee]) -> Result<(), ReplayError> {
+        self.finish_with(board, &[], fees)
+    }
+
+    /// [`Replay::finish`] with the hand run on further boards, which split every pot evenly
+    /// with the first. Odd chips go to the earlier boards.
+    ///
+    /// A runout deals the same streets as the board and repeats the cards it shares with it,
+    /// up to the first card it deals anew.
+    pub fn finish_with(
+        &mut self,
+        board: &Board,
+        runouts: &[Board],
+        fees: &[Fee],
+    ) -> Result<(), ReplayError> {
+        if !self.finished {
+            return Err(ReplayError::Incomplete {
+                seat: self.to_act().unwrap_or_default(),
+            });
+        }
+        let dealt = self.street.board_len();
+        if let Some(i) = board[..dealt].iter().position(|&c| c == Card::Xx) {
+            return Err(ReplayError::MissingBoard { index: i });
+        }
+        if let Some(i) = board[dealt..].iter().position(|&c| c != Card::Xx) {
+            return Err(ReplayError::ExtraBoardCards { index: dealt + i });
+        }
+        for (runout, cards) in runouts.iter().enumerate() {
+            let index = (0..5).find(|&i| (cards[i] == Card::Xx) != (i >= dealt));
+            if let Some(index) = index {
+                return Err(ReplayError::RunoutBoard { runout, index });
+            }
+        }
+        let mut seen = Vec::new();
+        let new_cards = runouts.iter().flat_map(|runout| {
+            let shared = (0..5).take_while(|&i| runout[i] == board[i]).count();
+            runout[shared..].iter()
+        });
+        for &card in self
+            .seats
+            .iter()
+            .flat_map(|s| s.hole_cards.iter())
+            .chain(board.iter())
+            .chain(new_cards)
+            .filter(|c| c.is_known())
+        {
+            if seen.contains(&card) {
+                return Err(ReplayError::DuplicateCard(card));
+            }
+            if !self.variant.deals(card) {
+                return Err(ReplayError::NotInDeck {
+                    card,
+                    variant: self.variant,
+                });
+            }
+            seen.push(card);
+        }
+        self.board = *board;
+        self.runouts = runouts.to_vec();
+
+        self.return_uncalled();
+        self.build_pots();
+        self.take_rake(fees);
+
+        let live: Vec<usize> = (0..self.seats.len())
+            .filter(|&i| !self.seats[i].folded)
+            .collect();
+        if live.len() > 1 {
+            self.showdown = self.ranks(self.dealt_board());
+        }
+        self.award_pots();
+        for fee in fees {
+            let Some(seat) = fee
+                .seat
+                .and_then(|s| self.seats.iter_mut().find(|x| x.seat == s))
+            else {
+                continue;
+            };
+            match fee.fee_type {
+                FeeType::InsurancePremium => seat.stack = seat.stack.saturating_sub(fee.amount),
+                FeeType::InsurancePayout => seat.stack += fee.amount,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// The index of the dealt seat at or immediately before the button.
+    fn button_index(&self) -> usize {
+        self.seats
+            .iter()
+            .rposition(|s| s.seat <= self.button)
+            .unwrap_or(self.seats.len() - 1)
+    }
+
+    /// Finds the next seat to act after `from`, moving through streets as betting closes.
+    fn settle(&mut self, from: usize) {
+        let n = self.seats.len();
+        let mut from = from;
+        loop {
+            if self.seats.iter().filter(|s| !s.folded).count() <= 1 {
+                break;
+            }
+            let current_bet = self.current_bet;
+            self.to_act = (1..=n)
+                .map(|k| (from + k) % n)
+                .find(|&i| self.seats[i].needs_to_act(current_bet));
+            if self.to_act.is_some() {
+                return;
+            }
+            let able = self.seats.iter().filter(|s| !s.folded && !s.all_in).count();
+            match self.street.next() {
+                // Nobody is left to bet against, so the board runs out.
+                Some(_) if able <= 1 => {
+                    self.street = Street::River;
+                    break;
+                }
+                Some(next) => {
+                    self.street = next;
+                    self.current_bet = 0;
+                    self.last_raise = self.big_blind.max(1);
+                    for seat in &mut self.seats {
+                        seat.street_committed = 0;
+                        seat.acted = false;
+                    }
+                    from = self.button_index();
+                }
+                None => break,
+            }
+        }
+        self.to_act = None;
+        self.finished = true;
+    }
+
+    fn return_uncalled(&mut self) {
+        let mut order: Vec<usize> = (0..self.seats.len()).collect();
+        order.sort_by(|&a, &b| self.seats[b].committed.cmp(&self.seats[a].committed));
+        let top = order[0];
+        let excess = self.seats[top].committed - self.seats[order[1]].committed;
+        if excess > 0 {
+            let seat = &mut self.seats[top];
+            seat.committed -= excess;
+            seat.stack += excess;
+            self.uncalled = Some((seat.seat, excess));
+        }
+    }
+
+    fn build_pots(&mut self) {
+        let mut levels: Vec<Decimal> = self
+            .seats
+            .iter()
+            .filter(|s| !s.folded)
+            .map(|s| s.committed)
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut pots: Vec<Pot> = Vec::new();
+        let mut previous = 0;
+        for &level in &levels {
+            let amount = self
+                .seats
+                .iter()
+                .map(|s| s.committed.min(level) - s.committed.min(previous))
+                .sum();
+            let eligible: Vec<u8> = self
+                .seats
+                .iter()
+                .filter(|s| !s.folded && s.committed >= level)
+                .map(|s| s.seat)
+                .collect();
+            previous = level;
+            match pots.last_mut() {
+                Some(pot) if pot.eligible == eligible => pot.amount += amount,
+                _ if amount == 0 => {}
+                _ => pots.push(Pot {
+                    amount,
+                    eligible,
+                    winners: Vec::new(),
+                    boards: Vec::new(),
+                }),
+            }
+        }
+        // Dead money from folded players above the last live contribution.
+        let dead: Decimal = self
+            .seats
+            .iter()
+            .map(|s| s.committed.saturating_sub(previous))
+            .sum();
+        if let Some(pot) = pots.last_mut() {
+            pot.amount += dead;
+        }
+        self.pots = pots;
+    }
+
+    /// Takes rake from the main pot first. Recorded pot deductions are used when present,
+    /// otherwise the table's rake applies once a flop has been dealt, with a zero cap
+    /// treated as uncapped.
+    fn take_rake(&mut self, fees: &[Fee]) {
+        let cash_drop: Decimal = fees
+            .iter()
+            .filter(|f| f.fee_type == FeeType::CashDrop)
+            .map(|f| f.amount)
+            .sum();
+        if let Some(pot) = self.pots.first_mut() {
+            pot.amount += cash_drop;
+        }
+        let mut rake = if fees.iter().any(|f| f.fee_type.is_pot_deduction()) {
+            fees.iter()
+                .filter(|f| f.fee_type.is_pot_deduction())
+                .map(|f| f.amount)
+                .sum()
+        } else if self.street == Street::Preflop {
+            0
+        } else {
+            let total: Decimal = self.pots.iter().map(|p| p.amount).sum();
+            let rake = total * self.rake_percentage as Decimal / 100;
+            if self.rake_cap > 0 {
+                rake.min(self.rake_cap)
+            } else {
+                rake
+            }
+        };
+        self.rake = rake;
+        for pot in &mut self.pots {
+            let taken = rake.min(pot.amount);
+            pot.amount -= taken;
+            rake -= taken;
+        }
+    }
+
+    /// The hand strengths of the seats still in the hand on a board, `None` for unknown hands.
+    fn ranks(&self, board: &[Card]) -> Vec<(u8, Option<HandRank>)> {
+        self.seats
+            .iter()
+            .filter(|s| !s.folded)
+            .map(|seat| {
+                let rank = if seat.hole_cards.iter().all(|c| c.is_known()) {
+                    eval::evaluate_hand(self.variant, &seat.hole_cards, board)
+                } else {
+                    None
+                };
+                (seat.seat, rank)
+            })
+            .collect()
+    }
+
+    fn award_pots(&mut self) {
+        let n = self.seats.len();
+        let first = self.button_index() + 1;
+        let order = |seat: u8, seats: &[SeatState]| {
+            let i = seats.iter().position(|s| s.seat == seat).unwrap_or(0);
+            (i + n - first % n) % n
+        };
+        let dealt = self.street.board_len();
+        let mut showdowns = vec![self.showdown.clone()];
+        for runout in &self.runouts {
+            showdowns.push(self.ranks(&runout[..dealt]));
+        }
+        let mut pots = std::mem::take(&mut self.pots);
+        for pot in &mut pots {
+            let boards = showdowns.len() as Decimal;
+            for (board, showdown) in showdowns.iter().enumerate() {
+                let mut winners: Vec<u8> = if pot.eligible.len() == 1 {
+                    pot.eligible.clone()
+                } else {
+                    let ranks: Vec<(u8, HandRank)> = showdown
+                        .iter()
+                        .filter(|(seat, _)| pot.eligible.contains(seat))
+                        .filter_map(|&(seat, rank)| rank.map(|r| (seat, r)))
+                        .collect();
+                    match ranks.iter().map(|(_, r)| *r).max() {
+                        Some(best) => ranks
+                            .iter()
+                            .filter(|(_, r)| *r == best)
+                            .map(|(s, _)| *s)
+                            .collect(),
+                        None => Vec::new(),
+                    }
+                };
+                if winners.is_empty() {
+                    pot.boards.push(Vec::new());
+                    continue;
+                }
+                let amount =
+                    pot.amount / boards + ((board as Decimal) < pot.amount % boards) as Decimal;
+                // Odd chips go to the winners closest to the left of the button.
+                winners.sort_by_key(|&seat| order(seat, &self.seats));
+                let share = amount / winners.len() as Decimal;
+                let odd = amount % winners.len() as Decimal;
+                let shares: Vec<(u8, Decimal)> = winners
+                    .iter()
+                    .enumerate()
+                    .map(|(k, &seat)| (seat, share + (k < odd as usize) as Decimal))
+                    .collect();
+                for &(seat, amount) in &shares {
+                    match pot.winners.iter_mut().find(|(s, _)| *s == seat) {
+                        Some((_, total)) => *total += amount,
+                        None => pot.winners.push((seat, amount)),
+                    }
+                    if let Some(state) = self.seats.iter_mut().find(|s| s.seat == seat) {
+                        state.stack += amount;
+                    }
+                }
+                pot.boards.push(shares);
+            }
+        }
+        self.pots = pots;
+    }
+}
+
+/// The reasons a [`Hand`] cannot be replayed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    NotEnoughPlayers,
+    TooManyHoleCards {
+        dealt: usize,
+        found: usize,
+    },
+    /// A player's hole cards are not as many as the [`Variant`] deals.
+    HoleCardCount {
+        seat: u8,
+        expected: usize,
+        found: usize,
+    },
+    HandFinished {
+        action: usize,
+    },
+    CannotCheck {
+        action: usize,
+        seat: u8,
+    },
+    CannotCall {
+        action: usize,
+        seat: u8,
+    },
+    CannotBet {
+        action: usize,
+        seat: u8,
+    },
+    CannotRaise {
+        action: usize,
+        seat: u8,
+    },
+    AmountMismatch {
+        action: usize,
+        seat: u8,
+        expected: Decimal,
+        found: Decimal,
+    },
+    BelowMinimum {
+        action: usize,
+        seat: u8,
+        minimum: Decimal,
+        found: Decimal,
+    },
+    ExceedsStack {
+        action: usize,
+        seat: u8,
+        available: Decimal,
+        found: Decimal,
+    },
+    AboveMaximum {
+        action: usize,
+        seat: u8,
+        maximum: Decimal,
+        found: Decimal,
+    },
+    BettingCapped {
+        action: usize,
+        seat: u8,
+    },
+    Incomplete {
+        seat: u8,
+    },
+    MissingBoard {
+        index: usize,
+    },
+    ExtraBoardCards {
+        index: usize,
+    },
+    RunoutBoard {
+        runout: usize,
+        index: usize,
+    },
+    /// An action's time is before the time of an earlier action.
+    ActionTime {
+        action: usize,
+    },
+    ClockEvent {
+        index: usize,
+    },
+    DuplicateCard(Card),
+    NotInDeck {
+        card: Card,
+        variant: Variant,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::NotEnoughPlayers => {
+                write!(f, "Fewer than two players with chips are seated.")
+            }
+            ReplayError::TooManyHoleCards { dealt, found } => write!(
+                f,
+                "{} pairs of hole cards given for {} dealt players.",
+                found, dealt
+            ),
+            ReplayError::HoleCardCount {
+                seat,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Seat {} holds {} hole cards instead of {}.",
+                seat, found, expected
+            ),
+            ReplayError::HandFinished { action } => {
+                write!(f, "Action {} occurs after betting is over.", action)
+            }
+            ReplayError::CannotCheck { action, seat } => {
+                write!(
+                    f,
+                    "Action {}: seat {} cannot check facing a bet.",
+                    action, seat
+                )
+            }
+            ReplayError::CannotCall { action, seat } => {
+                write!(f, "Action {}: seat {} has no bet to call.", action, seat)
+            }
+            ReplayError::CannotBet { action, seat } => write!(
+                f,
+                "Action {}: seat {} cannot bet facing a bet, it must raise.",
+                action, seat
+            ),
+            ReplayError::CannotRaise { action, seat } => write!(
+                f,
+                "Action {}: seat {} cannot raise without a bet, it must bet.",
+                action, seat
+            ),
+            ReplayError::AmountMismatch {
+                action,
+                seat,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Action {}: seat {} should be at {} but is at {}.",
+                action, seat, expected, found
+            ),
+            ReplayError::BelowMinimum {
+                action,
+                seat,
+                minimum,
+                found,
+            } => write!(
+                f,
+                "Action {}: seat {} bets to {} below the minimum of {}.",
+                action, seat, found, minimum
+            ),
+            ReplayError::ExceedsStack {
+                action,
+                seat,
+                available,
+                found,
+            } => write!(
+                f,
+                "Action {}: seat {} bets to {} with only {} available.",
+                action, seat, found, available
+            ),
+            ReplayError::AboveMaximum {
+                action,
+                seat,
+                maximum,
+                found,
+            } => write!(
+                f,
+                "Action {}: seat {} bets to {} above the maximum of {}.",
+                action, seat, found, maximum
+            ),
+            ReplayError::BettingCapped { action, seat } => write!(
+                f,
+                "Action {}: seat {} raises after betting is capped.",
+                action, seat
+            ),
+            ReplayError::Incomplete { seat } => {
+                write!(f, "Actions end while seat {} is still to act.", seat)
+            }
+            ReplayError::MissingBoard { index } => {
+                write!(f, "Board card {} was dealt but is missing.", index)
+            }
+            ReplayError::ExtraBoardCards { index } => {
+                write!(f, "Board card {} is present but was never dealt.", index)
+            }
+            ReplayError::RunoutBoard { runout, index } => write!(
+                f,
+                "Runout {} does not deal the board's streets at card {}.",
+                runout, index
+            ),
+            ReplayError::ActionTime { action } => {
+                write!(f, "Action {} is timed before an earlier action.", action)
+            }
+            ReplayError::ClockEvent { index } => write!(
+                f,
+                "Clock event {} does not match a dealt seat and its action.",
+                index
+            ),
+            ReplayError::DuplicateCard(card) => write!(f, "Card {} appears twice.", card),
+            ReplayError::NotInDeck { card, variant } => {
+                write!(f, "Card {} is not in the {} deck.", card, variant)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::eval::Category;
+    use crate::game::{Id, Session, TableEvent};
+
+    fn first_hand(session: &Session) -> (&Table, &Hand) {
+        let table = &session.tables[0];
+        match &table.events[0] {
+            TableEvent::Hand(hand) => (table, hand),
+            _ => unreachable!(),
+        }
+    }
+
+    fn seats(table: &Table) -> Vec<Option<Player>> {
+        table.initial_context.iter().cloned().map(Some).collect()
+    }
+
+    fn action(action_type: ActionType, bet_amount: u32) -> Action {
+        Action {
+            action_type,
+            bet_amount,
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn test_replay_attributes_actions() -> Result<(), ReplayError> {
+        let session = Session::exhaustive();
+        let (table, hand) = first_hand(&session);
+        let replay = Replay::hand(table, &seats(table), hand)?;
+
+        assert_eq!(
+            replay.blinds,
+            vec![
+                Blind {
+                    seat: 1,
+                    amount: 50
+                },
+                Blind {
+                    seat: 0,
+                    amount: 100
+                }
+            ]
+        );
+        let order: Vec<(Street, u8)> = replay.steps.iter().map(|s| (s.street, s.seat)).collect();
+        assert_eq!(
+            order,
+            vec![
+                (Street::Preflop, 1),
+                (Street::Preflop, 0),
+                (Street::Flop, 0),
+                (Street::Flop, 1),
+                (Street::Flop, 0),
+            ]
+        );
+        assert_eq!(replay.street, Street::Flop);
+        assert_eq!(replay.uncalled, Some((1, 1200)));
+        assert_eq!(replay.total_pot(), 1800);
+        assert_eq!(replay.rake, 3);
+        assert_eq!(replay.collected(1), 1797);
+        assert_eq!(replay.net(1), 897);
+        assert_eq!(replay.net(0), -900);
+        assert!(replay.showdown.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_showdown_and_side_pots() -> Result<(), ReplayError> {
+        let mut table = Session::exhaustive().tables.remove(0);
+        table.rake_percentage = 0;
+        let seats = vec![
+            Some(Player {
+                id: 1,
+                name: "Short".to_string(),
+                stack: 500,
+            }),
+            Some(Player {
+                id: 2,
+                name: "Deep".to_string(),
+                stack: 10_000,
+            }),
+            Some(Player {
+                id: 3,
+                name: "Deeper".to_string(),
+                stack: 20_000,
+            }),
+        ];
+        // Button on seat 0, so seat 1 is the small blind and seat 2 the big blind.
+        let hand = Hand {
+            id: 1,
+            button_position: 0,
+            hole_cards: vec![
+                vec![Card::AceClubs, Card::AceSpades],
+                vec![Card::KingClubs, Card::KingSpades],
+                vec![Card::TwoClubs, Card::SevenDiamonds],
+            ],
+            actions: vec![
+                action(ActionType::AllIn, 500),
+                action(ActionType::Raise, 2000),
+                action(ActionType::Call, 2000),
+                action(ActionType::Check, 0),
+                action(ActionType::Check, 0),
+                action(ActionType::Check, 0),
+                action(ActionType::Check, 0),
+                action(ActionType::Check, 0),
+                action(ActionType::Check, 0),
+            ],
+            timestamp: 0,
+            runouts: vec![],
+            bomb_pot: None,
+            fees: vec![],
+            clock_events: vec![],
+            board: [
+                Card::NineHearts,
+                Card::TenHearts,
+                Card::ThreeDiamonds,
+                Card::FourClubs,
+                Card::QueenSpades,
+            ],
+        };
+        let replay = Replay::hand(&table, &seats, &hand)?;
+        assert_eq!(replay.pots.len(), 2);
+        assert_eq!(replay.pots[0].amount, 1500);
+        assert_eq!(replay.pots[0].winners, vec![(0, 1500)]);
+        assert_eq!(replay.pots[1].amount, 3000);
+        assert_eq!(replay.pots[1].eligible, vec![1, 2]);
+        assert_eq!(replay.pots[1].winners, vec![(1, 3000)]);
+        assert_eq!(replay.net(0), 1000);
+        assert_eq!(replay.net(1), 1000);
+        assert_eq!(replay.net(2), -2000);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_runouts() -> Result<(), ReplayError> {
+        let mut table = Session::exhaustive().tables.remove(0);
+        table.rake_percentage = 0;
+        let player = |id: Id| Player {
+            id,
+            name: id.to_string(),
+            stack: 1000,
+        };
+        let seats = vec![Some(player(1)), Some(player(2))];
+        let board = |cards: &str| -> Board {
+            let cards: Vec<Card> = cards.split(' ').map(|c| c.parse().unwrap()).collect();
+            cards.try_into().unwrap()
+        };
+        let mut hand = Hand {
+            id: 1,
+            button_position: 0,
+            hole_cards: vec![
+                vec![Card::AceClubs, Card::AceSpades],
+                vec![Card::KingClubs, Card::KingSpades],
+            ],
+            actions: vec![
+                action(ActionType::AllIn, 1000),
+                action(ActionType::Call, 1000),
+            ],
+            timestamp: 0,
+            board: board("2d 7h 9c 3s 4d"),
+            runouts: vec![board("2d 7h 9c Kd 4h"), board("2d 7h 9c 3s 5h")],
+            bomb_pot: None,
+            fees: vec![],
+            clock_events: vec![],
+        };
+        let replay = Replay::hand(&table, &seats, &hand)?;
+        // Three boards share 2000, the odd chips going to the first two.
+        assert_eq!(
+            replay.pots[0].boards,
+            vec![vec![(0, 667)], vec![(1, 667)], vec![(0, 666)]]
+        );
+        assert_eq!(replay.pots[0].winners, vec![(0, 1333), (1, 667)]);
+        assert_eq!(replay.net(0), 333);
+        assert_eq!(replay.net(1), -333);
+
+        hand.runouts = vec![board("2d 7h 9c Kc 4h")];
+        assert_eq!(
+            Replay::hand(&table, &seats, &hand),
+            Err(ReplayError::DuplicateCard(Card::KingClubs))
+        );
+        hand.runouts[0][4] = Card::Xx;
+        assert_eq!(
+            Replay::hand(&table, &seats, &hand),
+            Err(ReplayError::RunoutBoard {
+                runout: 0,
+                index: 4
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_bomb_pot() -> Result<(), ReplayError> {
+        let session = Session::exhaustive();
+        let (table, hand) = first_hand(&session);
+        let mut hand = hand.clone();
+        hand.bomb_pot = Some(100);
+        // The first hand's flop betting, without the preflop raise and call.
+        hand.actions.drain(..2);
+        let replay = Replay::hand(table, &seats(table), &hand)?;
+        assert!(replay.bomb_pot && replay.blinds.is_empty());
+        let antes: Vec<(u8, Decimal)> = replay.antes.iter().map(|a| (a.seat, a.amount)).collect();
+        assert_eq!(antes, vec![(0, 100), (1, 100)]);
+        assert_eq!(replay.steps[0].street, Street::Flop);
+        assert_eq!(replay.steps[0].seat, 0);
+        assert_eq!(replay.total_pot(), 1400);
+        assert_eq!(replay.net(1), 697);
+
+        let mut replay = Replay::start_bomb_pot(
+            table,
+            &Stakes::table(table),
+            &seats(table),
+            1,
+            &hand.hole_cards,
+            100,
+        )?;
+        assert_eq!((replay.street, replay.to_act()), (Street::Flop, Some(0)));
+        assert_eq!(replay.min_raise_to(), 100);
+        assert!(replay.apply(&action(ActionType::Bet, 50)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_clock_events() {
+        let session = Session::exhaustive();
+        let (table, hand) = first_hand(&session);
+        let mut hand = hand.clone();
+        hand.actions[0].timestamp = Some(hand.timestamp * 1000 + 4000);
+        hand.actions[1].timestamp = Some(hand.timestamp * 1000 + 9000);
+        hand.clock_events = vec![
+            ClockEvent {
+                event_type: ClockEventType::TimeBank,
+                seat: 0,
+                action: 1,
+                timestamp: Some(hand.timestamp * 1000 + 6000),
+            },
+            ClockEvent {
+                event_type: ClockEventType::Disconnect,
+                seat: 1,
+                action: 5,
+                timestamp: None,
+            },
+        ];
+        assert!(Replay::hand(table, &seats(table), &hand).is_ok());
+        assert_eq!(hand.time_bank_used(0), 3000);
+        assert_eq!(hand.time_bank_used(1), 0);
+
+        // Seat 1 acts first, so the time bank cannot be its opponent's.
+        hand.clock_events[0].action = 0;
+        assert_eq!(
+            Replay::hand(table, &seats(table), &hand),
+            Err(ReplayError::ClockEvent { index: 0 })
+        );
+        hand.clock_events[0].action = 1;
+        hand.clock_events[1].action = 6;
+        assert_eq!(
+            Replay::hand(table, &seats(table), &hand),
+            Err(ReplayError::ClockEvent { index: 1 })
+        );
+        hand.clock_events.clear();
+        hand.actions[2].timestamp = Some(hand.timestamp * 1000);
+        assert_eq!(
+            Replay::hand(table, &seats(table), &hand),
+            Err(ReplayError::ActionTime { action: 2 })
+        );
+    }
+
+    #[test]
+    fn test_replay_rejects_illegal_actions() {
+        let session = Session::exhaustive();
+        let (table, hand) = first_hand(&session);
+        let mut replay = Replay::start(table, &seats(table), 1, &hand.hole_cards).unwrap();
+        assert_eq!(replay.to_act(), Some(1));
+        assert_eq!(
+            replay.apply(&action(ActionType::Check, 50)),
+            Err(ReplayError::CannotCheck { action: 0, seat: 1 })
+        );
+        assert_eq!(
+            replay.apply(&action(ActionType::Raise, 150)),
+            Err(ReplayError::BelowMinimum {
+                action: 0,
+                seat: 1,
+                minimum: 200,
+                found: 150
+            })
+        );
+        assert!(replay.apply(&action(ActionType::Call, 100)).is_ok());
+        assert!(replay.apply(&action(ActionType::Check, 100)).is_ok());
+        assert_eq!(replay.street, Street::Flop);
+        assert_eq!(replay.to_act(), Some(0));
+        assert_eq!(
+            replay.finish(&hand.board, &[]),
+            Err(ReplayError::Incomplete { seat: 0 })
+        );
+    }
+
+    #[test]
+    fn test_replay_betting_structures() {
+        let session = Session::exhaustive();
+        let (table, hand) = first_hand(&session);
+        let mut table = table.clone();
+
+        table.betting_structure = BettingStructure::PotLimit;
+        let mut replay = Replay::start(&table, &seats(&table), 1, &hand.hole_cards).unwrap();
+        // Calling 50 makes a pot of 200, so the small blind may raise to 300.
+        assert_eq!(replay.max_raise_to(), 300);
+        assert_eq!(
+            replay.apply(&action(ActionType::Raise, 301)),
+            Err(ReplayError::AboveMaximum {
+                action: 0,
+                seat: 1,
+                maximum: 300,
+                found: 301
+            })
+        );
+        assert!(replay.apply(&action(ActionType::Raise, 300)).is_ok());
+        assert_eq!(replay.max_raise_to(), 900);
+
+        table.betting_structure = BettingStructure::FixedLimit;
+        let mut replay = Replay::start(&table, &seats(&table), 1, &hand.hole_cards).unwrap();
+        assert_eq!((replay.min_raise_to(), replay.max_raise_to()), (200, 200));
+        assert!(replay.apply(&action(ActionType::Raise, 300)).is_err());
+        for to in [200, 300, 400] {
+            assert!(replay.apply(&action(ActionType::Raise, to)).is_ok());
+        }
+        assert_eq!(replay.bets(), 4);
+        assert_eq!(replay.max_raise_to(), 400);
+        assert_eq!(
+            replay.apply(&action(ActionType::Raise, 500)),
+            Err(ReplayError::BettingCapped { action: 3, seat: 0 })
+        );
+        assert!(replay.apply(&action(ActionType::Call, 400)).is_ok());
+        assert_eq!((replay.street, replay.bet_size()), (Street::Flop, 100));
+        assert!(replay.apply(&action(ActionType::Check, 0)).is_ok());
+        assert!(replay.apply(&action(ActionType::Check, 0)).is_ok());
+        assert_eq!((replay.street, replay.bet_size()), (Street::Turn, 200));
+        assert_eq!(replay.min_raise_to(), 200);
+    }
+
+    #[test]
+    fn test_replay_omaha() -> Result<(), ReplayError> {
+        let session = Session::exhaustive();
+        let (table, hand) = first_hand(&session);
+        let mut table = table.clone();
+        table.variant = Variant::Omaha;
+        table.rake_percentage = 0;
+        let mut hand = hand.clone();
+        assert_eq!(
+            Replay::hand(&table, &seats(&table), &hand),
+            Err(ReplayError::HoleCardCount {
+                seat: 0,
+                expected: 4,
+                found: 2
+            })
+        );
+
+        // A single heart in the hole makes no flush, so two pair takes the pot.
+        hand.hole_cards = vec![
+            "Ah 2c 3c Ks"
+                .split(' ')
+                .map(|c| c.parse().unwrap())
+                .collect(),
+            "Qc Qd 4c 7d"
+                .split(' ')
+                .map(|c| c.parse().unwrap())
+                .collect(),
+        ];
+        hand.actions = vec![action(ActionType::Call, 100)];
+        hand.actions
+            .extend((0..7).map(|_| action(ActionType::Check, 0)));
+        hand.board = ["2h", "5h", "9h", "9c", "3s"].map(|c| c.parse().unwrap());
+        let replay = Replay::hand(&table, &seats(&table), &hand)?;
+        assert_eq!(replay.pots[0].winners, vec![(1, 200)]);
+        let ranks: Vec<Option<Category>> = replay
+            .showdown
+            .iter()
+            .map(|(_, rank)| rank.map(|r| r.category()))
+            .collect();
+        assert_eq!(
+            ranks,
+            vec![Some(Category::TwoPair), Some(Category::TwoPair)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_short_deck() {
+        let session = Session::exhaustive();
+        let (table, hand) = first_hand(&session);
+        let mut table = table.clone();
+        table.variant = Variant::ShortDeck;
+        // The first hand's twos are not in a short deck.
+        assert_eq!(
+            Replay::hand(&table, &seats(&table), hand),
+            Err(ReplayError::NotInDeck {
+                card: Card::TwoClubs,
+                variant: Variant::ShortDeck
+            })
+        );
+        let mut hand = hand.clone();
+        hand.hole_cards[1] = vec![Card::SixClubs, Card::SixSpades];
+        hand.board[0] = Card::NineClubs;
+        hand.board[1] = Card::NineHearts;
+        assert!(Replay::hand(&table, &seats(&table), &hand).is_ok());
+    }
+
+    #[test]
+    fn test_replay_rejects_bad_board() {
+        let session = Session::exhaustive();
+        let (table, hand) = first_hand(&session);
+        let mut hand = hand.clone();
+        hand.board[3] = Card::FourClubs;
+        assert_eq!(
+            Replay::hand(table, &seats(table), &hand),
+            Err(ReplayError::ExtraBoardCards { index: 3 })
+        );
+        hand.board[3] = Card::Xx;
+        hand.board[0] = Card::AceClubs;
+        assert_eq!(
+            Replay::hand(table, &seats(table), &hand),
+            Err(ReplayError::DuplicateCard(Card::AceClubs))
+        );
+    }
+}
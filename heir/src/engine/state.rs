@@ -0,0 +1,415 @@
+use crate::engine::replay::{Replay, ReplayError, Stakes};
+use crate::game::{Decimal, Hand, Id, Player, Table, TableEvent};
+use std::fmt;
+
+/// The seats and stakes of a [`Table`] as they stand between [`TableEvent`]s.
+///
+/// Seats are indexed by seat number, starting with the [`Table`]'s `initial_context`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TableState {
+    pub seats: Vec<Option<Player>>,
+    /// The table's blinds, until a [`LevelChange`](crate::game::LevelChange) sets new ones.
+    pub stakes: Stakes,
+}
+
+impl TableState {
+    /// Seats the [`Table`]'s initial context.
+    pub fn new(table: &Table) -> Self {
+        let mut seats: Vec<Option<Player>> =
+            table.initial_context.iter().cloned().map(Some).collect();
+        if seats.len() < table.table_size as usize {
+            seats.resize(table.table_size as usize, None);
+        }
+        TableState {
+            seats,
+            stakes: Stakes::table(table),
+        }
+    }
+
+    /// Applies a [`TableEvent`], replaying hands to carry their results into the stacks.
+    pub fn apply(
+        &mut self,
+        table: &Table,
+        event: &TableEvent,
+    ) -> Result<Option<Replay>, ReplayError> {
+        match event {
+            TableEvent::Hand(hand) => {
+                let replay = Replay::hand_with(table, &self.stakes, &self.seats, hand)?;
+                for seat in &replay.seats {
+                    if let Some(Some(player)) = self.seats.get_mut(seat.seat as usize) {
+                        player.stack = seat.stack;
+                    }
+                }
+                Ok(Some(replay))
+            }
+            TableEvent::StackUpdate(update) => {
+                if let Some(Some(player)) = self.seats.get_mut(update.seat as usize) {
+                    player.stack = update.stack as u64;
+                }
+                Ok(None)
+            }
+            TableEvent::SeatUpdate(update) => {
+                let seat = update.seat as usize;
+                if seat >= self.seats.len() {
+                    self.seats.resize(seat + 1, None);
+                }
+                self.seats[seat] = update.player.clone();
+                Ok(None)
+            }
+            TableEvent::LevelChange(change) => {
+                self.stakes = Stakes {
+                    blinds: change.blinds.clone(),
+                    ante: change.ante,
+                };
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Something in a [`Table`]'s events that does not fit the state of the table before it.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Inconsistency {
+    /// The initial context seats more players than the table has seats.
+    TooManyPlayers {
+        players: usize,
+        table_size: u8,
+    },
+    StackUpdateToEmptySeat {
+        event: usize,
+        seat: u8,
+    },
+    /// A seat update empties a seat that was already empty.
+    EmptySeatVacated {
+        event: usize,
+        seat: u8,
+    },
+    /// A player is seated at two seats at once.
+    DuplicatePlayer {
+        event: usize,
+        id: Id,
+        seats: [u8; 2],
+    },
+    /// A stack update takes chips away from the stack the previous hand left. Larger stacks
+    /// are top-ups and are expected.
+    StackDecreased {
+        event: usize,
+        seat: u8,
+        expected: Decimal,
+        found: Decimal,
+    },
+    /// A player without chips is still seated when a hand starts. The hand is dealt without
+    /// them, but busted players should leave or rebuy first.
+    EmptyStack {
+        event: usize,
+        seat: u8,
+    },
+    /// A hand does not replay.
+    Replay {
+        event: usize,
+        hand: Id,
+        error: ReplayError,
+    },
+}
+
+impl Inconsistency {
+    /// The index of the event at fault, if any.
+    pub fn event(&self) -> Option<usize> {
+        match self {
+            Inconsistency::TooManyPlayers { .. } => None,
+            Inconsistency::StackUpdateToEmptySeat { event, .. }
+            | Inconsistency::EmptySeatVacated { event, .. }
+            | Inconsistency::DuplicatePlayer { event, .. }
+            | Inconsistency::StackDecreased { event, .. }
+            | Inconsistency::EmptyStack { event, .. }
+            | Inconsistency::Replay { event, .. } => Some(*event),
+        }
+    }
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Inconsistency::TooManyPlayers {
+                players,
+                table_size,
+            } => write!(
+                f,
+                "{} players start at a table of {} seats.",
+                players, table_size
+            ),
+            Inconsistency::StackUpdateToEmptySeat { seat, .. } => {
+                write!(f, "Stack update to empty seat {}.", seat)
+            }
+            Inconsistency::EmptySeatVacated { seat, .. } => {
+                write!(f, "Seat {} is emptied but was already empty.", seat)
+            }
+            Inconsistency::DuplicatePlayer { id, seats, .. } => write!(
+                f,
+                "Player {} is at both seat {} and seat {}.",
+                id, seats[0], seats[1]
+            ),
+            Inconsistency::StackDecreased {
+                seat,
+                expected,
+                found,
+                ..
+            } => write!(
+                f,
+                "Seat {}'s stack drops from {} to {} between hands.",
+                seat, expected, found
+            ),
+            Inconsistency::EmptyStack { seat, .. } => {
+                write!(f, "Seat {} is still seated with an empty stack.", seat)
+            }
+            Inconsistency::Replay { error, .. } => error.fmt(f),
+        }
+    }
+}
+
+/// A [`Hand`] together with the seats it was dealt to, as yielded by [`Tracker`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct TrackedHand<'a> {
+    /// The index of the hand among the table's events.
+    pub event: usize,
+    pub hand: &'a Hand,
+    /// Each seat's player and stack as the hand was dealt.
+    pub seats: Vec<Option<Player>>,
+    pub replay: Result<Replay, ReplayError>,
+}
+
+/// Applies a [`Table`]'s events in order, yielding each hand with the seats it was dealt to
+/// and recording any [`Inconsistency`] along the way.
+///
+/// Hands that do not replay leave the stacks as they were.
+pub struct Tracker<'a> {
+    table: &'a Table,
+    state: TableState,
+    next: usize,
+    inconsistencies: Vec<Inconsistency>,
+}
+
+impl<'a> Tracker<'a> {
+    pub fn new(table: &'a Table) -> Self {
+        let mut inconsistencies = Vec::new();
+        if table.initial_context.len() > table.table_size as usize {
+            inconsistencies.push(Inconsistency::TooManyPlayers {
+                players: table.initial_context.len(),
+                table_size: table.table_size,
+            });
+        }
+        let state = TableState::new(table);
+        let mut tracker = Tracker {
+            table,
+            state,
+            next: 0,
+            inconsistencies,
+        };
+        tracker.check_duplicates(0);
+        tracker
+    }
+
+    /// The seats as they stand after the events applied so far.
+    pub fn state(&self) -> &TableState {
+        &self.state
+    }
+
+    /// Everything found so far, in event order.
+    pub fn inconsistencies(&self) -> &[Inconsistency] {
+        &self.inconsistencies
+    }
+
+    fn occupied(&self, seat: u8) -> Option<&Player> {
+        self.state.seats.get(seat as usize).and_then(Option::as_ref)
+    }
+
+    fn check_duplicates(&mut self, event: usize) {
+        let seated: Vec<(u8, Id)> = (0..self.state.seats.len() as u8)
+            .filter_map(|s| self.occupied(s).map(|p| (s, p.id)))
+            .collect();
+        for (i, &(seat, id)) in seated.iter().enumerate() {
+            if let Some(&(other, _)) = seated[i + 1..].iter().find(|(_, other)| *other == id) {
+                self.inconsistencies.push(Inconsistency::DuplicatePlayer {
+                    event,
+                    id,
+                    seats: [seat, other],
+                });
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Tracker<'a> {
+    type Item = TrackedHand<'a>;
+
+    fn next(&mut self) -> Option<TrackedHand<'a>> {
+        while let Some(event) = self.table.events.get(self.next) {
+            let index = self.next;
+            self.next += 1;
+            match event {
+                TableEvent::Hand(hand) => {
+                    let seats = self.state.seats.clone();
+                    for (seat, player) in seats.iter().enumerate() {
+                        if player.as_ref().is_some_and(|p| p.stack == 0) {
+                            self.inconsistencies.push(Inconsistency::EmptyStack {
+                                event: index,
+                                seat: seat as u8,
+                            });
+                        }
+                    }
+                    let replay = self
+                        .state
+                        .apply(self.table, event)
+                        .map(|r| r.expect("Hands yield a replay"));
+                    if let Err(error) = &replay {
+                        self.inconsistencies.push(Inconsistency::Replay {
+                            event: index,
+                            hand: hand.id,
+                            error: error.clone(),
+                        });
+                    }
+                    return Some(TrackedHand {
+                        event: index,
+                        hand,
+                        seats,
+                        replay,
+                    });
+                }
+                TableEvent::StackUpdate(update) => match self.occupied(update.seat) {
+                    None => self
+                        .inconsistencies
+                        .push(Inconsistency::StackUpdateToEmptySeat {
+                            event: index,
+                            seat: update.seat,
+                        }),
+                    Some(player) if (update.stack as Decimal) < player.stack => {
+                        self.inconsistencies.push(Inconsistency::StackDecreased {
+                            event: index,
+                            seat: update.seat,
+                            expected: player.stack,
+                            found: update.stack as Decimal,
+                        })
+                    }
+                    Some(_) => {}
+                },
+                TableEvent::SeatUpdate(update) => {
+                    if update.player.is_none() && self.occupied(update.seat).is_none() {
+                        self.inconsistencies.push(Inconsistency::EmptySeatVacated {
+                            event: index,
+                            seat: update.seat,
+                        });
+                    }
+                }
+                TableEvent::LevelChange(_) => {}
+            }
+            // Updates never fail to apply.
+            let _ = self.state.apply(self.table, event);
+            if let TableEvent::SeatUpdate(_) = event {
+                self.check_duplicates(index);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{SeatUpdate, Session, StackUpdate};
+
+    #[test]
+    fn test_table_state_tracks_events() -> Result<(), ReplayError> {
+        let session = Session::exhaustive();
+        let table = &session.tables[0];
+        let mut state = TableState::new(table);
+
+        let replay = state.apply(table, &table.events[0])?;
+        assert!(replay.is_some());
+        assert_eq!(state.seats[0].as_ref().unwrap().stack, 9_100);
+        assert_eq!(state.seats[1].as_ref().unwrap().stack, 10_897);
+
+        state.apply(table, &table.events[1])?;
+        assert_eq!(state.seats[1].as_ref().unwrap().stack, 20_000);
+
+        state.apply(table, &table.events[2])?;
+        assert_eq!(state.seats[0].as_ref().unwrap().id, 1003);
+
+        let replay = state.apply(table, &table.events[3])?.unwrap();
+        assert_eq!(replay.showdown.len(), 2);
+        assert_eq!(replay.collected(0), 3_397);
+        assert_eq!(state.seats[0].as_ref().unwrap().stack, 16_697);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tracker() {
+        let session = Session::exhaustive();
+        let mut tracker = Tracker::new(&session.tables[0]);
+        let hands: Vec<TrackedHand> = tracker.by_ref().collect();
+        assert_eq!(hands.len(), 2);
+        assert_eq!(hands[1].event, 3);
+        assert_eq!(hands[1].seats[0].as_ref().unwrap().id, 1003);
+        assert_eq!(hands[1].seats[1].as_ref().unwrap().stack, 20_000);
+        assert!(hands.iter().all(|h| h.replay.is_ok()));
+        assert!(tracker.inconsistencies().is_empty());
+
+        let mut table = session.tables[0].clone();
+        table.events.insert(
+            1,
+            TableEvent::StackUpdate(StackUpdate {
+                seat: 0,
+                stack: 5_000,
+            }),
+        );
+        table.events.insert(
+            2,
+            TableEvent::SeatUpdate(SeatUpdate {
+                seat: 1,
+                player: Some(table.initial_context[0].clone()),
+            }),
+        );
+        table.events.push(TableEvent::StackUpdate(StackUpdate {
+            seat: 5,
+            stack: 100,
+        }));
+        let mut tracker = Tracker::new(&table);
+        assert_eq!(tracker.by_ref().count(), 2);
+        let found: Vec<String> = tracker
+            .inconsistencies()
+            .iter()
+            .map(|i| format!("{:?} {}", i.event(), i))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                "Some(1) Seat 0's stack drops from 9100 to 5000 between hands.",
+                "Some(2) Player 1001 is at both seat 0 and seat 1.",
+                "Some(6) Stack update to empty seat 5."
+            ]
+        );
+
+        let mut table = session.tables[0].clone();
+        table.events.insert(
+            1,
+            TableEvent::SeatUpdate(SeatUpdate {
+                seat: 2,
+                player: Some(Player {
+                    id: 1004,
+                    name: "Busted".to_string(),
+                    stack: 0,
+                }),
+            }),
+        );
+        let mut tracker = Tracker::new(&table);
+        assert!(tracker.by_ref().all(|h| h.replay.is_ok()));
+        let found: Vec<String> = tracker
+            .inconsistencies()
+            .iter()
+            .map(|i| format!("{:?} {}", i.event(), i))
+            .collect();
+        assert_eq!(
+            found,
+            ["Some(4) Seat 2 is still seated with an empty stack."]
+        );
+    }
+}
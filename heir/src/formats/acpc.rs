@@ -0,0 +1,834 @@
+use crate::engine::replay::{Replay, SeatState, Street};
+use crate::engine::state::TableState;
+use crate::formats::builder::{BuiltHand, HandBuilder, SessionBuilder, SiteAction};
+use crate::formats::{concatenated_cards, sniff_text, HandHistoryImporter, Import};
+use crate::game::{
+    ActionType, BettingStructure, Decimal, Hand, Id, Player, Table, TableEvent, TableSize,
+    Timestamp, Variant,
+};
+use crate::types::card::Card;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+
+const SITE: &str = "ACPC";
+
+/// The number of board cards dealt at the start of each round.
+const BOARD_CARDS: [usize; 4] = [0, 3, 1, 1];
+
+/// Fails unless the table plays a game this module can describe, which is no-limit Texas
+/// hold'em.
+fn check_game(table: &Table) -> io::Result<()> {
+    if table.variant != Variant::Holdem {
+        return Err(invalid(format!("ACPC has no {} games.", table.variant)));
+    }
+    if table.betting_structure != BettingStructure::NoLimit {
+        return Err(invalid(format!(
+            "Only no-limit games are supported, not {}.",
+            table.betting_structure
+        )));
+    }
+    Ok(())
+}
+
+fn invalid(message: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// An ACPC game definition, limited to the no-limit Texas hold'em games heir can represent.
+///
+/// Positions are numbered as by the ACPC dealer. Heads-up the button is the position
+/// posting the smaller blind, and otherwise the last position, with the blinds posted from
+/// position 0. Betting order follows from the blinds as it does in heir.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Game {
+    /// The starting stack of each position.
+    pub stacks: Vec<Decimal>,
+    /// The blind posted by each position.
+    pub blinds: Vec<Decimal>,
+}
+
+impl Game {
+    /// The ACPC heads-up no-limit game, with 20000 chip stacks and reversed 50/100 blinds.
+    pub fn heads_up_no_limit() -> Self {
+        Game {
+            stacks: vec![20_000; 2],
+            blinds: vec![100, 50],
+        }
+    }
+
+    pub fn players(&self) -> usize {
+        self.stacks.len()
+    }
+
+    /// The position of the button.
+    pub fn button(&self) -> usize {
+        match self.blinds[..] {
+            [first, second] if first < second => 0,
+            [_, _] => 1,
+            _ => self.players() - 1,
+        }
+    }
+
+    /// Positions in the order they post blinds, from the small blind.
+    fn posting_order(&self) -> Vec<usize> {
+        if self.players() == 2 {
+            vec![self.button(), 1 - self.button()]
+        } else {
+            (0..self.players()).collect()
+        }
+    }
+
+    /// The blinds in the order they are posted, as in [`Table::blinds`].
+    pub fn table_blinds(&self) -> io::Result<Vec<Decimal>> {
+        let mut blinds: Vec<Decimal> = self
+            .posting_order()
+            .into_iter()
+            .map(|p| self.blinds[p])
+            .collect();
+        while blinds.last() == Some(&0) {
+            blinds.pop();
+        }
+        if blinds.contains(&0) {
+            return Err(invalid("Blinds must be posted from position 0."));
+        }
+        Ok(blinds)
+    }
+
+    /// The first position to act in each round.
+    fn first_player(&self) -> [usize; 4] {
+        let n = self.players();
+        let (preflop, postflop) = if n == 2 {
+            (self.button(), 1 - self.button())
+        } else {
+            (self.table_blinds().map_or(0, |b| b.len()) % n, 0)
+        };
+        [preflop, postflop, postflop, postflop]
+    }
+}
+
+impl FromStr for Game {
+    type Err = io::Error;
+
+    /// Parses a `GAMEDEF` block, rejecting games heir cannot represent.
+    fn from_str(text: &str) -> io::Result<Self> {
+        let mut game = Game {
+            stacks: Vec::new(),
+            blinds: Vec::new(),
+        };
+        let mut players = None;
+        let mut first_player = None;
+        for line in text.lines().map(str::trim) {
+            let numbers = |values: &str| -> io::Result<Vec<u64>> {
+                values
+                    .split_whitespace()
+                    .map(|v| {
+                        v.parse()
+                            .map_err(|_| invalid(format!("Invalid line {:?}.", line)))
+                    })
+                    .collect()
+            };
+            let Some((key, values)) = line.split_once('=') else {
+                match line.to_ascii_lowercase().as_str() {
+                    "limit" | "pot" => return Err(invalid("Only no-limit games are supported.")),
+                    _ => continue,
+                }
+            };
+            let values = numbers(values)?;
+            let expect = |expected: &[u64]| {
+                if values == expected {
+                    Ok(())
+                } else {
+                    Err(invalid(format!("Unsupported {:?}.", line)))
+                }
+            };
+            match key.trim().to_ascii_lowercase().as_str() {
+                "numplayers" => players = values.first().map(|&p| p as usize),
+                "stack" => game.stacks = values,
+                "blind" => game.blinds = values,
+                "firstplayer" => first_player = Some(values),
+                "numrounds" => expect(&[4])?,
+                "numsuits" => expect(&[4])?,
+                "numranks" => expect(&[13])?,
+                "numholecards" => expect(&[2])?,
+                "numboardcards" => expect(&[0, 3, 1, 1])?,
+                _ => {}
+            }
+        }
+        let players = players.ok_or_else(|| invalid("Missing numPlayers."))?;
+        if players < 2 || game.stacks.len() != players || game.blinds.len() != players {
+            return Err(invalid("Expected a stack and blind for every player."));
+        }
+        game.table_blinds()?;
+        if let Some(first_player) = first_player {
+            let expected: Vec<u64> = game.first_player().iter().map(|&p| p as u64 + 1).collect();
+            if first_player != expected {
+                return Err(invalid(format!(
+                    "Unsupported firstPlayer {:?}, expected {:?}.",
+                    first_player, expected
+                )));
+            }
+        }
+        Ok(game)
+    }
+}
+
+/// A betting action in ACPC notation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AcpcAction {
+    Fold,
+    /// A check or call.
+    Call,
+    /// A bet or raise to a total over the whole hand.
+    Raise(Decimal),
+}
+
+/// An ACPC `MATCHSTATE:` string: a hand as seen by the player at one position.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MatchState {
+    pub position: u8,
+    pub hand_number: Id,
+    /// The actions of each round dealt so far.
+    pub betting: Vec<Vec<AcpcAction>>,
+    /// The hole cards of each position, empty where they are not visible.
+    pub hole_cards: Vec<Vec<Card>>,
+    /// The board cards dealt so far.
+    pub board: Vec<Card>,
+}
+
+impl FromStr for MatchState {
+    type Err = io::Error;
+
+    fn from_str(text: &str) -> io::Result<Self> {
+        let fields: Vec<&str> = text.trim().split(':').collect();
+        let ["MATCHSTATE", position, hand_number, betting, cards] = fields[..] else {
+            return Err(invalid(format!("Invalid match state {:?}.", text)));
+        };
+        let number = |field: &str| {
+            field
+                .parse()
+                .map_err(|_| invalid(format!("Invalid match state {:?}.", text)))
+        };
+        let (hole_cards, board) = parse_cards(cards)?;
+        Ok(MatchState {
+            position: number(position)? as u8,
+            hand_number: number(hand_number)?,
+            betting: parse_betting(betting)?,
+            hole_cards,
+            board,
+        })
+    }
+}
+
+impl fmt::Display for MatchState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MATCHSTATE:{}:{}:{}:{}",
+            self.position,
+            self.hand_number,
+            betting(&self.betting),
+            cards(&self.hole_cards, &self.board, self.betting.len())
+        )
+    }
+}
+
+impl MatchState {
+    /// The hand so far, with position `i` in seat `i`. The hand need not be complete.
+    pub fn to_hand(&self, game: &Game) -> io::Result<Hand> {
+        Ok(self.builder(game)?.into_hand())
+    }
+
+    /// The position next to act, or `None` once betting is over.
+    pub fn to_act(&self, game: &Game) -> io::Result<Option<u8>> {
+        Ok(self.builder(game)?.replay().to_act())
+    }
+
+    fn builder(&self, game: &Game) -> io::Result<HandBuilder> {
+        let seats = (0..game.players())
+            .map(|p| {
+                Some(Player {
+                    id: p as Id,
+                    name: format!("Position {}", p),
+                    stack: game.stacks[p],
+                })
+            })
+            .collect();
+        let positions: Vec<u8> = (0..game.players() as u8).collect();
+        build(
+            game,
+            self.hand_number,
+            0,
+            &self.betting,
+            &self.hole_cards,
+            &self.board,
+            seats,
+            &positions,
+        )
+        .map_err(invalid)
+    }
+
+    /// A complete [`Hand`] dealt to the seats and at the stakes of a [`TableState`] as seen
+    /// from a position, with other players' hole cards visible only if they reached showdown.
+    pub fn from_hand(
+        table: &Table,
+        state: &TableState,
+        hand: &Hand,
+        position: u8,
+    ) -> io::Result<MatchState> {
+        check_game(table)?;
+        let replay =
+            Replay::hand_with(table, &state.stakes, &state.seats, hand).map_err(invalid)?;
+        let positions = positions(&replay);
+        if position as usize >= positions.len() {
+            return Err(invalid(format!("No player at position {}.", position)));
+        }
+        let showdown = replay.showdown.len() > 1;
+        let hole_cards = positions
+            .iter()
+            .enumerate()
+            .map(|(p, s)| {
+                let shown = showdown && replay.showdown.iter().any(|(seat, _)| *seat == s.seat);
+                let known = s.hole_cards.iter().all(|c| c.is_known());
+                if (p == position as usize || shown) && known {
+                    s.hole_cards.to_vec()
+                } else {
+                    Vec::new()
+                }
+            })
+            .collect();
+        Ok(MatchState {
+            position,
+            hand_number: hand.id,
+            betting: replay_betting(&replay),
+            hole_cards,
+            board: replay.dealt_board().to_vec(),
+        })
+    }
+}
+
+pub(crate) fn parse_betting(text: &str) -> io::Result<Vec<Vec<AcpcAction>>> {
+    text.split('/')
+        .map(|round| {
+            let mut actions = Vec::new();
+            let mut rest = round;
+            while let Some(c) = rest.chars().next() {
+                rest = &rest[c.len_utf8()..];
+                actions.push(match c {
+                    'f' => AcpcAction::Fold,
+                    'c' | 'k' => AcpcAction::Call,
+                    'r' => {
+                        let end = rest
+                            .find(|c: char| !c.is_ascii_digit())
+                            .unwrap_or(rest.len());
+                        let amount = rest[..end].parse().map_err(|_| {
+                            invalid(format!("Raise without an amount in {:?}.", text))
+                        })?;
+                        rest = &rest[end..];
+                        AcpcAction::Raise(amount)
+                    }
+                    _ => return Err(invalid(format!("Invalid betting {:?}.", text))),
+                });
+            }
+            Ok(actions)
+        })
+        .collect()
+}
+
+fn betting(rounds: &[Vec<AcpcAction>]) -> String {
+    let rounds: Vec<String> = rounds
+        .iter()
+        .map(|actions| {
+            actions
+                .iter()
+                .map(|action| match action {
+                    AcpcAction::Fold => "f".to_string(),
+                    AcpcAction::Call => "c".to_string(),
+                    AcpcAction::Raise(to) => format!("r{}", to),
+                })
+                .collect()
+        })
+        .collect();
+    rounds.join("/")
+}
+
+/// Parses cards such as "9s8h|/8c8d5c/6s" into hole cards by position and the board.
+fn parse_cards(text: &str) -> io::Result<(Vec<Vec<Card>>, Vec<Card>)> {
+    let mut rounds = text.split('/');
+    let hole_cards = rounds
+        .next()
+        .unwrap_or_default()
+        .split('|')
+        .map(concatenated_cards)
+        .collect::<io::Result<_>>()?;
+    let mut board = Vec::new();
+    for round in rounds {
+        board.extend(concatenated_cards(round)?);
+    }
+    Ok((hole_cards, board))
+}
+
+fn cards(hole_cards: &[Vec<Card>], board: &[Card], rounds: usize) -> String {
+    let hole_cards: Vec<String> = hole_cards
+        .iter()
+        .map(|cards| cards.iter().map(Card::to_string).collect())
+        .collect();
+    let mut text = hole_cards.join("|");
+    let mut start = 0;
+    for &count in BOARD_CARDS.iter().take(rounds).skip(1) {
+        text.push('/');
+        let end = (start + count).min(board.len());
+        text.extend(board[start..end].iter().map(Card::to_string));
+        start = end;
+    }
+    text
+}
+
+/// The dealt seats by ACPC position, from the first left of the button round to the button.
+fn positions(replay: &Replay) -> Vec<&SeatState> {
+    let n = replay.seats.len();
+    let first = replay
+        .seats
+        .iter()
+        .position(|s| s.seat > replay.button)
+        .unwrap_or(0);
+    (0..n).map(|k| &replay.seats[(first + k) % n]).collect()
+}
+
+/// The replayed actions in ACPC notation, with raises to totals over the whole hand.
+pub(crate) fn replay_betting(replay: &Replay) -> Vec<Vec<AcpcAction>> {
+    let size = replay
+        .seats
+        .iter()
+        .map(|s| s.seat as usize + 1)
+        .max()
+        .unwrap_or(0);
+    let mut totals = vec![0; size];
+    for blind in &replay.blinds {
+        totals[blind.seat as usize] = blind.amount;
+    }
+    let mut before_street = vec![0; size];
+    let mut street = Street::Preflop;
+    let mut rounds = vec![Vec::new(); replay.street as usize + 1];
+    for step in &replay.steps {
+        if step.street != street {
+            before_street = totals.clone();
+            street = step.street;
+        }
+        let seat = step.seat as usize;
+        totals[seat] = before_street[seat] + step.to;
+        let action = match step.action.action_type {
+            ActionType::Fold => AcpcAction::Fold,
+            ActionType::Check | ActionType::Call => AcpcAction::Call,
+            ActionType::AllIn if step.raise == 0 => AcpcAction::Call,
+            ActionType::Bet | ActionType::Raise | ActionType::AllIn => {
+                AcpcAction::Raise(totals[seat])
+            }
+        };
+        rounds[step.street as usize].push(action);
+    }
+    rounds
+}
+
+/// Builds a hand from ACPC betting, with each position in the given seat.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn build(
+    game: &Game,
+    id: Id,
+    timestamp: Timestamp,
+    betting: &[Vec<AcpcAction>],
+    hole_cards: &[Vec<Card>],
+    board: &[Card],
+    seats: Vec<Option<Player>>,
+    positions: &[u8],
+) -> Result<HandBuilder, String> {
+    let names: Vec<String> = positions
+        .iter()
+        .map(|&seat| {
+            seats[seat as usize]
+                .as_ref()
+                .map(|p| p.name.clone())
+                .expect("Positions are seated")
+        })
+        .collect();
+    let blinds = game.table_blinds().map_err(|e| e.to_string())?;
+    let button = positions[game.button()];
+    let mut hand = HandBuilder::new(id, timestamp, button, blinds.clone(), seats)?;
+    for (p, blind) in game.posting_order().into_iter().zip(blinds) {
+        hand.post(&names[p], blind.min(game.stacks[p]))?;
+    }
+    for (round, actions) in betting.iter().enumerate() {
+        if !actions.is_empty() && hand.replay().street as usize != round {
+            return Err(format!("Round {} does not follow the betting.", round + 1));
+        }
+        for &action in actions {
+            act(&mut hand, action)?;
+        }
+    }
+    for (name, cards) in names.iter().zip(hole_cards) {
+        if !cards.is_empty() {
+            hand.show(name, cards)?;
+        }
+    }
+    hand.board(board);
+    Ok(hand)
+}
+
+/// Applies an ACPC action for the seat next to act.
+pub(crate) fn act(hand: &mut HandBuilder, action: AcpcAction) -> Result<(), String> {
+    let replay = hand.replay();
+    let seat = replay.to_act().ok_or("Action after betting is over.")?;
+    let state = replay.seat(seat).expect("Seat to act is dealt");
+    let before_street = state.committed - state.street_committed;
+    let name = state.player.name.clone();
+    let action = match action {
+        AcpcAction::Fold => SiteAction::Fold,
+        AcpcAction::Call => SiteAction::CheckOrCall,
+        AcpcAction::Raise(total) => SiteAction::To(
+            total
+                .checked_sub(before_street)
+                .ok_or_else(|| format!("Raise to {} is below the amount committed.", total))?,
+        ),
+    };
+    hand.act(&name, action, false)
+}
+
+/// The [`HandHistoryImporter`] for ACPC dealer logs of a [`Game`].
+pub struct AcpcLog {
+    pub game: Game,
+}
+
+impl Default for AcpcLog {
+    fn default() -> Self {
+        AcpcLog {
+            game: Game::heads_up_no_limit(),
+        }
+    }
+}
+
+impl HandHistoryImporter for AcpcLog {
+    fn name(&self) -> &'static str {
+        SITE
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        sniff_text(head).lines().any(|l| l.starts_with("STATE:"))
+    }
+
+    fn import(&self, reader: &mut dyn Read) -> io::Result<Import> {
+        import(reader, &self.game)
+    }
+}
+
+/// Imports the `STATE:` lines of an ACPC dealer log as hands at one table.
+///
+/// Players keep the seat they first appear in while the dealer rotates positions, and
+/// every hand starts from the game's stacks. The match name in the log header, if any,
+/// names the table.
+pub fn import<R: Read>(mut reader: R, game: &Game) -> io::Result<Import> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut session = SessionBuilder::new(SITE);
+    let mut table = SITE.to_string();
+    let mut seat_names: Vec<String> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(header) = line.strip_prefix("# name/game/hands/seed ") {
+            if let Some(name) = header.split_whitespace().next() {
+                table = name.to_string();
+            }
+        }
+        let Some(state) = line.strip_prefix("STATE:") else {
+            continue;
+        };
+        if let Err(e) = parse_state(&mut session, game, &table, &mut seat_names, state) {
+            let id = state.split(':').next().unwrap_or(state);
+            session.warn(format!("Hand {} skipped: {}", id, e));
+        }
+    }
+    Ok(session.finish())
+}
+
+fn parse_state(
+    session: &mut SessionBuilder,
+    game: &Game,
+    table: &str,
+    seat_names: &mut Vec<String>,
+    state: &str,
+) -> Result<(), String> {
+    let fields: Vec<&str> = state.split(':').collect();
+    let [id, betting, cards, nets, names] = fields[..] else {
+        return Err(format!("Invalid state {:?}.", state));
+    };
+    let id: Id = id
+        .parse()
+        .map_err(|_| format!("Invalid hand number {:?}.", id))?;
+    let names: Vec<&str> = names.split('|').collect();
+    let nets: Vec<f64> = nets
+        .split('|')
+        .map(|n| n.parse().map_err(|_| format!("Invalid net {:?}.", n)))
+        .collect::<Result<_, _>>()?;
+    let n = game.players();
+    if names.len() != n || nets.len() != n {
+        return Err(format!("Expected {} players.", n));
+    }
+    let mut positions = Vec::new();
+    for name in &names {
+        let seat = match seat_names.iter().position(|s| s == name) {
+            Some(seat) => seat,
+            None if seat_names.len() < n => {
+                seat_names.push(name.to_string());
+                seat_names.len() - 1
+            }
+            None => return Err(format!("More than {} players.", n)),
+        };
+        positions.push(seat as u8);
+    }
+    let mut seats = vec![None; n];
+    for (p, &seat) in positions.iter().enumerate() {
+        seats[seat as usize] = Some(session.player(names[p], game.stacks[p]));
+    }
+    let blinds = game.table_blinds().map_err(|e| e.to_string())?;
+    let betting = parse_betting(betting).map_err(|e| e.to_string())?;
+    let (hole_cards, board) = parse_cards(cards).map_err(|e| e.to_string())?;
+    let hand = build(
+        game,
+        id,
+        0,
+        &betting,
+        &hole_cards,
+        &board,
+        seats,
+        &positions,
+    )?;
+
+    let BuiltHand {
+        seats,
+        hand,
+        replay,
+        warnings,
+        ..
+    } = hand.finish()?;
+    for warning in warnings {
+        session.warn(warning);
+    }
+    for (p, &seat) in positions.iter().enumerate() {
+        let net = replay.net(seat);
+        if net as f64 != nets[p].round() {
+            session.warn(format!(
+                "Hand {}: {} won {} but the replay gives {}.",
+                id, names[p], nets[p], net
+            ));
+        }
+    }
+    session.push(table, n as TableSize, &blinds, seats, hand);
+    Ok(())
+}
+
+/// Writes every [`Hand`] at a [`Table`] as ACPC dealer log `STATE:` lines, followed by a
+/// `SCORE:` line with each player's total. Only no-limit Texas hold'em tables can be written,
+/// and hands run on more than one board and bomb pots cannot be.
+pub fn write_log<W: Write>(writer: &mut W, table: &Table) -> io::Result<()> {
+    check_game(table)?;
+    // ACPC separates fields and players with ':' and '|'.
+    let name = |player: &Player| player.name.replace([':', '|'], "_");
+    let mut state = TableState::new(table);
+    let mut scores: Vec<(String, i64)> = Vec::new();
+    for event in &table.events {
+        if let TableEvent::Hand(hand) = event {
+            if !hand.runouts.is_empty() {
+                return Err(invalid(format!(
+                    "Hand {} is run on more than one board.",
+                    hand.id
+                )));
+            }
+            if hand.bomb_pot.is_some() {
+                return Err(invalid(format!("Hand {} is a bomb pot.", hand.id)));
+            }
+            let replay =
+                Replay::hand_with(table, &state.stakes, &state.seats, hand).map_err(invalid)?;
+            let positions = positions(&replay);
+            let hole_cards: Vec<Vec<Card>> = positions
+                .iter()
+                .map(|s| {
+                    if s.hole_cards.iter().all(|c| c.is_known()) {
+                        s.hole_cards.to_vec()
+                    } else {
+                        Vec::new()
+                    }
+                })
+                .collect();
+            let rounds = replay.street as usize + 1;
+            let mut nets = Vec::new();
+            let mut names = Vec::new();
+            for s in &positions {
+                let (net, name) = (replay.net(s.seat), name(&s.player));
+                match scores.iter_mut().find(|(n, _)| *n == name) {
+                    Some((_, score)) => *score += net,
+                    None => scores.push((name.clone(), net)),
+                }
+                nets.push(net.to_string());
+                names.push(name);
+            }
+            writeln!(
+                writer,
+                "STATE:{}:{}:{}:{}:{}",
+                hand.id,
+                betting(&replay_betting(&replay)),
+                cards(&hole_cards, replay.dealt_board(), rounds),
+                nets.join("|"),
+                names.join("|")
+            )?;
+        }
+        state.apply(table, event).map_err(invalid)?;
+    }
+    let (names, totals): (Vec<String>, Vec<String>) = scores
+        .into_iter()
+        .map(|(name, score)| (name, score.to_string()))
+        .unzip();
+    writeln!(writer, "SCORE:{}:{}", totals.join("|"), names.join("|"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Session;
+
+    const LOG: &str = "\
+# name/game/hands/seed match1 holdem.nolimit.2p.reverse_blinds.game 2 0
+STATE:0:r300c/cr900f:Ah9d|Kc7s/2h8dTs:-300|300:Alice|Bob
+STATE:1:cc/cc/cc/r200c:6h4c|QdJh/3s9c2d/Ks/5h:200|-200:Bob|Alice
+SCORE:-500|500:Alice|Bob
+";
+
+    #[test]
+    fn test_match_state() -> io::Result<()> {
+        let text = "MATCHSTATE:0:30:cc/r250c/r500c/:9s8h|/8c8d5c/6s/2d";
+        let state: MatchState = text.parse()?;
+        assert_eq!(state.position, 0);
+        assert_eq!(state.hand_number, 30);
+        assert_eq!(state.betting.len(), 4);
+        assert_eq!(
+            state.betting[1],
+            vec![AcpcAction::Raise(250), AcpcAction::Call]
+        );
+        assert_eq!(state.hole_cards[1], vec![]);
+        assert_eq!(state.to_string(), text);
+
+        let hand = state.to_hand(&Game::heads_up_no_limit())?;
+        assert_eq!(hand.button_position, 1);
+        assert_eq!(hand.hole_cards, vec![[Card::NineSpades, Card::EightHearts]]);
+        let actions: Vec<_> = hand
+            .actions
+            .iter()
+            .map(|a| (a.action_type.clone(), a.bet_amount))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                (ActionType::Call, 100),
+                (ActionType::Check, 100),
+                (ActionType::Bet, 150),
+                (ActionType::Call, 150),
+                (ActionType::Bet, 250),
+                (ActionType::Call, 250),
+            ]
+        );
+        assert_eq!(hand.board[4], Card::TwoDiamonds);
+        assert!("MATCHSTATE:0:1:cr:".parse::<MatchState>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_log_round_trip() -> io::Result<()> {
+        let import = import(LOG.as_bytes(), &Game::heads_up_no_limit())?;
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        let table = &import.session.tables[0];
+        assert_eq!(table.name, "match1");
+        assert_eq!(table.blinds, vec![50, 100]);
+        assert_eq!(table.events.len(), 4);
+        let TableEvent::Hand(hand) = &table.events[3] else {
+            panic!("Expected a hand");
+        };
+        // Alice keeps seat 0 and has the button in the second hand.
+        assert_eq!(hand.button_position, 0);
+
+        let mut written = Vec::new();
+        write_log(&mut written, table)?;
+        let expected: Vec<&str> = LOG.lines().skip(1).collect();
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            expected.join("\n") + "\n"
+        );
+
+        let mut state = TableState::new(table);
+        state.apply(table, &table.events[0]).unwrap();
+        state.apply(table, &table.events[1]).unwrap();
+        state.apply(table, &table.events[2]).unwrap();
+        let state = MatchState::from_hand(table, &state, hand, 1)?;
+        assert_eq!(
+            state.to_string(),
+            "MATCHSTATE:1:1:cc/cc/cc/r200c:6h4c|QdJh/3s9c2d/Ks/5h"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_log_rejects_other_games() {
+        let session = Session::omaha(Variant::Omaha);
+        let table = &session.tables[0];
+        let error = write_log(&mut Vec::new(), table).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "ACPC has no omaha games.");
+        let TableEvent::Hand(hand) = &table.events[0] else {
+            panic!("Expected a hand");
+        };
+        let state = TableState::new(table);
+        assert!(MatchState::from_hand(table, &state, hand, 0).is_err());
+
+        for structure in [BettingStructure::PotLimit, BettingStructure::FixedLimit] {
+            let mut session = Session::exhaustive();
+            session.tables[0].betting_structure = structure;
+            let table = &session.tables[0];
+            let error = write_log(&mut Vec::new(), table).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(
+                error.to_string(),
+                format!("Only no-limit games are supported, not {}.", structure)
+            );
+            let TableEvent::Hand(hand) = &table.events[0] else {
+                panic!("Expected a hand");
+            };
+            let state = TableState::new(table);
+            assert!(MatchState::from_hand(table, &state, hand, 0).is_err());
+        }
+    }
+
+    #[test]
+    fn test_log_warns_on_wrong_net() -> io::Result<()> {
+        let log = LOG.replace("-300|300:Alice", "-200|200:Alice");
+        let import = import(log.as_bytes(), &Game::heads_up_no_limit())?;
+        assert_eq!(
+            import.warnings,
+            vec![
+                "Hand 0: Alice won -200 but the replay gives -300.",
+                "Hand 0: Bob won 200 but the replay gives 300."
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_game() -> io::Result<()> {
+        let text = "GAMEDEF\nnolimit\nnumPlayers = 2\nnumRounds = 4\nstack = 20000 20000\n\
+                    blind = 100 50\nfirstPlayer = 2 1 1 1\nnumSuits = 4\nnumRanks = 13\n\
+                    numHoleCards = 2\nnumBoardCards = 0 3 1 1\nEND GAMEDEF\n";
+        assert_eq!(text.parse::<Game>()?, Game::heads_up_no_limit());
+        assert!(text.replace("nolimit", "limit").parse::<Game>().is_err());
+        assert!(text.replace("2 1 1 1", "1 2 2 2").parse::<Game>().is_err());
+        let three = "numPlayers = 3\nstack = 500 500 500\nblind = 5 10 0\nfirstPlayer = 3 1 1 1";
+        let game: Game = three.parse()?;
+        assert_eq!(game.button(), 2);
+        assert_eq!(game.table_blinds()?, vec![5, 10]);
+        Ok(())
+    }
+}
@@ -0,0 +1,988 @@
+use crate::formats::{HandHistoryImporter, Import};
+use crate::game::{
+    Action, ActionType, Annotations, BettingStructure, ClockEvent, ClockEventType, Decimal, Fee,
+    FeeType, Finish, Hand, HandAnnotation, Label, Level, LevelChange, Millis, Player, PlayerNote,
+    SeatUpdate, Session, StackUpdate, Table, TableEvent, Tournament, Variant,
+};
+use crate::types::board::Board;
+use crate::types::card::Card;
+use crate::types::version::Version;
+use std::io::{self, Read, Write};
+
+/// The bytes every `.heir.bin` file starts with, followed by its [`Version`].
+pub const MAGIC: &[u8; 4] = b"HEIR";
+
+const HAND: u8 = 0;
+const STACK_UPDATE: u8 = 1;
+const SEAT_UPDATE: u8 = 2;
+const LEVEL_CHANGE: u8 = 3;
+
+/// The seat byte of a [`Fee`] that belongs to no seat.
+const NO_SEAT: u8 = u8::MAX;
+/// The action of a [`HandAnnotation`] on the whole hand.
+const NO_ACTION: u16 = u16::MAX;
+
+/// The version from which sessions end with their tournament, if any.
+const TOURNAMENTS: (u8, u8) = (0, 2);
+/// The version from which tables carry their [`BettingStructure`] after their blinds.
+/// Earlier versions only hold no-limit games.
+const STRUCTURES: (u8, u8) = (0, 3);
+/// The version from which tables carry their [`Variant`] after their betting structure, and
+/// hands as many hole cards per player as the variant deals. Earlier versions only hold Texas
+/// hold'em, with two hole cards.
+const VARIANTS: (u8, u8) = (0, 4);
+/// The version from which hands carry their runouts after the board.
+const RUNOUTS: (u8, u8) = (0, 5);
+/// The version from which hands carry their bomb pot after the runouts.
+const BOMB_POTS: (u8, u8) = (0, 6);
+/// The version from which hands end with their action times and clock events.
+const CLOCKS: (u8, u8) = (0, 7);
+/// The version from which sessions end with their annotations, after their tournament.
+const ANNOTATIONS: (u8, u8) = (0, 8);
+
+/// The time of an action or clock event without a recorded time.
+const NO_TIME: u32 = u32::MAX;
+
+/// Whether a version is the given version or later.
+fn since(version: Version, first: (u8, u8)) -> bool {
+    (version.major(), version.minor()) >= first
+}
+
+fn invalid(message: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// The [`HandHistoryImporter`] for `.heir.bin` files.
+pub struct HeirBin;
+
+impl HandHistoryImporter for HeirBin {
+    fn name(&self) -> &'static str {
+        "heir.bin"
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        head.starts_with(MAGIC)
+    }
+
+    fn import(&self, reader: &mut dyn Read) -> io::Result<Import> {
+        Ok(Import {
+            session: read_session(reader)?,
+            warnings: Vec::new(),
+        })
+    }
+}
+
+/// Writes a [`Session`] in the `.heir.bin` format.
+///
+/// Numbers are little-endian, strings are UTF-8 prefixed by their length in bytes, and
+/// lists are prefixed by their length. Boards are packed as by [`Board`]. From version 0.2,
+/// the session ends with its tournament, from version 0.3, each table's betting structure
+/// follows its blinds, from version 0.4, each table's variant follows its betting structure,
+/// from version 0.5, each hand's runouts follow its board, and from version 0.6, its bomb pot
+/// follows the runouts. From version 0.7, hands end with their action times and clock events,
+/// as by `write_clock`, and from version 0.8, the session ends with its annotations.
+pub fn write_session<W: Write>(writer: &mut W, session: &Session) -> io::Result<()> {
+    writer.write_all(MAGIC)?;
+    session.version.serialize(writer)?;
+    write_u64(writer, session.id)?;
+    write_str(writer, &session.name)?;
+    write_u64(writer, session.hero_id)?;
+    write_len(writer, session.tables.len(), u32::MAX as usize)?;
+    for table in &session.tables {
+        write_table(writer, table, session.version)?;
+    }
+    if since(session.version, TOURNAMENTS) {
+        match &session.tournament {
+            Some(tournament) => {
+                writer.write_all(&[1])?;
+                write_tournament(writer, tournament)?;
+            }
+            None => writer.write_all(&[0])?,
+        }
+    } else if session.tournament.is_some() {
+        return Err(invalid(format!(
+            "Version {}.{} sessions have no tournament.",
+            session.version.major(),
+            session.version.minor()
+        )));
+    }
+    if since(session.version, ANNOTATIONS) {
+        write_annotations(writer, &session.annotations)?;
+    } else if !session.annotations.is_empty() {
+        return Err(invalid(format!(
+            "Version {}.{} sessions have no annotations.",
+            session.version.major(),
+            session.version.minor()
+        )));
+    }
+    Ok(())
+}
+
+/// Reads a [`Session`] in the `.heir.bin` format.
+pub fn read_session<R: Read>(mut reader: R) -> io::Result<Session> {
+    let reader = &mut reader;
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid("Not a .heir.bin file."));
+    }
+    let version = Version::deserialize(reader)?;
+    let current = (Version::CURRENT.major(), Version::CURRENT.minor());
+    if (version.major(), version.minor()) > current {
+        return Err(invalid(format!(
+            "Unsupported .heir.bin version {}.{}.",
+            version.major(),
+            version.minor()
+        )));
+    }
+    let id = read_u64(reader)?;
+    let name = read_str(reader)?;
+    let hero_id = read_u64(reader)?;
+    let tables = (0..read_u32(reader)?)
+        .map(|_| read_table(reader, version))
+        .collect::<io::Result<_>>()?;
+    let tournament = if since(version, TOURNAMENTS) {
+        match read_u8(reader)? {
+            0 => None,
+            1 => Some(read_tournament(reader)?),
+            flag => return Err(invalid(format!("Invalid tournament flag {}.", flag))),
+        }
+    } else {
+        None
+    };
+    let annotations = if since(version, ANNOTATIONS) {
+        read_annotations(reader)?
+    } else {
+        Annotations::default()
+    };
+    Ok(Session {
+        version,
+        id,
+        name,
+        tables,
+        hero_id,
+        tournament,
+        annotations,
+    })
+}
+
+/// Writes the hand annotations, each with its table and hand ids, action index or
+/// [`NO_ACTION`], tags and note, then the player notes, each with its player id, label or 0,
+/// and note.
+fn write_annotations<W: Write>(writer: &mut W, annotations: &Annotations) -> io::Result<()> {
+    write_len(writer, annotations.hands.len(), u32::MAX as usize)?;
+    for annotation in &annotations.hands {
+        write_u64(writer, annotation.table_id)?;
+        write_u64(writer, annotation.hand_id)?;
+        let action = match annotation.action {
+            Some(action) => u16::try_from(action)
+                .ok()
+                .filter(|&action| action != NO_ACTION)
+                .ok_or_else(|| invalid("Annotated action is out of range."))?,
+            None => NO_ACTION,
+        };
+        writer.write_all(&action.to_le_bytes())?;
+        write_len(writer, annotation.tags.len(), u8::MAX as usize)?;
+        for tag in &annotation.tags {
+            write_str(writer, tag)?;
+        }
+        write_str(writer, &annotation.note)?;
+    }
+    write_len(writer, annotations.players.len(), u32::MAX as usize)?;
+    for note in &annotations.players {
+        write_u64(writer, note.player_id)?;
+        let label = match note.label {
+            None => 0,
+            Some(Label::Red) => 1,
+            Some(Label::Orange) => 2,
+            Some(Label::Yellow) => 3,
+            Some(Label::Green) => 4,
+            Some(Label::Blue) => 5,
+            Some(Label::Purple) => 6,
+        };
+        writer.write_all(&[label])?;
+        write_str(writer, &note.note)?;
+    }
+    Ok(())
+}
+
+fn read_annotations<R: Read>(reader: &mut R) -> io::Result<Annotations> {
+    let hands = (0..read_u32(reader)?)
+        .map(|_| {
+            Ok(HandAnnotation {
+                table_id: read_u64(reader)?,
+                hand_id: read_u64(reader)?,
+                action: match read_u16(reader)? {
+                    NO_ACTION => None,
+                    action => Some(action as usize),
+                },
+                tags: (0..read_u8(reader)?)
+                    .map(|_| read_str(reader))
+                    .collect::<io::Result<_>>()?,
+                note: read_str(reader)?,
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    let players = (0..read_u32(reader)?)
+        .map(|_| {
+            Ok(PlayerNote {
+                player_id: read_u64(reader)?,
+                label: match read_u8(reader)? {
+                    0 => None,
+                    1 => Some(Label::Red),
+                    2 => Some(Label::Orange),
+                    3 => Some(Label::Yellow),
+                    4 => Some(Label::Green),
+                    5 => Some(Label::Blue),
+                    6 => Some(Label::Purple),
+                    byte => return Err(invalid(format!("Invalid label {}.", byte))),
+                },
+                note: read_str(reader)?,
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    Ok(Annotations { hands, players })
+}
+
+fn write_tournament<W: Write>(writer: &mut W, tournament: &Tournament) -> io::Result<()> {
+    write_u64(writer, tournament.buy_in)?;
+    write_u64(writer, tournament.fee)?;
+    write_u64(writer, tournament.starting_stack)?;
+    write_u64(writer, tournament.start)?;
+    writer.write_all(&tournament.entrants.to_le_bytes())?;
+    write_len(writer, tournament.levels.len(), u16::MAX as usize)?;
+    for level in &tournament.levels {
+        write_blinds(writer, &level.blinds)?;
+        write_u64(writer, level.ante)?;
+        writer.write_all(&level.duration.to_le_bytes())?;
+    }
+    write_len(writer, tournament.payouts.len(), u32::MAX as usize)?;
+    for &payout in &tournament.payouts {
+        write_u64(writer, payout)?;
+    }
+    write_len(writer, tournament.finishes.len(), u32::MAX as usize)?;
+    for finish in &tournament.finishes {
+        write_u64(writer, finish.player_id)?;
+        writer.write_all(&finish.position.to_le_bytes())?;
+        write_u64(writer, finish.prize)?;
+    }
+    Ok(())
+}
+
+fn read_tournament<R: Read>(reader: &mut R) -> io::Result<Tournament> {
+    Ok(Tournament {
+        buy_in: read_u64(reader)?,
+        fee: read_u64(reader)?,
+        starting_stack: read_u64(reader)?,
+        start: read_u64(reader)?,
+        entrants: read_u32(reader)?,
+        levels: (0..read_u16(reader)?)
+            .map(|_| {
+                Ok(Level {
+                    blinds: read_blinds(reader)?,
+                    ante: read_u64(reader)?,
+                    duration: read_u32(reader)?,
+                })
+            })
+            .collect::<io::Result<_>>()?,
+        payouts: (0..read_u32(reader)?)
+            .map(|_| read_u64(reader))
+            .collect::<io::Result<_>>()?,
+        finishes: (0..read_u32(reader)?)
+            .map(|_| {
+                Ok(Finish {
+                    player_id: read_u64(reader)?,
+                    position: read_u32(reader)?,
+                    prize: read_u64(reader)?,
+                })
+            })
+            .collect::<io::Result<_>>()?,
+    })
+}
+
+fn write_blinds<W: Write>(writer: &mut W, blinds: &[Decimal]) -> io::Result<()> {
+    write_len(writer, blinds.len(), u8::MAX as usize)?;
+    for &blind in blinds {
+        write_u64(writer, blind)?;
+    }
+    Ok(())
+}
+
+fn read_blinds<R: Read>(reader: &mut R) -> io::Result<Vec<Decimal>> {
+    (0..read_u8(reader)?).map(|_| read_u64(reader)).collect()
+}
+
+fn write_table<W: Write>(writer: &mut W, table: &Table, version: Version) -> io::Result<()> {
+    write_u64(writer, table.id)?;
+    write_str(writer, &table.name)?;
+    write_str(writer, &table.location)?;
+    writer.write_all(&[table.table_size, table.rake_percentage])?;
+    write_u64(writer, table.rake_cap)?;
+    write_blinds(writer, &table.blinds)?;
+    if since(version, STRUCTURES) {
+        let structure = match table.betting_structure {
+            BettingStructure::NoLimit => 0,
+            BettingStructure::PotLimit => 1,
+            BettingStructure::FixedLimit => 2,
+        };
+        writer.write_all(&[structure])?;
+    } else if table.betting_structure != BettingStructure::NoLimit {
+        return Err(invalid(format!(
+            "Version {}.{} sessions only hold no-limit games.",
+            version.major(),
+            version.minor()
+        )));
+    }
+    if since(version, VARIANTS) {
+        let variant = match table.variant {
+            Variant::Holdem => 0,
+            Variant::Omaha => 1,
+            Variant::Omaha5 => 2,
+            Variant::Omaha6 => 3,
+            Variant::ShortDeck => 4,
+        };
+        writer.write_all(&[variant])?;
+    } else if table.variant != Variant::Holdem {
+        return Err(invalid(format!(
+            "Version {}.{} sessions only hold Texas hold'em.",
+            version.major(),
+            version.minor()
+        )));
+    }
+    write_len(writer, table.initial_context.len(), u8::MAX as usize)?;
+    for player in &table.initial_context {
+        write_player(writer, player)?;
+    }
+    write_len(writer, table.events.len(), u32::MAX as usize)?;
+    for event in &table.events {
+        match event {
+            TableEvent::Hand(hand) => {
+                writer.write_all(&[HAND])?;
+                write_hand(writer, hand, table.variant, version)?;
+            }
+            TableEvent::StackUpdate(update) => {
+                writer.write_all(&[STACK_UPDATE, update.seat])?;
+                writer.write_all(&update.stack.to_le_bytes())?;
+            }
+            TableEvent::SeatUpdate(update) => {
+                writer.write_all(&[SEAT_UPDATE, update.seat])?;
+                match &update.player {
+                    Some(player) => {
+                        writer.write_all(&[1])?;
+                        write_player(writer, player)?;
+                    }
+                    None => writer.write_all(&[0])?,
+                }
+            }
+            TableEvent::LevelChange(change) => {
+                writer.write_all(&[LEVEL_CHANGE])?;
+                writer.write_all(&change.level.to_le_bytes())?;
+                write_blinds(writer, &change.blinds)?;
+                write_u64(writer, change.ante)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_table<R: Read>(reader: &mut R, version: Version) -> io::Result<Table> {
+    let id = read_u64(reader)?;
+    let name = read_str(reader)?;
+    let location = read_str(reader)?;
+    let table_size = read_u8(reader)?;
+    let rake_percentage = read_u8(reader)?;
+    let rake_cap = read_u64(reader)?;
+    let blinds = read_blinds(reader)?;
+    let betting_structure = if since(version, STRUCTURES) {
+        match read_u8(reader)? {
+            0 => BettingStructure::NoLimit,
+            1 => BettingStructure::PotLimit,
+            2 => BettingStructure::FixedLimit,
+            byte => return Err(invalid(format!("Invalid betting structure {}.", byte))),
+        }
+    } else {
+        BettingStructure::NoLimit
+    };
+    let variant = if since(version, VARIANTS) {
+        match read_u8(reader)? {
+            0 => Variant::Holdem,
+            1 => Variant::Omaha,
+            2 => Variant::Omaha5,
+            3 => Variant::Omaha6,
+            4 => Variant::ShortDeck,
+            byte => return Err(invalid(format!("Invalid variant {}.", byte))),
+        }
+    } else {
+        Variant::Holdem
+    };
+    let initial_context = (0..read_u8(reader)?)
+        .map(|_| read_player(reader))
+        .collect::<io::Result<_>>()?;
+    let events = (0..read_u32(reader)?)
+        .map(|_| match read_u8(reader)? {
+            HAND => Ok(TableEvent::Hand(read_hand(reader, variant, version)?)),
+            STACK_UPDATE => Ok(TableEvent::StackUpdate(StackUpdate {
+                seat: read_u8(reader)?,
+                stack: read_u32(reader)?,
+            })),
+            SEAT_UPDATE => {
+                let seat = read_u8(reader)?;
+                let player = match read_u8(reader)? {
+                    0 => None,
+                    1 => Some(read_player(reader)?),
+                    flag => return Err(invalid(format!("Invalid player flag {}.", flag))),
+                };
+                Ok(TableEvent::SeatUpdate(SeatUpdate { seat, player }))
+            }
+            LEVEL_CHANGE => Ok(TableEvent::LevelChange(LevelChange {
+                level: read_u32(reader)?,
+                blinds: read_blinds(reader)?,
+                ante: read_u64(reader)?,
+            })),
+            tag => Err(invalid(format!("Invalid table event {}.", tag))),
+        })
+        .collect::<io::Result<_>>()?;
+    Ok(Table {
+        id,
+        name,
+        location,
+        table_size,
+        rake_percentage,
+        rake_cap,
+        blinds,
+        betting_structure,
+        variant,
+        initial_context,
+        events,
+    })
+}
+
+fn write_player<W: Write>(writer: &mut W, player: &Player) -> io::Result<()> {
+    write_u64(writer, player.id)?;
+    write_str(writer, &player.name)?;
+    write_u64(writer, player.stack)
+}
+
+fn read_player<R: Read>(reader: &mut R) -> io::Result<Player> {
+    Ok(Player {
+        id: read_u64(reader)?,
+        name: read_str(reader)?,
+        stack: read_u64(reader)?,
+    })
+}
+
+/// Hole cards are written as the variant deals them, so every player holds as many.
+fn write_hand<W: Write>(
+    writer: &mut W,
+    hand: &Hand,
+    variant: Variant,
+    version: Version,
+) -> io::Result<()> {
+    write_u64(writer, hand.id)?;
+    writer.write_all(&[hand.button_position])?;
+    write_len(writer, hand.hole_cards.len(), u8::MAX as usize)?;
+    for cards in &hand.hole_cards {
+        if cards.len() != variant.hole_cards() {
+            return Err(invalid(format!(
+                "Hand {} deals {} hole cards in {}.",
+                hand.id,
+                cards.len(),
+                variant
+            )));
+        }
+        for card in cards {
+            writer.write_all(&[card.to_u8()])?;
+        }
+    }
+    write_len(writer, hand.actions.len(), u16::MAX as usize)?;
+    for action in &hand.actions {
+        writer.write_all(&[action_type_to_u8(&action.action_type)])?;
+        writer.write_all(&action.bet_amount.to_le_bytes())?;
+    }
+    write_u64(writer, hand.timestamp)?;
+    Board::from_array(hand.board).serialize(writer)?;
+    if since(version, RUNOUTS) {
+        write_len(writer, hand.runouts.len(), u8::MAX as usize)?;
+        for runout in &hand.runouts {
+            Board::from_array(*runout).serialize(writer)?;
+        }
+    } else if !hand.runouts.is_empty() {
+        return Err(invalid(format!(
+            "Version {}.{} sessions hold a single board per hand.",
+            version.major(),
+            version.minor()
+        )));
+    }
+    if since(version, BOMB_POTS) {
+        match hand.bomb_pot {
+            Some(amount) => {
+                writer.write_all(&[1])?;
+                write_u64(writer, amount)?;
+            }
+            None => writer.write_all(&[0])?,
+        }
+    } else if hand.bomb_pot.is_some() {
+        return Err(invalid(format!(
+            "Version {}.{} sessions have no bomb pots.",
+            version.major(),
+            version.minor()
+        )));
+    }
+    write_len(writer, hand.fees.len(), u8::MAX as usize)?;
+    for fee in &hand.fees {
+        writer.write_all(&[fee_type_to_u8(fee.fee_type)])?;
+        write_u64(writer, fee.amount)?;
+        writer.write_all(&[fee.seat.unwrap_or(NO_SEAT)])?;
+    }
+    let timed = hand.actions.iter().any(|a| a.timestamp.is_some());
+    if since(version, CLOCKS) {
+        write_clock(writer, hand, timed)?;
+    } else if timed || !hand.clock_events.is_empty() {
+        return Err(invalid(format!(
+            "Version {}.{} sessions have no action times or clock events.",
+            version.major(),
+            version.minor()
+        )));
+    }
+    Ok(())
+}
+
+/// Writes whether the actions are timed and, if they are, each action's milliseconds since
+/// the previous timed action, or since the hand's timestamp for the first. Clock events
+/// follow, timed from the hand's timestamp. Untimed actions and events are [`NO_TIME`].
+fn write_clock<W: Write>(writer: &mut W, hand: &Hand, timed: bool) -> io::Result<()> {
+    let start = hand.timestamp * 1000;
+    let delta = |from: Millis, to: Option<Millis>| match to {
+        None => Ok(NO_TIME),
+        Some(to) => to
+            .checked_sub(from)
+            .and_then(|delta| u32::try_from(delta).ok())
+            .filter(|&delta| delta != NO_TIME)
+            .ok_or_else(|| invalid(format!("Hand {} has a time out of order.", hand.id))),
+    };
+    writer.write_all(&[timed as u8])?;
+    if timed {
+        let mut previous = start;
+        for action in &hand.actions {
+            writer.write_all(&delta(previous, action.timestamp)?.to_le_bytes())?;
+            previous = action.timestamp.unwrap_or(previous);
+        }
+    }
+    write_len(writer, hand.clock_events.len(), u8::MAX as usize)?;
+    for event in &hand.clock_events {
+        let event_type = match event.event_type {
+            ClockEventType::TimeBank => 0,
+            ClockEventType::Disconnect => 1,
+            ClockEventType::Timeout => 2,
+        };
+        writer.write_all(&[event_type, event.seat])?;
+        let action = u16::try_from(event.action)
+            .map_err(|_| invalid(format!("Hand {} has a clock event out of range.", hand.id)))?;
+        writer.write_all(&action.to_le_bytes())?;
+        writer.write_all(&delta(start, event.timestamp)?.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_clock<R: Read>(reader: &mut R, hand: &mut Hand) -> io::Result<()> {
+    let start = hand.timestamp * 1000;
+    let time = |from: Millis, delta: u32| (delta != NO_TIME).then(|| from + delta as Millis);
+    match read_u8(reader)? {
+        0 => {}
+        1 => {
+            let mut previous = start;
+            for action in &mut hand.actions {
+                action.timestamp = time(previous, read_u32(reader)?);
+                previous = action.timestamp.unwrap_or(previous);
+            }
+        }
+        flag => return Err(invalid(format!("Invalid action times flag {}.", flag))),
+    }
+    hand.clock_events = (0..read_u8(reader)?)
+        .map(|_| {
+            let event_type = match read_u8(reader)? {
+                0 => ClockEventType::TimeBank,
+                1 => ClockEventType::Disconnect,
+                2 => ClockEventType::Timeout,
+                byte => return Err(invalid(format!("Invalid clock event type {}.", byte))),
+            };
+            Ok(ClockEvent {
+                event_type,
+                seat: read_u8(reader)?,
+                action: read_u16(reader)? as usize,
+                timestamp: time(start, read_u32(reader)?),
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    Ok(())
+}
+
+fn read_hand<R: Read>(reader: &mut R, variant: Variant, version: Version) -> io::Result<Hand> {
+    let id = read_u64(reader)?;
+    let button_position = read_u8(reader)?;
+    let hole_cards = (0..read_u8(reader)?)
+        .map(|_| {
+            (0..variant.hole_cards())
+                .map(|_| read_card(reader))
+                .collect()
+        })
+        .collect::<io::Result<_>>()?;
+    let actions = (0..read_u16(reader)?)
+        .map(|_| {
+            Ok(Action {
+                action_type: action_type_from_u8(read_u8(reader)?)?,
+                bet_amount: read_u32(reader)?,
+                timestamp: None,
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    let timestamp = read_u64(reader)?;
+    let board = Board::deserialize(reader)?.to_array()?;
+    let runouts = if since(version, RUNOUTS) {
+        (0..read_u8(reader)?)
+            .map(|_| Board::deserialize(reader)?.to_array())
+            .collect::<io::Result<_>>()?
+    } else {
+        Vec::new()
+    };
+    let bomb_pot = if since(version, BOMB_POTS) {
+        match read_u8(reader)? {
+            0 => None,
+            1 => Some(read_u64(reader)?),
+            flag => return Err(invalid(format!("Invalid bomb pot flag {}.", flag))),
+        }
+    } else {
+        None
+    };
+    let fees = (0..read_u8(reader)?)
+        .map(|_| {
+            Ok(Fee {
+                fee_type: fee_type_from_u8(read_u8(reader)?)?,
+                amount: read_u64(reader)?,
+                seat: Some(read_u8(reader)?).filter(|&s| s != NO_SEAT),
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    let mut hand = Hand {
+        id,
+        button_position,
+        hole_cards,
+        actions,
+        timestamp,
+        board,
+        runouts,
+        bomb_pot,
+        fees,
+        clock_events: Vec::new(),
+    };
+    if since(version, CLOCKS) {
+        read_clock(reader, &mut hand)?;
+    }
+    Ok(hand)
+}
+
+fn action_type_to_u8(action_type: &ActionType) -> u8 {
+    match action_type {
+        ActionType::Fold => 0,
+        ActionType::Check => 1,
+        ActionType::Bet => 2,
+        ActionType::Call => 3,
+        ActionType::Raise => 4,
+        ActionType::AllIn => 5,
+    }
+}
+
+fn action_type_from_u8(value: u8) -> io::Result<ActionType> {
+    Ok(match value {
+        0 => ActionType::Fold,
+        1 => ActionType::Check,
+        2 => ActionType::Bet,
+        3 => ActionType::Call,
+        4 => ActionType::Raise,
+        5 => ActionType::AllIn,
+        _ => return Err(invalid(format!("Invalid action type {}.", value))),
+    })
+}
+
+fn fee_type_to_u8(fee_type: FeeType) -> u8 {
+    match fee_type {
+        FeeType::Rake => 0,
+        FeeType::Jackpot => 1,
+        FeeType::Promotion => 2,
+        FeeType::Tax => 3,
+        FeeType::CashDrop => 4,
+        FeeType::InsurancePremium => 5,
+        FeeType::InsurancePayout => 6,
+    }
+}
+
+fn fee_type_from_u8(value: u8) -> io::Result<FeeType> {
+    Ok(match value {
+        0 => FeeType::Rake,
+        1 => FeeType::Jackpot,
+        2 => FeeType::Promotion,
+        3 => FeeType::Tax,
+        4 => FeeType::CashDrop,
+        5 => FeeType::InsurancePremium,
+        6 => FeeType::InsurancePayout,
+        _ => return Err(invalid(format!("Invalid fee type {}.", value))),
+    })
+}
+
+fn write_len<W: Write>(writer: &mut W, len: usize, max: usize) -> io::Result<()> {
+    if len > max {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("A list of {} entries exceeds the limit of {}.", len, max),
+        ));
+    }
+    match max {
+        m if m <= u8::MAX as usize => writer.write_all(&[len as u8]),
+        m if m <= u16::MAX as usize => writer.write_all(&(len as u16).to_le_bytes()),
+        _ => writer.write_all(&(len as u32).to_le_bytes()),
+    }
+}
+
+fn write_u64<W: Write>(writer: &mut W, value: u64) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_str<W: Write>(writer: &mut W, text: &str) -> io::Result<()> {
+    write_len(writer, text.len(), u32::MAX as usize)?;
+    writer.write_all(text.as_bytes())
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut buf = [0u8; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u16<R: Read>(reader: &mut R) -> io::Result<u16> {
+    let mut buf = [0u8; 2];
+    reader.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_card<R: Read>(reader: &mut R) -> io::Result<Card> {
+    Card::from_u8(read_u8(reader)?)
+}
+
+fn read_str<R: Read>(reader: &mut R) -> io::Result<String> {
+    let len = read_u32(reader)?;
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len as usize {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    String::from_utf8(bytes).map_err(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() -> io::Result<()> {
+        let mut session = Session::exhaustive();
+        session.version = Version::CURRENT;
+        session.tables[0].betting_structure = BettingStructure::PotLimit;
+        let TableEvent::Hand(hand) = &mut session.tables[0].events[0] else {
+            panic!("Expected a hand");
+        };
+        hand.fees.push(Fee {
+            fee_type: FeeType::Rake,
+            amount: 15,
+            seat: None,
+        });
+        hand.fees.push(Fee {
+            fee_type: FeeType::InsurancePayout,
+            amount: 200,
+            seat: Some(1),
+        });
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        assert!(HeirBin.sniff(&bytes));
+        assert_eq!(read_session(&mut bytes.as_slice())?, session);
+        // Sessions before version 0.3 only hold no-limit games.
+        session.version = Version::new(0, 2).expect("Valid version");
+        assert!(write_session(&mut Vec::new(), &session).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tournament_round_trip() -> io::Result<()> {
+        let mut session = Session::sit_and_go();
+        session.version = Version::CURRENT;
+        session.tournament.as_mut().expect("Tournament").finishes = vec![Finish {
+            player_id: 1,
+            position: 1,
+            prize: 2000,
+        }];
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        assert_eq!(read_session(&mut bytes.as_slice())?, session);
+        // Sessions before version 0.2 have no tournament.
+        session.version = Version::new(0, 1).expect("Valid version");
+        assert!(write_session(&mut Vec::new(), &session).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_variant_round_trip() -> io::Result<()> {
+        for variant in [Variant::Omaha, Variant::Omaha5, Variant::Omaha6] {
+            let session = Session::omaha(variant);
+            let mut bytes = Vec::new();
+            write_session(&mut bytes, &session)?;
+            assert_eq!(read_session(&mut bytes.as_slice())?, session);
+        }
+        // Sessions before version 0.4 have no variant to write.
+        let mut session = Session::omaha(Variant::Omaha);
+        session.version = Version::new(0, 3).expect("Valid version");
+        assert!(write_session(&mut Vec::new(), &session).is_err());
+        let mut session = Session::exhaustive();
+        session.version = Version::CURRENT;
+        let TableEvent::Hand(hand) = &mut session.tables[0].events[0] else {
+            panic!("Expected a hand");
+        };
+        hand.hole_cards[0].push(Card::KingDiamonds);
+        assert!(write_session(&mut Vec::new(), &session).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_runouts_round_trip() -> io::Result<()> {
+        let mut session = Session::exhaustive();
+        session.version = Version::CURRENT;
+        let TableEvent::Hand(hand) = &mut session.tables[0].events[0] else {
+            panic!("Expected a hand");
+        };
+        hand.runouts.push([
+            Card::ThreeClubs,
+            Card::ThreeHearts,
+            Card::KingDiamonds,
+            Card::Xx,
+            Card::Xx,
+        ]);
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        assert_eq!(read_session(&mut bytes.as_slice())?, session);
+        // Sessions before version 0.5 have a single board per hand.
+        session.version = Version::new(0, 4).expect("Valid version");
+        assert!(write_session(&mut Vec::new(), &session).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bomb_pot_round_trip() -> io::Result<()> {
+        let mut session = Session::exhaustive();
+        session.version = Version::CURRENT;
+        let TableEvent::Hand(hand) = &mut session.tables[0].events[0] else {
+            panic!("Expected a hand");
+        };
+        hand.bomb_pot = Some(100);
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        assert_eq!(read_session(&mut bytes.as_slice())?, session);
+        // Sessions before version 0.6 have no bomb pots.
+        session.version = Version::new(0, 5).expect("Valid version");
+        assert!(write_session(&mut Vec::new(), &session).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_clock_round_trip() -> io::Result<()> {
+        let mut session = Session::exhaustive();
+        session.version = Version::CURRENT;
+        let TableEvent::Hand(hand) = &mut session.tables[0].events[0] else {
+            panic!("Expected a hand");
+        };
+        let start = hand.timestamp * 1000;
+        hand.actions[0].timestamp = Some(start + 1500);
+        hand.actions[2].timestamp = Some(start + 32_000);
+        hand.clock_events = vec![
+            ClockEvent {
+                event_type: ClockEventType::TimeBank,
+                seat: 0,
+                action: 1,
+                timestamp: Some(start + 16_000),
+            },
+            ClockEvent {
+                event_type: ClockEventType::Timeout,
+                seat: 0,
+                action: 4,
+                timestamp: None,
+            },
+        ];
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        assert_eq!(read_session(&mut bytes.as_slice())?, session);
+
+        // Times are deltas, so they cannot go backwards.
+        let TableEvent::Hand(hand) = &mut session.tables[0].events[0] else {
+            panic!("Expected a hand");
+        };
+        hand.actions[2].timestamp = Some(start);
+        assert!(write_session(&mut Vec::new(), &session).is_err());
+        // Sessions before version 0.7 have no times.
+        session.version = Version::new(0, 6).expect("Valid version");
+        assert!(write_session(&mut Vec::new(), &session).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_annotations_round_trip() -> io::Result<()> {
+        let mut session = Session::exhaustive();
+        session.version = Version::CURRENT;
+        session.annotations = Annotations::example();
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        assert_eq!(read_session(&mut bytes.as_slice())?, session);
+        // Sessions without tables go straight from the tournament flag to the annotations.
+        session.tables.clear();
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        assert_eq!(read_session(&mut bytes.as_slice())?, session);
+        // Sessions before version 0.8 have no annotations.
+        session.version = Version::new(0, 7).expect("Valid version");
+        assert!(write_session(&mut Vec::new(), &session).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_invalid() -> io::Result<()> {
+        let mut session = Session::sit_and_go();
+        session.version = Version::CURRENT;
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        assert_eq!(read_session(&mut bytes.as_slice())?, session);
+        // Truncated files are errors, not older sessions.
+        for len in [bytes.len() - 2, bytes.len() - 4, bytes.len() - 40, 20] {
+            assert!(read_session(&mut &bytes[..len]).is_err());
+        }
+        assert!(read_session(&mut &b"HEIM\x01"[..]).is_err());
+        // Versions newer than this crate writes are rejected, even minor ones.
+        let (major, minor) = (Version::CURRENT.major(), Version::CURRENT.minor());
+        for (major, minor) in [(major + 1, 0), (major, minor + 1)] {
+            bytes[4] = (major << 4) | minor;
+            let error = read_session(&mut bytes.as_slice()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert!(error
+                .to_string()
+                .starts_with("Unsupported .heir.bin version"));
+        }
+        Ok(())
+    }
+}
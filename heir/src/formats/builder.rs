@@ -0,0 +1,522 @@
+use crate::engine::replay::{Replay, Stakes};
+use crate::engine::state::TableState;
+use crate::formats::{site_id, Import};
+use crate::game::{
+    Action, ActionType, Annotations, BettingStructure, Decimal, Fee, Hand, Id, LevelChange, Player,
+    SeatUpdate, Session, StackUpdate, Table, TableEvent, TableSize, Timestamp, Variant,
+};
+use crate::types::card::Card;
+use crate::types::version::Version;
+
+/// Splits a line such as "Player One raises 2€ to 4€" into the player's name, the first
+/// of the given verbs found after it, and the rest of the line.
+pub(crate) fn split_actor<'a>(
+    line: &'a str,
+    verbs: &[&'a str],
+) -> Option<(&'a str, &'a str, &'a str)> {
+    verbs
+        .iter()
+        .filter_map(|&verb| {
+            let pattern = format!(" {}", verb);
+            line.find(&pattern).and_then(|i| {
+                let rest = &line[i + pattern.len()..];
+                (rest.is_empty() || rest.starts_with(' ')).then(|| (i, verb, rest.trim_start()))
+            })
+        })
+        .min_by_key(|&(i, _, _)| i)
+        .map(|(i, verb, rest)| (&line[..i], verb, rest))
+}
+
+/// A bet or raise as written by a site, before it is resolved against the betting state.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum SiteAction {
+    Fold,
+    Check,
+    Call,
+    /// A check, or a call when facing a bet.
+    CheckOrCall,
+    /// A bet or raise to a street total.
+    To(Decimal),
+    /// A bet or raise adding the given amount to the seat's street contribution.
+    By(Decimal),
+}
+
+/// Builds a [`Hand`] from site hand history lines, replaying it as it goes so that site
+/// amounts are converted into heir actions and unrepresentable hands are rejected.
+pub(crate) struct HandBuilder {
+    table: Table,
+    stakes: Stakes,
+    seats: Vec<Option<Player>>,
+    replay: Replay,
+    hand: Hand,
+    posted: Vec<(u8, Decimal)>,
+    blinds_checked: bool,
+    shown: Vec<(u8, Vec<Card>)>,
+    collected: Vec<(u8, Decimal)>,
+}
+
+impl HandBuilder {
+    pub fn new(
+        id: Id,
+        timestamp: Timestamp,
+        button: u8,
+        blinds: Vec<Decimal>,
+        seats: Vec<Option<Player>>,
+    ) -> Result<Self, String> {
+        let table = game_table(seats.len() as TableSize, blinds);
+        let stakes = Stakes::table(&table);
+        let replay = Replay::start(&table, &seats, button, &[]).map_err(|e| e.to_string())?;
+        Ok(HandBuilder {
+            table,
+            stakes,
+            seats,
+            replay,
+            hand: Hand {
+                id,
+                button_position: button,
+                hole_cards: Vec::new(),
+                actions: Vec::new(),
+                timestamp,
+                board: [Card::Xx; 5],
+                runouts: Vec::new(),
+                bomb_pot: None,
+                fees: Vec::new(),
+                clock_events: Vec::new(),
+            },
+            posted: Vec::new(),
+            blinds_checked: false,
+            shown: Vec::new(),
+            collected: Vec::new(),
+        })
+    }
+
+    /// Deals the hand with an ante from every player, before anything else is recorded.
+    pub fn ante(mut self, ante: Decimal) -> Result<Self, String> {
+        self.stakes.ante = ante;
+        self.restart()?;
+        Ok(self)
+    }
+
+    /// Deals the hand with the given betting structure, before anything else is recorded.
+    pub fn betting_structure(
+        mut self,
+        betting_structure: BettingStructure,
+    ) -> Result<Self, String> {
+        self.table.betting_structure = betting_structure;
+        self.restart()?;
+        Ok(self)
+    }
+
+    /// Deals the hand as the given variant, before anything else is recorded.
+    pub fn variant(mut self, variant: Variant) -> Result<Self, String> {
+        self.table.variant = variant;
+        self.restart()?;
+        Ok(self)
+    }
+
+    /// Deals the hand again at the builder's table and stakes.
+    fn restart(&mut self) -> Result<(), String> {
+        self.replay = Replay::start_with(
+            &self.table,
+            &self.stakes,
+            &self.seats,
+            self.hand.button_position,
+            &[],
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// The seat of the named player.
+    pub fn seat(&self, name: &str) -> Result<u8, String> {
+        self.seats
+            .iter()
+            .position(|p| p.as_ref().is_some_and(|p| p.name == name))
+            .map(|i| i as u8)
+            .ok_or_else(|| format!("{} is not seated.", name))
+    }
+
+    /// Records a blind posted by the named player.
+    pub fn post(&mut self, name: &str, amount: Decimal) -> Result<(), String> {
+        let seat = self.seat(name)?;
+        self.posted.push((seat, amount));
+        Ok(())
+    }
+
+    /// Applies the named player's action, which must be theirs to take.
+    pub fn act(&mut self, name: &str, action: SiteAction, all_in: bool) -> Result<(), String> {
+        self.check_blinds()?;
+        let seat = self.seat(name)?;
+        if self.replay.to_act() != Some(seat) {
+            return Err(format!("{} acts out of turn.", name));
+        }
+        let state = self.replay.seat(seat).expect("Seat to act is dealt");
+        let committed = state.street_committed;
+        let available = committed + state.stack;
+        let current_bet = self.replay.current_bet();
+        let (action_type, to) = match action {
+            SiteAction::Fold => (ActionType::Fold, committed),
+            SiteAction::Check => (ActionType::Check, committed),
+            SiteAction::CheckOrCall if current_bet == committed => (ActionType::Check, committed),
+            SiteAction::Call | SiteAction::CheckOrCall => {
+                let to = current_bet.min(available);
+                let all_in = all_in || to == available;
+                (all_in_or(ActionType::Call, all_in), to)
+            }
+            SiteAction::To(_) | SiteAction::By(_) => {
+                let to = match action {
+                    SiteAction::To(to) => to,
+                    SiteAction::By(amount) => committed + amount,
+                    _ => unreachable!(),
+                };
+                let base = if current_bet == 0 {
+                    ActionType::Bet
+                } else {
+                    ActionType::Raise
+                };
+                (all_in_or(base, all_in || to == available), to)
+            }
+        };
+        let action = Action {
+            action_type,
+            bet_amount: u32::try_from(to).map_err(|_| format!("{} bets too much.", name))?,
+            timestamp: None,
+        };
+        self.replay.apply(&action).map_err(|e| e.to_string())?;
+        self.hand.actions.push(action);
+        Ok(())
+    }
+
+    /// Sets the board from its first card onwards.
+    pub fn board(&mut self, cards: &[Card]) {
+        for (slot, card) in self.hand.board.iter_mut().zip(cards) {
+            *slot = *card;
+        }
+    }
+
+    /// Sets a further board of a hand run more than once from its first card onwards,
+    /// repeating the cards it shares with the first board. Runout 0 is the second board.
+    pub fn runout(&mut self, runout: usize, cards: &[Card]) {
+        if self.hand.runouts.len() <= runout {
+            self.hand.runouts.resize(runout + 1, [Card::Xx; 5]);
+        }
+        for (slot, card) in self.hand.runouts[runout].iter_mut().zip(cards) {
+            *slot = *card;
+        }
+    }
+
+    /// Records the named player's hole cards.
+    pub fn show(&mut self, name: &str, cards: &[Card]) -> Result<(), String> {
+        let seat = self.seat(name)?;
+        if cards.len() != self.table.variant.hole_cards() {
+            return Err(format!("{} shows {} hole cards.", name, cards.len()));
+        }
+        self.shown.retain(|(s, _)| *s != seat);
+        self.shown.push((seat, cards.to_vec()));
+        Ok(())
+    }
+
+    /// Records an amount the site says the named player collected, to check against the replay.
+    pub fn collect(&mut self, name: &str, amount: Decimal) -> Result<(), String> {
+        let seat = self.seat(name)?;
+        self.collected.push((seat, amount));
+        Ok(())
+    }
+
+    pub fn fee(&mut self, fee: Fee) {
+        self.hand.fees.push(fee);
+    }
+
+    /// The replay of the hand so far.
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// The hand so far, without checking that it is complete.
+    pub fn into_hand(mut self) -> Hand {
+        self.set_hole_cards();
+        self.hand
+    }
+
+    /// Completes the hand, checking it replays.
+    pub fn finish(mut self) -> Result<BuiltHand, String> {
+        self.check_blinds()?;
+        self.set_hole_cards();
+        let replay = Replay::hand_with(&self.table, &self.stakes, &self.seats, &self.hand)
+            .map_err(|e| e.to_string())?;
+
+        let mut warnings = Vec::new();
+        if !self.collected.is_empty() {
+            for seat in &replay.seats {
+                let site: Decimal = self
+                    .collected
+                    .iter()
+                    .filter(|(s, _)| *s == seat.seat)
+                    .map(|(_, amount)| amount)
+                    .sum();
+                let replayed = replay.collected(seat.seat);
+                if site != replayed {
+                    warnings.push(format!(
+                        "Hand {}: {} collected {} but the replay awards {}.",
+                        self.hand.id, seat.player.name, site, replayed
+                    ));
+                }
+            }
+        }
+        Ok(BuiltHand {
+            table: self.table,
+            stakes: self.stakes,
+            seats: self.seats,
+            hand: self.hand,
+            replay,
+            warnings,
+        })
+    }
+
+    /// Sets the hole cards shown so far, dropping trailing unknown ones.
+    fn set_hole_cards(&mut self) {
+        let mut hole_cards: Vec<Vec<Card>> = self
+            .replay
+            .seats
+            .iter()
+            .map(|s| {
+                self.shown
+                    .iter()
+                    .find(|(seat, _)| *seat == s.seat)
+                    .map_or_else(
+                        || vec![Card::Unknown; self.table.variant.hole_cards()],
+                        |(_, cards)| cards.clone(),
+                    )
+            })
+            .collect();
+        while hole_cards
+            .last()
+            .is_some_and(|cards| cards.iter().all(|&c| c == Card::Unknown))
+        {
+            hole_cards.pop();
+        }
+        self.hand.hole_cards = hole_cards;
+    }
+
+    fn check_blinds(&mut self) -> Result<(), String> {
+        if self.blinds_checked {
+            return Ok(());
+        }
+        let expected: Vec<(u8, Decimal)> = self
+            .replay
+            .blinds
+            .iter()
+            .map(|b| (b.seat, b.amount))
+            .collect();
+        if self.posted != expected {
+            return Err(format!(
+                "Posted blinds {:?} differ from the expected {:?}.",
+                self.posted, expected
+            ));
+        }
+        self.blinds_checked = true;
+        Ok(())
+    }
+}
+
+/// A completed [`Hand`] with the game and stakes it was played at and the seats it was
+/// dealt to.
+pub(crate) struct BuiltHand {
+    /// The game the hand was played at, as a [`Table`] without a name or events.
+    pub table: Table,
+    pub stakes: Stakes,
+    pub seats: Vec<Option<Player>>,
+    pub hand: Hand,
+    pub replay: Replay,
+    /// Disagreements between the site's results and the replay.
+    pub warnings: Vec<String>,
+}
+
+/// A nameless no-limit hold'em [`Table`] without events, which builders adjust to the game
+/// being imported.
+fn game_table(table_size: TableSize, blinds: Vec<Decimal>) -> Table {
+    Table {
+        id: 0,
+        name: String::new(),
+        location: String::new(),
+        table_size,
+        rake_percentage: 0,
+        rake_cap: 0,
+        blinds,
+        betting_structure: BettingStructure::NoLimit,
+        variant: Variant::Holdem,
+        initial_context: Vec::new(),
+        events: Vec::new(),
+    }
+}
+
+fn all_in_or(action_type: ActionType, all_in: bool) -> ActionType {
+    if all_in {
+        ActionType::AllIn
+    } else {
+        action_type
+    }
+}
+
+/// The [`SeatUpdate`]s and [`StackUpdate`]s that take a table's seats from `before` to `after`.
+pub(crate) fn seat_updates(before: &[Option<Player>], after: &[Option<Player>]) -> Vec<TableEvent> {
+    let mut events = Vec::new();
+    for seat in 0..before.len().max(after.len()) {
+        let before = before.get(seat).cloned().flatten();
+        let after = after.get(seat).cloned().flatten();
+        let event = match (&before, &after) {
+            (Some(b), Some(a)) if b.id == a.id && b.stack == a.stack => continue,
+            (Some(b), Some(a)) if b.id == a.id => TableEvent::StackUpdate(StackUpdate {
+                seat: seat as u8,
+                stack: u32::try_from(a.stack).unwrap_or(u32::MAX),
+            }),
+            (None, None) => continue,
+            _ => TableEvent::SeatUpdate(SeatUpdate {
+                seat: seat as u8,
+                player: after.clone(),
+            }),
+        };
+        events.push(event);
+    }
+    events
+}
+
+/// Groups imported hands into [`Table`]s, emitting seat and stack updates between hands.
+pub(crate) struct SessionBuilder {
+    site: &'static str,
+    tables: Vec<Table>,
+    states: Vec<TableState>,
+    hero_id: Option<Id>,
+    warnings: Vec<String>,
+}
+
+impl SessionBuilder {
+    pub fn new(site: &'static str) -> Self {
+        SessionBuilder {
+            site,
+            tables: Vec::new(),
+            states: Vec::new(),
+            hero_id: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// A [`Player`] with an [`Id`] derived from their name on the site.
+    pub fn player(&self, name: &str, stack: Decimal) -> Player {
+        Player {
+            id: site_id(self.site, name),
+            name: name.to_string(),
+            stack,
+        }
+    }
+
+    pub fn hero(&mut self, name: &str) {
+        self.hero_id = Some(site_id(self.site, name));
+    }
+
+    pub fn warn(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    /// Adds a hand to the table with the given name and blinds, creating it if needed.
+    pub fn push(
+        &mut self,
+        table_name: &str,
+        table_size: TableSize,
+        blinds: &[Decimal],
+        seats: Vec<Option<Player>>,
+        hand: Hand,
+    ) {
+        let game = game_table(table_size, blinds.to_vec());
+        let stakes = Stakes::table(&game);
+        self.push_at(table_name, &game, &stakes, seats, hand);
+    }
+
+    /// Adds a built hand to the table with the given name and the hand's game and blinds,
+    /// creating it if needed, after a [`LevelChange`] if the table is not already at the
+    /// hand's stakes.
+    pub fn push_built(&mut self, table_name: &str, built: BuiltHand) {
+        let BuiltHand {
+            table,
+            stakes,
+            seats,
+            hand,
+            warnings,
+            ..
+        } = built;
+        self.warnings.extend(warnings);
+        self.push_at(table_name, &table, &stakes, seats, hand);
+    }
+
+    fn push_at(
+        &mut self,
+        table_name: &str,
+        game: &Table,
+        stakes: &Stakes,
+        seats: Vec<Option<Player>>,
+        hand: Hand,
+    ) {
+        let index = match self.tables.iter().position(|t| {
+            t.name == table_name
+                && t.blinds == game.blinds
+                && t.table_size == game.table_size
+                && t.betting_structure == game.betting_structure
+                && t.variant == game.variant
+        }) {
+            Some(index) => index,
+            None => {
+                let table = Table {
+                    id: site_id(self.site, &format!("{} {:?}", table_name, game.blinds)),
+                    name: table_name.to_string(),
+                    location: self.site.to_string(),
+                    initial_context: seats.iter().map_while(|p| p.clone()).collect(),
+                    ..game.clone()
+                };
+                self.states.push(TableState::new(&table));
+                self.tables.push(table);
+                self.tables.len() - 1
+            }
+        };
+        let table = &mut self.tables[index];
+        let state = &mut self.states[index];
+        if state.stakes != *stakes {
+            let level = table
+                .events
+                .iter()
+                .filter(|e| matches!(e, TableEvent::LevelChange(_)))
+                .count() as u32;
+            let event = TableEvent::LevelChange(LevelChange {
+                level: level + 1,
+                blinds: stakes.blinds.clone(),
+                ante: stakes.ante,
+            });
+            let _ = state.apply(table, &event);
+            table.events.push(event);
+        }
+        for event in seat_updates(&state.seats, &seats) {
+            // Updates cannot fail, only hands are replayed.
+            let _ = state.apply(table, &event);
+            table.events.push(event);
+        }
+        let event = TableEvent::Hand(hand);
+        if let Err(e) = state.apply(table, &event) {
+            self.warnings.push(e.to_string());
+        }
+        table.events.push(event);
+    }
+
+    pub fn finish(self) -> Import {
+        Import {
+            session: Session {
+                version: Version::CURRENT,
+                id: 0,
+                name: format!("{} import", self.site),
+                tables: self.tables,
+                hero_id: self.hero_id.unwrap_or(0),
+                tournament: None,
+                annotations: Annotations::default(),
+            },
+            warnings: self.warnings,
+        }
+    }
+}
@@ -0,0 +1,481 @@
+use crate::formats::builder::{BuiltHand, HandBuilder, SessionBuilder, SiteAction};
+use crate::formats::{
+    bracketed_cards, parse_amount, parse_datetime, site_id, sniff_text, HandHistoryImporter, Import,
+};
+use crate::game::{Decimal, Fee, FeeType, Id, Player, TableSize};
+use std::io::{self, Read};
+
+const SITE: &str = "GGPoker";
+
+/// The [`HandHistoryImporter`] for GGPoker hand histories.
+pub struct GgPoker;
+
+impl HandHistoryImporter for GgPoker {
+    fn name(&self) -> &'static str {
+        SITE
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        sniff_text(head).starts_with("Poker Hand #")
+    }
+
+    fn import(&self, reader: &mut dyn Read) -> io::Result<Import> {
+        import(reader)
+    }
+}
+
+/// Imports GGPoker No Limit Hold'em cash game hand histories.
+///
+/// Rake, jackpot, promotion and tax deductions, cash drops and all-in insurance are kept as
+/// [`Fee`]s. Hands that cannot be represented (e.g. antes or dead blinds) are skipped with a
+/// warning. Hands run multiple times keep their further boards as runouts.
+pub fn import<R: Read>(mut reader: R) -> io::Result<Import> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut session = SessionBuilder::new(SITE);
+    let mut block: Vec<&str> = Vec::new();
+    for line in text.lines().map(|l| l.trim_end_matches('\r')) {
+        if line.starts_with("Poker Hand #") && !block.is_empty() {
+            import_hand(&mut session, &block);
+            block.clear();
+        }
+        if !line.trim().is_empty() {
+            block.push(line);
+        }
+    }
+    if !block.is_empty() {
+        import_hand(&mut session, &block);
+    }
+    Ok(session.finish())
+}
+
+fn import_hand(session: &mut SessionBuilder, lines: &[&str]) {
+    if let Err(e) = parse_hand(session, lines) {
+        let id = lines[0]
+            .strip_prefix("Poker Hand #")
+            .and_then(|h| h.split(':').next())
+            .unwrap_or(lines[0]);
+        session.warn(format!("Hand {} skipped: {}", id, e));
+    }
+}
+
+/// The parts of a hand's header and seat list.
+struct Header<'a> {
+    id: Id,
+    timestamp: u64,
+    blinds: Vec<Decimal>,
+    table: &'a str,
+    table_size: TableSize,
+    button: u8,
+    seats: Vec<Option<Player>>,
+}
+
+fn parse_header<'a>(session: &SessionBuilder, lines: &[&'a str]) -> Result<Header<'a>, String> {
+    let malformed = |line: &str| format!("Malformed line {:?}.", line);
+    let header = lines[0];
+    let (hand_id, rest) = header
+        .strip_prefix("Poker Hand #")
+        .and_then(|h| h.split_once(':'))
+        .ok_or_else(|| malformed(header))?;
+    let id = hand_id
+        .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+        .parse()
+        .unwrap_or_else(|_| site_id(SITE, hand_id));
+    if !rest.contains("Hold'em No Limit") {
+        return Err("Only No Limit Hold'em is supported.".to_string());
+    }
+    let (game, date) = rest.rsplit_once(" - ").ok_or_else(|| malformed(header))?;
+    let timestamp = parse_datetime(date).map_err(|e| e.to_string())?;
+    let stakes = game
+        .rsplit_once('(')
+        .and_then(|(_, s)| s.split_once(')'))
+        .map(|(s, _)| s)
+        .ok_or_else(|| malformed(header))?;
+    let blinds = stakes
+        .split('/')
+        .map(|b| parse_amount(b.trim_end_matches(" USD")))
+        .collect::<io::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    let table_line = lines.get(1).copied().unwrap_or_default();
+    let (table, rest) = table_line
+        .strip_prefix("Table '")
+        .and_then(|t| t.rsplit_once('\''))
+        .ok_or_else(|| malformed(table_line))?;
+    let (size, rest) = rest
+        .trim()
+        .split_once("-max")
+        .ok_or_else(|| malformed(table_line))?;
+    let table_size: TableSize = size.parse().map_err(|_| malformed(table_line))?;
+    let button = rest
+        .split_once('#')
+        .and_then(|(_, b)| b.split_whitespace().next())
+        .and_then(|b| b.parse::<u8>().ok())
+        .filter(|&b| b >= 1)
+        .ok_or_else(|| malformed(table_line))?
+        - 1;
+
+    let mut seats = vec![None; table_size as usize];
+    for line in lines[2..].iter().take_while(|l| l.starts_with("Seat ")) {
+        let (number, rest) = line[5..].split_once(": ").ok_or_else(|| malformed(line))?;
+        let (name, chips) = rest.rsplit_once(" (").ok_or_else(|| malformed(line))?;
+        let (chips, status) = chips
+            .split_once(" in chips)")
+            .ok_or_else(|| malformed(line))?;
+        if status.contains("sitting out") {
+            continue;
+        }
+        let seat: usize = number.parse().map_err(|_| malformed(line))?;
+        let slot = seat
+            .checked_sub(1)
+            .and_then(|s| seats.get_mut(s))
+            .ok_or_else(|| malformed(line))?;
+        *slot = Some(session.player(name, parse_amount(chips).map_err(|e| e.to_string())?));
+    }
+    Ok(Header {
+        id,
+        timestamp,
+        blinds,
+        table,
+        table_size,
+        button,
+        seats,
+    })
+}
+
+fn parse_hand(session: &mut SessionBuilder, lines: &[&str]) -> Result<(), String> {
+    let header = parse_header(session, lines)?;
+    let mut hand = HandBuilder::new(
+        header.id,
+        header.timestamp,
+        header.button,
+        header.blinds.clone(),
+        header.seats,
+    )?;
+    let amount = |text: &str| parse_amount(text).map_err(|e| e.to_string());
+    let cards = |line: &str| bracketed_cards(line).map_err(|e| e.to_string());
+    let mut hero = None;
+    let mut summary = false;
+
+    for line in &lines[2..] {
+        if let Some(street) = line.strip_prefix("*** ") {
+            let (name, _) = street.split_once(" ***").unwrap_or((street, ""));
+            match name.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["SUMMARY"] => summary = true,
+                ["FLOP" | "TURN" | "RIVER"] | ["FIRST", "FLOP" | "TURN" | "RIVER"] => {
+                    hand.board(&cards(line)?)
+                }
+                ["SECOND", "FLOP" | "TURN" | "RIVER"] => hand.runout(0, &cards(line)?),
+                ["THIRD", "FLOP" | "TURN" | "RIVER"] => hand.runout(1, &cards(line)?),
+                _ => {}
+            }
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("Dealt to ") {
+            if let Some((name, _)) = rest.split_once(" [") {
+                hand.show(name, &cards(line)?)?;
+                hero = Some(name.to_string());
+            }
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("Cash Drop to Pot : total ") {
+            hand.fee(Fee {
+                fee_type: FeeType::CashDrop,
+                amount: amount(rest)?,
+                seat: None,
+            });
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("Total pot ") {
+            for part in rest.split(" | ").skip(1) {
+                let (kind, value) = part.split_once(' ').unwrap_or((part, "0"));
+                let fee_type = match kind {
+                    "Rake" => FeeType::Rake,
+                    "Jackpot" => FeeType::Jackpot,
+                    "Bingo" | "Fortune" => FeeType::Promotion,
+                    "Tax" => FeeType::Tax,
+                    _ => continue,
+                };
+                let value = amount(value)?;
+                if value > 0 {
+                    hand.fee(Fee {
+                        fee_type,
+                        amount: value,
+                        seat: None,
+                    });
+                }
+            }
+            continue;
+        }
+        if summary {
+            // Cards shown in the summary (e.g. "Seat 1: name (button) showed [Qd Qc] and lost").
+            if let Some((name, _)) = line
+                .strip_prefix("Seat ")
+                .and_then(|l| l.split_once(": "))
+                .and_then(|(_, rest)| rest.split_once(" showed ["))
+            {
+                let name = name.split(" (").next().unwrap_or(name);
+                hand.show(name, &cards(line)?)?;
+            }
+            continue;
+        }
+        if let Some((name, rest)) = line.split_once(" collected ") {
+            hand.collect(name, amount(rest.split(" from ").next().unwrap_or(rest))?)?;
+            continue;
+        }
+        let Some((name, action)) = line.rsplit_once(": ") else {
+            continue;
+        };
+        let all_in = action.ends_with(" and is all-in");
+        let action = action.trim_end_matches(" and is all-in");
+        let mut words = action.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some("posts"), Some("small" | "big" | "straddle")) => {
+                hand.post(name, amount(action.rsplit(' ').next().unwrap_or_default())?)?
+            }
+            (Some("posts"), _) => return Err(format!("Unsupported post {:?}.", action)),
+            (Some("folds"), _) => hand.act(name, SiteAction::Fold, all_in)?,
+            (Some("checks"), _) => hand.act(name, SiteAction::Check, all_in)?,
+            (Some("calls"), _) => hand.act(name, SiteAction::Call, all_in)?,
+            (Some("bets"), Some(value)) => {
+                hand.act(name, SiteAction::By(amount(value)?), all_in)?
+            }
+            (Some("raises"), _) => {
+                let to = action.rsplit(' ').next().unwrap_or_default();
+                hand.act(name, SiteAction::To(amount(to)?), all_in)?
+            }
+            (Some("shows"), _) => hand.show(name, &cards(action)?)?,
+            (Some("Pays"), _) | (Some("Receives"), _) => {
+                let value = action
+                    .rsplit_once('(')
+                    .map_or(action, |(_, v)| v.trim_end_matches(')'));
+                let fee_type = if action.starts_with("Pays") {
+                    FeeType::InsurancePremium
+                } else {
+                    FeeType::InsurancePayout
+                };
+                hand.fee(Fee {
+                    fee_type,
+                    amount: amount(value)?,
+                    seat: Some(hand.seat(name)?),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let BuiltHand {
+        seats,
+        hand,
+        warnings,
+        ..
+    } = hand.finish()?;
+    if let Some(hero) = hero {
+        session.hero(&hero);
+    }
+    for warning in warnings {
+        session.warn(warning);
+    }
+    session.push(header.table, header.table_size, &header.blinds, seats, hand);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::state::TableState;
+    use crate::game::{ActionType, TableEvent};
+    use crate::types::card::Card;
+
+    const HISTORY: &str = "\
+Poker Hand #HD100001: Hold'em No Limit ($0.05/$0.1) - 2024/01/15 12:34:56
+Table 'NLHGold12' 6-max Seat #1 is the button
+Seat 1: 7a8b9c0d ($10 in chips)
+Seat 2: Hero ($10.52 in chips)
+Seat 3: 1f2e3d4c ($12.34 in chips)
+Seat 5: ab12cd34 ($9.80 in chips)
+Hero: posts small blind $0.05
+1f2e3d4c: posts big blind $0.1
+*** HOLE CARDS ***
+Dealt to 7a8b9c0d
+Dealt to Hero [Ah Kd]
+Dealt to 1f2e3d4c
+Dealt to ab12cd34
+ab12cd34: folds
+7a8b9c0d: raises $0.2 to $0.3
+Hero: raises $0.7 to $1
+1f2e3d4c: folds
+7a8b9c0d: calls $0.7
+*** FLOP *** [Ks 7d 2c]
+Hero: bets $1.2
+7a8b9c0d: calls $1.2
+*** TURN *** [Ks 7d 2c] [9h]
+Hero: checks
+7a8b9c0d: checks
+*** RIVER *** [Ks 7d 2c 9h] [3s]
+Hero: bets $2
+7a8b9c0d: calls $2
+*** SHOWDOWN ***
+Hero: shows [Ah Kd] (a pair of Kings)
+7a8b9c0d: shows [Qd Qc] (a pair of Queens)
+Hero collected $8.12 from pot
+*** SUMMARY ***
+Total pot $8.5 | Rake $0.33 | Jackpot $0.05 | Bingo $0 | Fortune $0 | Tax $0
+Board [Ks 7d 2c 9h 3s]
+Seat 1: 7a8b9c0d (button) showed [Qd Qc] and lost with a pair of Queens
+Seat 2: Hero (small blind) showed [Ah Kd] and won ($8.12) with a pair of Kings
+Seat 3: 1f2e3d4c (big blind) folded before Flop
+Seat 5: ab12cd34 folded before Flop (didn't bet)
+
+Poker Hand #HD100002: Hold'em No Limit ($0.05/$0.1) - 2024/01/15 12:35:40
+Table 'NLHGold12' 6-max Seat #2 is the button
+Seat 1: 7a8b9c0d ($5.8 in chips)
+Seat 2: Hero ($14.44 in chips)
+Seat 3: 1f2e3d4c ($12.24 in chips)
+Seat 6: c0ffee12 ($10 in chips)
+1f2e3d4c: posts small blind $0.05
+c0ffee12: posts big blind $0.1
+*** HOLE CARDS ***
+Dealt to Hero [2c 2d]
+7a8b9c0d: raises $0.15 to $0.25
+Hero: calls $0.25
+1f2e3d4c: folds
+c0ffee12: raises $0.75 to $1
+7a8b9c0d: raises $4.8 to $5.8 and is all-in
+Hero: calls $5.55
+c0ffee12: folds
+7a8b9c0d: Pays Cashout Risk ($0.5)
+*** FLOP *** [As 8h 4c]
+*** TURN *** [As 8h 4c] [Jd]
+*** RIVER *** [As 8h 4c Jd] [6s]
+Cash Drop to Pot : total $1
+*** SHOWDOWN ***
+7a8b9c0d: shows [Qs Qh] (a pair of Queens)
+Hero: shows [2c 2d] (a pair of Deuces)
+7a8b9c0d collected $13 from pot
+*** SUMMARY ***
+Total pot $12.65 | Rake $0.6 | Jackpot $0.05 | Bingo $0 | Fortune $0 | Tax $0
+Board [As 8h 4c Jd 6s]
+
+Poker Hand #HD100003: Hold'em No Limit ($0.05/$0.1) - 2024/01/15 12:36:10
+Table 'NLHGold12' 6-max Seat #3 is the button
+Seat 1: 7a8b9c0d ($12.5 in chips)
+Seat 2: Hero ($8.64 in chips)
+7a8b9c0d: posts the ante $0.02
+Hero: posts the ante $0.02
+";
+
+    #[test]
+    fn test_import_session() -> io::Result<()> {
+        let import = import(HISTORY.as_bytes())?;
+        assert_eq!(import.warnings.len(), 1, "{:?}", import.warnings);
+        assert!(import.warnings[0].starts_with("Hand HD100003 skipped"));
+
+        let session = import.session;
+        assert_eq!(session.hero_id, site_id(SITE, "Hero"));
+        assert_eq!(session.tables.len(), 1);
+        let table = &session.tables[0];
+        assert_eq!(table.name, "NLHGold12");
+        assert_eq!(table.blinds, vec![5, 10]);
+        assert_eq!(table.initial_context.len(), 3);
+        // Seat 5 sits beyond the contiguous initial context, so it joins through an update.
+        assert_eq!(table.events.len(), 5);
+        assert!(matches!(
+            &table.events[0],
+            TableEvent::SeatUpdate(update) if update.seat == 4 && update.player.is_some()
+        ));
+        assert!(matches!(
+            &table.events[2],
+            TableEvent::SeatUpdate(update) if update.seat == 4 && update.player.is_none()
+        ));
+
+        let TableEvent::Hand(first) = &table.events[1] else {
+            panic!("Expected a hand");
+        };
+        assert_eq!(first.id, 100001);
+        assert_eq!(first.button_position, 0);
+        assert_eq!(first.actions[2].action_type, ActionType::Raise);
+        assert_eq!(first.actions[2].bet_amount, 100);
+        assert_eq!(first.hole_cards[0], [Card::QueenDiamonds, Card::QueenClubs]);
+        assert_eq!(first.hole_cards[1], [Card::AceHearts, Card::KingDiamonds]);
+        assert_eq!(first.board[4], Card::ThreeSpades);
+        assert_eq!(first.fees.len(), 2);
+
+        let TableEvent::Hand(second) = &table.events[4] else {
+            panic!("Expected a hand");
+        };
+        assert_eq!(second.actions[4].action_type, ActionType::AllIn);
+        let fee_types: Vec<FeeType> = second.fees.iter().map(|f| f.fee_type).collect();
+        assert_eq!(
+            fee_types,
+            vec![
+                FeeType::InsurancePremium,
+                FeeType::CashDrop,
+                FeeType::Rake,
+                FeeType::Jackpot
+            ]
+        );
+
+        let mut state = TableState::new(table);
+        for event in &table.events {
+            state.apply(table, event).expect("Imported hands replay");
+        }
+        // 5.80 all-in, won 13.00 and paid 0.50 for insurance.
+        assert_eq!(state.seats[0].as_ref().unwrap().stack, 1_250);
+        assert_eq!(state.seats[1].as_ref().unwrap().stack, 864);
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_run_twice() -> io::Result<()> {
+        let history = "\
+Poker Hand #HD200001: Hold'em No Limit ($0.05/$0.1) - 2024/01/15 13:00:00
+Table 'NLHGold12' 6-max Seat #1 is the button
+Seat 1: aaaa ($1 in chips)
+Seat 2: bbbb ($1 in chips)
+aaaa: posts small blind $0.05
+bbbb: posts big blind $0.1
+*** HOLE CARDS ***
+aaaa: raises $0.9 to $1 and is all-in
+bbbb: calls $0.9 and is all-in
+Hand was run two times
+*** FIRST FLOP *** [2h 3h 4h]
+*** FIRST TURN *** [2h 3h 4h] [5h]
+*** FIRST RIVER *** [2h 3h 4h 5h] [9c]
+*** SECOND FLOP *** [7c 8c 9d]
+*** SECOND TURN *** [7c 8c 9d] [Td]
+*** SECOND RIVER *** [7c 8c 9d Td] [Jd]
+*** FIRST SHOWDOWN ***
+aaaa: shows [Ah Kh] (a straight flush, Ace to Five)
+bbbb: shows [Qc Qd] (a pair of Queens)
+aaaa collected $1 from pot
+*** SECOND SHOWDOWN ***
+bbbb collected $1 from pot
+*** SUMMARY ***
+Total pot $2 | Rake $0 | Jackpot $0 | Bingo $0 | Fortune $0 | Tax $0
+";
+        let import = import(history.as_bytes())?;
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        let table = &import.session.tables[0];
+        let TableEvent::Hand(hand) = &table.events[0] else {
+            panic!("Expected a hand");
+        };
+        assert_eq!(
+            hand.runouts,
+            vec![[
+                Card::SevenClubs,
+                Card::EightClubs,
+                Card::NineDiamonds,
+                Card::TenDiamonds,
+                Card::JackDiamonds
+            ]]
+        );
+        // Each board wins half the pot.
+        let replay = TableState::new(table)
+            .apply(table, &table.events[0])
+            .expect("Imported hands replay")
+            .expect("A hand");
+        assert_eq!((replay.collected(0), replay.collected(1)), (100, 100));
+        Ok(())
+    }
+}
@@ -0,0 +1,425 @@
+use crate::formats::builder::{BuiltHand, HandBuilder, SessionBuilder, SiteAction};
+use crate::formats::{parse_amount, parse_datetime, sniff_text, HandHistoryImporter, Import};
+use crate::game::{Fee, FeeType, TableSize};
+use crate::types::card::{Card, Rank, Suit};
+use std::io::{self, Read};
+
+const SITE: &str = "iPoker";
+
+/// The [`HandHistoryImporter`] for iPoker XML hand histories.
+pub struct IPoker;
+
+impl HandHistoryImporter for IPoker {
+    fn name(&self) -> &'static str {
+        SITE
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        sniff_text(head).contains("<session")
+    }
+
+    fn import(&self, reader: &mut dyn Read) -> io::Result<Import> {
+        import(reader)
+    }
+}
+
+/// Imports iPoker No Limit Hold'em XML hand histories.
+///
+/// Each `<session>` holds its table's `<game>`s, whose actions record the amount added.
+pub fn import<R: Read>(mut reader: R) -> io::Result<Import> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let root = Element::parse(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut session = SessionBuilder::new(SITE);
+    let sessions = if root.name == "session" {
+        vec![&root]
+    } else {
+        root.children("session").collect()
+    };
+    for xml in sessions {
+        let general = xml.child("general");
+        let value = |name: &str| general.and_then(|g| g.child(name)).map(|e| e.text.trim());
+        if let Some(hero) = value("nickname") {
+            session.hero(hero);
+        }
+        let table = Table {
+            name: value("tablename").unwrap_or_default(),
+            game: value("gametype").unwrap_or_default(),
+            size: value("tablesize").and_then(|s| s.parse().ok()),
+            small_blind: value("smallblind").unwrap_or_default(),
+            big_blind: value("bigblind").unwrap_or_default(),
+        };
+        for game in xml.children("game") {
+            if let Err(e) = parse_game(&mut session, &table, game) {
+                let id = game.attribute("gamecode").unwrap_or_default();
+                session.warn(format!("Hand {} skipped: {}", id, e));
+            }
+        }
+    }
+    Ok(session.finish())
+}
+
+/// The table details from a session's `<general>` element.
+struct Table<'a> {
+    name: &'a str,
+    game: &'a str,
+    size: Option<TableSize>,
+    small_blind: &'a str,
+    big_blind: &'a str,
+}
+
+fn parse_game(session: &mut SessionBuilder, table: &Table, game: &Element) -> Result<(), String> {
+    let amount = |text: &str| parse_amount(text).map_err(|e| e.to_string());
+    if !table.game.starts_with("Holdem NL") {
+        return Err("Only No Limit Hold'em is supported.".to_string());
+    }
+    let blinds = vec![amount(table.small_blind)?, amount(table.big_blind)?];
+    let id = game
+        .attribute("gamecode")
+        .and_then(|id| id.parse().ok())
+        .ok_or("Missing game code.")?;
+    let general = game.child("general").ok_or("Missing game details.")?;
+    let timestamp = general
+        .child("startdate")
+        .map(|d| parse_datetime(&d.text).map_err(|e| e.to_string()))
+        .ok_or("Missing start date.")??;
+
+    let players: Vec<&Element> = general
+        .child("players")
+        .map(|p| p.children("player").collect())
+        .unwrap_or_default();
+    let seat_of = |player: &Element| {
+        player
+            .attribute("seat")
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|&s| s >= 1)
+            .map(|s| s - 1)
+            .ok_or_else(|| format!("Invalid seat for {:?}.", player.attribute("name")))
+    };
+    let mut table_size = table.size.unwrap_or(0);
+    for player in &players {
+        table_size = table_size.max(seat_of(player)? as TableSize + 1);
+    }
+    let mut seats = vec![None; table_size as usize];
+    let mut button = None;
+    let mut rake = 0;
+    for player in &players {
+        let seat = seat_of(player)?;
+        let name = player.attribute("name").unwrap_or_default();
+        seats[seat] = Some(session.player(name, amount(player.attribute("chips").unwrap_or("0"))?));
+        if player.attribute("dealer") == Some("1") {
+            button = Some(seat as u8);
+        }
+        if let Some(player_rake) = player.attribute("rakeamount") {
+            rake += amount(player_rake)?;
+        }
+    }
+    let button = button.ok_or("Missing dealer.")?;
+
+    let mut hand = HandBuilder::new(id, timestamp, button, blinds.clone(), seats)?;
+    let mut board = Vec::new();
+    let mut rounds: Vec<&Element> = game.children("round").collect();
+    rounds.sort_by_key(|r| r.attribute("no").and_then(|n| n.parse::<u8>().ok()));
+    for round in rounds {
+        for cards in round.children("cards") {
+            let parsed = parse_cards(&cards.text)?;
+            match cards.attribute("type") {
+                Some("Pocket") if parsed.iter().all(|c| c.is_known()) => {
+                    hand.show(cards.attribute("player").unwrap_or_default(), &parsed)?;
+                }
+                Some("Flop" | "Turn" | "River") => {
+                    board.extend(parsed);
+                    hand.board(&board);
+                }
+                _ => {}
+            }
+        }
+        for action in round.children("action") {
+            let name = action.attribute("player").unwrap_or_default();
+            let sum = amount(action.attribute("sum").unwrap_or("0"))?;
+            match action.attribute("type").unwrap_or_default() {
+                "0" => hand.act(name, SiteAction::Fold, false)?,
+                "1" | "2" => hand.post(name, sum)?,
+                "3" => hand.act(name, SiteAction::Call, false)?,
+                "4" => hand.act(name, SiteAction::Check, false)?,
+                "5" | "23" => hand.act(name, SiteAction::By(sum), false)?,
+                "7" => hand.act(name, SiteAction::By(sum), true)?,
+                "15" => return Err("Antes are not supported.".to_string()),
+                other => return Err(format!("Unsupported action type {:?}.", other)),
+            }
+        }
+    }
+    for player in &players {
+        let win = amount(player.attribute("win").unwrap_or("0"))?;
+        if win > 0 {
+            hand.collect(player.attribute("name").unwrap_or_default(), win)?;
+        }
+    }
+    if rake > 0 {
+        hand.fee(Fee {
+            fee_type: FeeType::Rake,
+            amount: rake,
+            seat: None,
+        });
+    }
+
+    let BuiltHand {
+        seats,
+        hand,
+        warnings,
+        ..
+    } = hand.finish()?;
+    for warning in warnings {
+        session.warn(warning);
+    }
+    session.push(table.name, table_size, &blinds, seats, hand);
+    Ok(())
+}
+
+/// Parses iPoker's suit-first cards such as "D7 H4 C10", where "X" is an unseen card.
+fn parse_cards(text: &str) -> Result<Vec<Card>, String> {
+    text.split_whitespace()
+        .map(|card| {
+            if card == "X" {
+                return Ok(Card::Unknown);
+            }
+            let mut chars = card.chars();
+            let suit = chars.next().and_then(Suit::from_char);
+            let rank = match chars.as_str() {
+                "10" => Some(Rank::Ten),
+                rank if rank.len() == 1 => rank.chars().next().and_then(Rank::from_char),
+                _ => None,
+            };
+            match (rank, suit) {
+                (Some(rank), Some(suit)) => Ok(Card::new(rank, suit)),
+                _ => Err(format!("Invalid card: {:?}.", card)),
+            }
+        })
+        .collect()
+}
+
+/// An XML element, with just enough of XML supported to read hand histories.
+#[derive(Default, Debug)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    elements: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.elements.iter().find(|e| e.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.elements.iter().filter(move |e| e.name == name)
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Parses a document into its root element.
+    fn parse(text: &str) -> Result<Element, String> {
+        let mut stack = vec![Element::default()];
+        let mut rest = text;
+        while let Some(start) = rest.find('<') {
+            let parent = stack.last_mut().expect("Stack has the document");
+            parent.text.push_str(&unescape(&rest[..start])?);
+            rest = &rest[start..];
+            if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let end = cdata.find("]]>").ok_or("Unclosed CDATA section.")?;
+                parent.text.push_str(&cdata[..end]);
+                rest = &cdata[end + 3..];
+                continue;
+            }
+            let (close, skip) = match rest.as_bytes().get(1) {
+                Some(b'?') => ("?>", true),
+                Some(b'!') if rest.starts_with("<!--") => ("-->", true),
+                Some(b'!') => (">", true),
+                _ => (">", false),
+            };
+            let end = rest.find(close).ok_or("Unclosed tag.")?;
+            let tag = &rest[1..end];
+            rest = &rest[end + close.len()..];
+            if skip {
+                continue;
+            }
+            if let Some(name) = tag.strip_prefix('/') {
+                let element = stack.pop().filter(|_| !stack.is_empty());
+                match element {
+                    Some(element) if element.name == name.trim() => {
+                        stack.last_mut().expect("Parent").elements.push(element)
+                    }
+                    _ => return Err(format!("Unexpected closing tag </{}>.", name.trim())),
+                }
+                continue;
+            }
+            let (tag, empty) = match tag.strip_suffix('/') {
+                Some(tag) => (tag, true),
+                None => (tag, false),
+            };
+            let element = parse_tag(tag)?;
+            if empty {
+                stack.last_mut().expect("Parent").elements.push(element);
+            } else {
+                stack.push(element);
+            }
+        }
+        if stack.len() != 1 {
+            return Err("Unclosed element.".to_string());
+        }
+        let document = stack.pop().expect("Stack has the document");
+        document
+            .elements
+            .into_iter()
+            .next()
+            .ok_or_else(|| "Empty document.".to_string())
+    }
+}
+
+/// Parses the name and attributes of a start tag, e.g. `player seat="1" name="Hero"`.
+fn parse_tag(tag: &str) -> Result<Element, String> {
+    let tag = tag.trim();
+    let (name, mut rest) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+    let mut element = Element {
+        name: name.to_string(),
+        ..Element::default()
+    };
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return Ok(element);
+        }
+        let invalid = || format!("Invalid attributes in <{}>.", tag);
+        let (attribute, value) = rest.split_once('=').ok_or_else(invalid)?;
+        let value = value.trim_start();
+        let quote = value.chars().next().filter(|q| matches!(q, '"' | '\''));
+        let quote = quote.ok_or_else(invalid)?;
+        let end = value[1..].find(quote).ok_or_else(invalid)? + 1;
+        element
+            .attributes
+            .push((attribute.trim().to_string(), unescape(&value[1..end])?));
+        rest = &value[end + 1..];
+    }
+}
+
+/// Replaces XML entities such as `&amp;` and `&#36;`.
+fn unescape(text: &str) -> Result<String, String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or_else(|| format!("Unterminated entity in {:?}.", text))?
+            + start;
+        let entity = &rest[start + 1..end];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        unescaped.push(c.ok_or_else(|| format!("Unknown entity &{};.", entity))?);
+        rest = &rest[end + 1..];
+    }
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::site_id;
+    use crate::game::{ActionType, TableEvent};
+
+    const HISTORY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<session sessioncode="-1">
+  <general>
+    <gametype>Holdem NL $0.01/$0.02</gametype>
+    <tablename>Bangui</tablename>
+    <smallblind>$0.01</smallblind>
+    <bigblind>$0.02</bigblind>
+    <nickname>Hero</nickname>
+    <tablesize>6</tablesize>
+  </general>
+  <game gamecode="7001">
+    <general>
+      <startdate>2020-06-20 14:10:26</startdate>
+      <players>
+        <player seat="1" name="Hero" chips="$2" dealer="1" win="$0.11" rakeamount="$0.01"/>
+        <player seat="3" name="Tom &amp; Jerry" chips="$1.50" dealer="0" win="$0"/>
+      </players>
+    </general>
+    <round no="0">
+      <action no="1" player="Hero" type="1" sum="$0.01"/>
+      <action no="2" player="Tom &amp; Jerry" type="2" sum="$0.02"/>
+    </round>
+    <round no="1">
+      <cards type="Pocket" player="Hero">D10 HA</cards>
+      <cards type="Pocket" player="Tom &amp; Jerry">X X</cards>
+      <action no="3" player="Hero" type="23" sum="$0.05"/>
+      <action no="4" player="Tom &amp; Jerry" type="3" sum="$0.04"/>
+    </round>
+    <round no="2">
+      <cards type="Flop" player="">C2 S8 DK</cards>
+      <action no="5" player="Tom &amp; Jerry" type="4" sum="$0"/>
+      <action no="6" player="Hero" type="5" sum="$0.04"/>
+      <action no="7" player="Tom &amp; Jerry" type="0" sum="$0"/>
+    </round>
+  </game>
+</session>
+"#;
+
+    #[test]
+    fn test_import() -> io::Result<()> {
+        let import = IPoker.import(&mut HISTORY.as_bytes())?;
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        let session = import.session;
+        assert_eq!(session.hero_id, site_id(SITE, "Hero"));
+        let table = &session.tables[0];
+        assert_eq!(table.name, "Bangui");
+        assert_eq!(table.table_size, 6);
+        assert_eq!(table.initial_context[0].name, "Hero");
+        let TableEvent::SeatUpdate(update) = &table.events[0] else {
+            panic!("Expected a seat update");
+        };
+        assert_eq!(update.player.as_ref().unwrap().name, "Tom & Jerry");
+        let TableEvent::Hand(hand) = &table.events[1] else {
+            panic!("Expected a hand");
+        };
+        assert_eq!(hand.id, 7001);
+        assert_eq!(hand.button_position, 0);
+        assert_eq!(hand.hole_cards, vec![[Card::TenDiamonds, Card::AceHearts]]);
+        assert_eq!(hand.actions[0].action_type, ActionType::Raise);
+        assert_eq!(hand.actions[0].bet_amount, 6);
+        assert_eq!(hand.actions[3].action_type, ActionType::Bet);
+        assert_eq!(
+            hand.board[..3],
+            [Card::TwoClubs, Card::EightSpades, Card::KingDiamonds]
+        );
+        assert_eq!(hand.fees[0].amount, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_xml() {
+        let root = Element::parse("<a x='1'><!-- note --><b>t &lt; u</b><c/></a>").unwrap();
+        assert_eq!(root.attribute("x"), Some("1"));
+        assert_eq!(root.child("b").unwrap().text, "t < u");
+        assert!(root.child("c").is_some());
+        assert!(Element::parse("<a><b></a>").is_err());
+    }
+}
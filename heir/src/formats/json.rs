@@ -0,0 +1,995 @@
+use crate::formats::{sniff_text, HandHistoryImporter, Import};
+use crate::game::{
+    Action, Annotations, BettingStructure, Board, ClockEvent, Decimal, Fee, Finish, Hand,
+    HandAnnotation, Level, LevelChange, Player, PlayerNote, SeatUpdate, Session, StackUpdate,
+    Table, TableEvent, Tournament, Variant,
+};
+use crate::types::card::Card;
+use crate::types::version::Version;
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+
+fn invalid(message: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// The [`HandHistoryImporter`] for JSON sessions written by [`write_session`].
+pub struct Json;
+
+impl HandHistoryImporter for Json {
+    fn name(&self) -> &'static str {
+        "JSON"
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        let text = sniff_text(head);
+        text.starts_with('{') && text.contains("\"tables\"")
+    }
+
+    fn import(&self, reader: &mut dyn Read) -> io::Result<Import> {
+        Ok(Import {
+            session: read_session(reader)?,
+            warnings: Vec::new(),
+        })
+    }
+}
+
+/// A JSON value. Numbers are limited to the unsigned integers heir uses.
+#[derive(Clone, PartialEq, Debug)]
+enum Value {
+    Null,
+    Boolean(bool),
+    Number(u64),
+    String(String),
+    Array(Vec<Value>),
+    /// Members in the order they were written.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn object(members: Vec<(&str, Value)>) -> Value {
+        Value::Object(
+            members
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+
+    fn string(text: impl ToString) -> Value {
+        Value::String(text.to_string())
+    }
+
+    /// Writes the value indented by `depth` levels, keeping arrays of scalars on one line.
+    fn write(&self, out: &mut String, depth: usize) {
+        let indent = |out: &mut String, depth: usize| out.push_str(&"  ".repeat(depth));
+        match self {
+            Value::Null => out.push_str("null"),
+            Value::Boolean(b) => out.push_str(if *b { "true" } else { "false" }),
+            Value::Number(n) => out.push_str(&n.to_string()),
+            Value::String(s) => quote(out, s),
+            Value::Array(values) if values.is_empty() => out.push_str("[]"),
+            Value::Array(values) if values.iter().all(Value::is_inline) => {
+                out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    value.write(out, depth);
+                }
+                out.push(']');
+            }
+            Value::Array(values) => {
+                out.push_str("[\n");
+                for (i, value) in values.iter().enumerate() {
+                    indent(out, depth + 1);
+                    value.write(out, depth + 1);
+                    out.push_str(if i + 1 < values.len() { ",\n" } else { "\n" });
+                }
+                indent(out, depth);
+                out.push(']');
+            }
+            Value::Object(members) if members.is_empty() => out.push_str("{}"),
+            Value::Object(members) => {
+                out.push_str("{\n");
+                for (i, (key, value)) in members.iter().enumerate() {
+                    indent(out, depth + 1);
+                    quote(out, key);
+                    out.push_str(": ");
+                    value.write(out, depth + 1);
+                    out.push_str(if i + 1 < members.len() { ",\n" } else { "\n" });
+                }
+                indent(out, depth);
+                out.push('}');
+            }
+        }
+    }
+
+    fn is_inline(&self) -> bool {
+        match self {
+            Value::Array(values) => values.iter().all(Value::is_inline),
+            Value::Object(members) => members.is_empty(),
+            _ => true,
+        }
+    }
+
+    fn get(&self, key: &str) -> io::Result<&Value> {
+        match self {
+            Value::Object(members) => members
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v)
+                .ok_or_else(|| invalid(format!("Missing field {:?}.", key))),
+            _ => Err(invalid(format!("Expected an object with {:?}.", key))),
+        }
+    }
+
+    fn number(&self, key: &str) -> io::Result<u64> {
+        match self.get(key)? {
+            Value::Number(n) => Ok(*n),
+            _ => Err(invalid(format!("Field {:?} is not a number.", key))),
+        }
+    }
+
+    fn small(&self, key: &str) -> io::Result<u8> {
+        u8::try_from(self.number(key)?)
+            .map_err(|_| invalid(format!("Field {:?} is out of range.", key)))
+    }
+
+    fn medium(&self, key: &str) -> io::Result<u32> {
+        u32::try_from(self.number(key)?)
+            .map_err(|_| invalid(format!("Field {:?} is out of range.", key)))
+    }
+
+    fn numbers(&self, key: &str) -> io::Result<Vec<u64>> {
+        self.array(key)?
+            .iter()
+            .map(|n| match n {
+                Value::Number(n) => Ok(*n),
+                _ => Err(invalid(format!("Field {:?} must hold numbers.", key))),
+            })
+            .collect()
+    }
+
+    fn str(&self, key: &str) -> io::Result<&str> {
+        match self.get(key)? {
+            Value::String(s) => Ok(s),
+            _ => Err(invalid(format!("Field {:?} is not a string.", key))),
+        }
+    }
+
+    fn array(&self, key: &str) -> io::Result<&[Value]> {
+        match self.get(key)? {
+            Value::Array(values) => Ok(values),
+            _ => Err(invalid(format!("Field {:?} is not an array.", key))),
+        }
+    }
+}
+
+fn quote(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Writes a [`Session`] as JSON, with fields named as in [`crate::game`] and cards in
+/// their two character notation.
+pub fn write_session<W: Write>(writer: &mut W, session: &Session) -> io::Result<()> {
+    let mut out = String::new();
+    session_value(session).write(&mut out, 0);
+    out.push('\n');
+    writer.write_all(out.as_bytes())
+}
+
+fn session_value(session: &Session) -> Value {
+    let version = format!("{}.{}", session.version.major(), session.version.minor());
+    Value::object(vec![
+        ("version", Value::String(version)),
+        ("id", Value::Number(session.id)),
+        ("name", Value::string(&session.name)),
+        ("hero_id", Value::Number(session.hero_id)),
+        (
+            "tables",
+            Value::Array(session.tables.iter().map(table_value).collect()),
+        ),
+        (
+            "tournament",
+            session
+                .tournament
+                .as_ref()
+                .map_or(Value::Null, tournament_value),
+        ),
+        ("annotations", annotations_value(&session.annotations)),
+    ])
+}
+
+fn annotations_value(annotations: &Annotations) -> Value {
+    let hands = annotations
+        .hands
+        .iter()
+        .map(|annotation| {
+            Value::object(vec![
+                ("table_id", Value::Number(annotation.table_id)),
+                ("hand_id", Value::Number(annotation.hand_id)),
+                (
+                    "action",
+                    annotation
+                        .action
+                        .map_or(Value::Null, |a| Value::Number(a as u64)),
+                ),
+                (
+                    "tags",
+                    Value::Array(annotation.tags.iter().map(Value::string).collect()),
+                ),
+                ("note", Value::string(&annotation.note)),
+            ])
+        })
+        .collect();
+    let players = annotations
+        .players
+        .iter()
+        .map(|note| {
+            Value::object(vec![
+                ("player_id", Value::Number(note.player_id)),
+                ("label", note.label.map_or(Value::Null, Value::string)),
+                ("note", Value::string(&note.note)),
+            ])
+        })
+        .collect();
+    Value::object(vec![
+        ("hands", Value::Array(hands)),
+        ("players", Value::Array(players)),
+    ])
+}
+
+fn numbers_value(numbers: &[Decimal]) -> Value {
+    Value::Array(numbers.iter().map(|&n| Value::Number(n)).collect())
+}
+
+fn tournament_value(tournament: &Tournament) -> Value {
+    let levels = tournament
+        .levels
+        .iter()
+        .map(|level| {
+            Value::object(vec![
+                ("blinds", numbers_value(&level.blinds)),
+                ("ante", Value::Number(level.ante)),
+                ("duration", Value::Number(level.duration as u64)),
+            ])
+        })
+        .collect();
+    let finishes = tournament
+        .finishes
+        .iter()
+        .map(|finish| {
+            Value::object(vec![
+                ("player_id", Value::Number(finish.player_id)),
+                ("position", Value::Number(finish.position as u64)),
+                ("prize", Value::Number(finish.prize)),
+            ])
+        })
+        .collect();
+    Value::object(vec![
+        ("buy_in", Value::Number(tournament.buy_in)),
+        ("fee", Value::Number(tournament.fee)),
+        ("starting_stack", Value::Number(tournament.starting_stack)),
+        ("start", Value::Number(tournament.start)),
+        ("entrants", Value::Number(tournament.entrants as u64)),
+        ("levels", Value::Array(levels)),
+        ("payouts", numbers_value(&tournament.payouts)),
+        ("finishes", Value::Array(finishes)),
+    ])
+}
+
+fn table_value(table: &Table) -> Value {
+    let events = table
+        .events
+        .iter()
+        .map(|event| match event {
+            TableEvent::Hand(hand) => Value::object(vec![("hand", hand_value(hand))]),
+            TableEvent::StackUpdate(update) => Value::object(vec![(
+                "stack_update",
+                Value::object(vec![
+                    ("seat", Value::Number(update.seat as u64)),
+                    ("stack", Value::Number(update.stack as u64)),
+                ]),
+            )]),
+            TableEvent::SeatUpdate(update) => Value::object(vec![(
+                "seat_update",
+                Value::object(vec![
+                    ("seat", Value::Number(update.seat as u64)),
+                    (
+                        "player",
+                        update.player.as_ref().map_or(Value::Null, player_value),
+                    ),
+                ]),
+            )]),
+            TableEvent::LevelChange(change) => Value::object(vec![(
+                "level_change",
+                Value::object(vec![
+                    ("level", Value::Number(change.level as u64)),
+                    ("blinds", numbers_value(&change.blinds)),
+                    ("ante", Value::Number(change.ante)),
+                ]),
+            )]),
+        })
+        .collect();
+    Value::object(vec![
+        ("id", Value::Number(table.id)),
+        ("name", Value::string(&table.name)),
+        ("location", Value::string(&table.location)),
+        ("table_size", Value::Number(table.table_size as u64)),
+        (
+            "rake_percentage",
+            Value::Number(table.rake_percentage as u64),
+        ),
+        ("rake_cap", Value::Number(table.rake_cap)),
+        ("blinds", numbers_value(&table.blinds)),
+        ("betting_structure", Value::string(table.betting_structure)),
+        ("variant", Value::string(table.variant)),
+        (
+            "initial_context",
+            Value::Array(table.initial_context.iter().map(player_value).collect()),
+        ),
+        ("events", Value::Array(events)),
+    ])
+}
+
+fn player_value(player: &Player) -> Value {
+    Value::object(vec![
+        ("id", Value::Number(player.id)),
+        ("name", Value::string(&player.name)),
+        ("stack", Value::Number(player.stack)),
+    ])
+}
+
+fn hand_value(hand: &Hand) -> Value {
+    let cards = |cards: &[Card]| Value::Array(cards.iter().map(Value::string).collect());
+    let actions = hand
+        .actions
+        .iter()
+        .map(|action| {
+            Value::object(vec![
+                ("action_type", Value::string(&action.action_type)),
+                ("bet_amount", Value::Number(action.bet_amount as u64)),
+                (
+                    "timestamp",
+                    action.timestamp.map_or(Value::Null, Value::Number),
+                ),
+            ])
+        })
+        .collect();
+    let clock_events = hand
+        .clock_events
+        .iter()
+        .map(|event| {
+            Value::object(vec![
+                ("event_type", Value::string(event.event_type)),
+                ("seat", Value::Number(event.seat as u64)),
+                ("action", Value::Number(event.action as u64)),
+                (
+                    "timestamp",
+                    event.timestamp.map_or(Value::Null, Value::Number),
+                ),
+            ])
+        })
+        .collect();
+    let fees = hand
+        .fees
+        .iter()
+        .map(|fee| {
+            Value::object(vec![
+                ("fee_type", Value::string(fee.fee_type)),
+                ("amount", Value::Number(fee.amount)),
+                (
+                    "seat",
+                    fee.seat.map_or(Value::Null, |s| Value::Number(s as u64)),
+                ),
+            ])
+        })
+        .collect();
+    Value::object(vec![
+        ("id", Value::Number(hand.id)),
+        (
+            "button_position",
+            Value::Number(hand.button_position as u64),
+        ),
+        (
+            "hole_cards",
+            Value::Array(hand.hole_cards.iter().map(|c| cards(c)).collect()),
+        ),
+        ("actions", Value::Array(actions)),
+        ("timestamp", Value::Number(hand.timestamp)),
+        ("board", cards(&hand.board)),
+        (
+            "runouts",
+            Value::Array(hand.runouts.iter().map(|b| cards(b)).collect()),
+        ),
+        ("bomb_pot", hand.bomb_pot.map_or(Value::Null, Value::Number)),
+        ("fees", Value::Array(fees)),
+        ("clock_events", Value::Array(clock_events)),
+    ])
+}
+
+/// Reads a [`Session`] written by [`write_session`].
+pub fn read_session<R: Read>(mut reader: R) -> io::Result<Session> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut parser = Parser {
+        text: &text,
+        position: 0,
+    };
+    let value = parser.value()?;
+    parser.whitespace();
+    if parser.position != text.len() {
+        return Err(parser.error("Trailing characters"));
+    }
+    session_from(&value)
+}
+
+fn session_from(value: &Value) -> io::Result<Session> {
+    let version = value.str("version")?;
+    let (major, minor) = version
+        .split_once('.')
+        .and_then(|(major, minor)| Some((major.parse().ok()?, minor.parse().ok()?)))
+        .ok_or_else(|| invalid(format!("Invalid version {:?}.", version)))?;
+    let mut session = Session {
+        version: Version::new(major, minor).map_err(invalid)?,
+        id: value.number("id")?,
+        name: value.str("name")?.to_string(),
+        tables: value
+            .array("tables")?
+            .iter()
+            .map(table_from)
+            .collect::<io::Result<_>>()?,
+        hero_id: value.number("hero_id")?,
+        // Sessions written before tournaments have no such field.
+        tournament: match value.get("tournament") {
+            Ok(Value::Null) | Err(_) => None,
+            Ok(tournament) => Some(tournament_from(tournament)?),
+        },
+        annotations: Annotations::default(),
+    };
+    // Sessions written before annotations have none.
+    if let Ok(annotations) = value.get("annotations") {
+        session.annotations = annotations_from(annotations, &session)?;
+    }
+    Ok(session)
+}
+
+/// Reads annotations, where hand annotations written without a table id belong to the first
+/// table of the session that played their hand.
+fn annotations_from(value: &Value, session: &Session) -> io::Result<Annotations> {
+    let hands = value
+        .array("hands")?
+        .iter()
+        .map(|annotation| {
+            Ok(HandAnnotation {
+                table_id: match optional_number(annotation, "table_id")? {
+                    Some(table_id) => table_id,
+                    None => {
+                        let hand_id = annotation.number("hand_id")?;
+                        session.hand_table(hand_id).ok_or_else(|| {
+                            invalid(format!("Annotated hand {} is at no table.", hand_id))
+                        })?
+                    }
+                },
+                hand_id: annotation.number("hand_id")?,
+                action: optional_number(annotation, "action")?
+                    .map(|a| usize::try_from(a).map_err(|_| invalid("Action is out of range.")))
+                    .transpose()?,
+                tags: annotation
+                    .array("tags")?
+                    .iter()
+                    .map(|tag| match tag {
+                        Value::String(tag) => Ok(tag.clone()),
+                        _ => Err(invalid("Tags must be strings.")),
+                    })
+                    .collect::<io::Result<_>>()?,
+                note: annotation.str("note")?.to_string(),
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    let players = value
+        .array("players")?
+        .iter()
+        .map(|note| {
+            Ok(PlayerNote {
+                player_id: note.number("player_id")?,
+                label: match note.get("label")? {
+                    Value::Null => None,
+                    _ => Some(note.str("label")?.parse()?),
+                },
+                note: note.str("note")?.to_string(),
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    Ok(Annotations { hands, players })
+}
+
+fn tournament_from(value: &Value) -> io::Result<Tournament> {
+    let levels = value
+        .array("levels")?
+        .iter()
+        .map(|level| {
+            Ok(Level {
+                blinds: level.numbers("blinds")?,
+                ante: level.number("ante")?,
+                duration: level.medium("duration")?,
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    let finishes = value
+        .array("finishes")?
+        .iter()
+        .map(|finish| {
+            Ok(Finish {
+                player_id: finish.number("player_id")?,
+                position: finish.medium("position")?,
+                prize: finish.number("prize")?,
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    Ok(Tournament {
+        buy_in: value.number("buy_in")?,
+        fee: value.number("fee")?,
+        starting_stack: value.number("starting_stack")?,
+        start: value.number("start")?,
+        entrants: value.medium("entrants")?,
+        levels,
+        payouts: value.numbers("payouts")?,
+        finishes,
+    })
+}
+
+fn table_from(value: &Value) -> io::Result<Table> {
+    let events = value
+        .array("events")?
+        .iter()
+        .map(|event| {
+            let Value::Object(members) = event else {
+                return Err(invalid("Expected an event object."));
+            };
+            let [(kind, event)] = &members[..] else {
+                return Err(invalid("Expected an event with a single member."));
+            };
+            Ok(match kind.as_str() {
+                "hand" => TableEvent::Hand(hand_from(event)?),
+                "stack_update" => TableEvent::StackUpdate(StackUpdate {
+                    seat: event.small("seat")?,
+                    stack: u32::try_from(event.number("stack")?)
+                        .map_err(|_| invalid("Stack update is out of range."))?,
+                }),
+                "seat_update" => TableEvent::SeatUpdate(SeatUpdate {
+                    seat: event.small("seat")?,
+                    player: match event.get("player")? {
+                        Value::Null => None,
+                        player => Some(player_from(player)?),
+                    },
+                }),
+                "level_change" => TableEvent::LevelChange(LevelChange {
+                    level: event.medium("level")?,
+                    blinds: event.numbers("blinds")?,
+                    ante: event.number("ante")?,
+                }),
+                _ => return Err(invalid(format!("Unknown event {:?}.", kind))),
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    Ok(Table {
+        id: value.number("id")?,
+        name: value.str("name")?.to_string(),
+        location: value.str("location")?.to_string(),
+        table_size: value.small("table_size")?,
+        rake_percentage: value.small("rake_percentage")?,
+        rake_cap: value.number("rake_cap")?,
+        blinds: value.numbers("blinds")?,
+        // Tables written before betting structures are no-limit.
+        betting_structure: match value.get("betting_structure") {
+            Ok(_) => value.str("betting_structure")?.parse()?,
+            Err(_) => BettingStructure::NoLimit,
+        },
+        // Tables written before variants are Texas hold'em.
+        variant: match value.get("variant") {
+            Ok(_) => value.str("variant")?.parse()?,
+            Err(_) => Variant::Holdem,
+        },
+        initial_context: value
+            .array("initial_context")?
+            .iter()
+            .map(player_from)
+            .collect::<io::Result<_>>()?,
+        events,
+    })
+}
+
+fn player_from(value: &Value) -> io::Result<Player> {
+    Ok(Player {
+        id: value.number("id")?,
+        name: value.str("name")?.to_string(),
+        stack: value.number("stack")?,
+    })
+}
+
+fn cards_from(values: &[Value]) -> io::Result<Vec<Card>> {
+    values
+        .iter()
+        .map(|card| match card {
+            Value::String(card) => card.parse(),
+            _ => Err(invalid("Cards must be strings.")),
+        })
+        .collect()
+}
+
+fn hand_from(value: &Value) -> io::Result<Hand> {
+    let hole_cards = value
+        .array("hole_cards")?
+        .iter()
+        .map(|cards| match cards {
+            Value::Array(cards) => cards_from(cards),
+            _ => Err(invalid("Hole cards must be arrays.")),
+        })
+        .collect::<io::Result<_>>()?;
+    let actions = value
+        .array("actions")?
+        .iter()
+        .map(|action| {
+            Ok(Action {
+                action_type: action.str("action_type")?.parse()?,
+                bet_amount: u32::try_from(action.number("bet_amount")?)
+                    .map_err(|_| invalid("Bet amount is out of range."))?,
+                timestamp: optional_number(action, "timestamp")?,
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    let fees = value
+        .array("fees")?
+        .iter()
+        .map(|fee| {
+            Ok(Fee {
+                fee_type: fee.str("fee_type")?.parse()?,
+                amount: fee.number("amount")?,
+                seat: match fee.get("seat")? {
+                    Value::Null => None,
+                    _ => Some(fee.small("seat")?),
+                },
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    // Hands written before clock events have none.
+    let clock_events = match value.get("clock_events") {
+        Ok(_) => value
+            .array("clock_events")?
+            .iter()
+            .map(|event| {
+                Ok(ClockEvent {
+                    event_type: event.str("event_type")?.parse()?,
+                    seat: event.small("seat")?,
+                    action: usize::try_from(event.number("action")?)
+                        .map_err(|_| invalid("Clock event action is out of range."))?,
+                    timestamp: optional_number(event, "timestamp")?,
+                })
+            })
+            .collect::<io::Result<_>>()?,
+        Err(_) => Vec::new(),
+    };
+    let board = |cards: &[Value]| -> io::Result<Board> {
+        cards_from(cards)?
+            .try_into()
+            .map_err(|_| invalid("The board must have 5 cards."))
+    };
+    // Hands written before runouts have a single board.
+    let runouts = match value.get("runouts") {
+        Ok(_) => value
+            .array("runouts")?
+            .iter()
+            .map(|runout| match runout {
+                Value::Array(cards) => board(cards),
+                _ => Err(invalid("Runouts must be arrays.")),
+            })
+            .collect::<io::Result<_>>()?,
+        Err(_) => Vec::new(),
+    };
+    let board = board(value.array("board")?)?;
+    Ok(Hand {
+        id: value.number("id")?,
+        button_position: value.small("button_position")?,
+        hole_cards,
+        actions,
+        timestamp: value.number("timestamp")?,
+        board,
+        runouts,
+        // Hands written before bomb pots have blinds.
+        bomb_pot: optional_number(value, "bomb_pot")?,
+        fees,
+        clock_events,
+    })
+}
+
+/// Reads a number that may be null or, in files written before it existed, missing.
+fn optional_number(value: &Value, key: &str) -> io::Result<Option<u64>> {
+    match value.get(key) {
+        Ok(Value::Null) | Err(_) => Ok(None),
+        Ok(_) => Ok(Some(value.number(key)?)),
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> io::Error {
+        invalid(format!("{} at byte {}.", message, self.position))
+    }
+
+    fn whitespace(&mut self) {
+        let rest = &self.text[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn expect(&mut self, c: char) -> io::Result<()> {
+        self.whitespace();
+        if self.peek() != Some(c) {
+            return Err(self.error(&format!("Expected {:?}", c)));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn value(&mut self) -> io::Result<Value> {
+        self.whitespace();
+        let rest = &self.text[self.position..];
+        for (word, value) in [
+            ("null", Value::Null),
+            ("true", Value::Boolean(true)),
+            ("false", Value::Boolean(false)),
+        ] {
+            if rest.starts_with(word) {
+                self.position += word.len();
+                return Ok(value);
+            }
+        }
+        match self.peek() {
+            Some('"') => Ok(Value::String(self.string()?)),
+            Some('[') => {
+                self.position += 1;
+                let mut values = Vec::new();
+                if !self.close(']') {
+                    loop {
+                        values.push(self.value()?);
+                        if self.close(']') {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                Ok(Value::Array(values))
+            }
+            Some('{') => {
+                self.position += 1;
+                let mut members = Vec::new();
+                if !self.close('}') {
+                    loop {
+                        self.whitespace();
+                        let key = self.string()?;
+                        self.expect(':')?;
+                        members.push((key, self.value()?));
+                        if self.close('}') {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                Ok(Value::Object(members))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let end = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                let number = rest[..end]
+                    .parse()
+                    .map_err(|_| self.error("Number out of range"))?;
+                self.position += end;
+                if matches!(self.peek(), Some('.' | 'e' | 'E')) {
+                    return Err(self.error("Expected a whole number"));
+                }
+                Ok(Value::Number(number))
+            }
+            _ => Err(self.error("Expected a value")),
+        }
+    }
+
+    /// Consumes the closing character if it is next.
+    fn close(&mut self, c: char) -> bool {
+        self.whitespace();
+        if self.peek() == Some(c) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        if self.peek() != Some('"') {
+            return Err(self.error("Expected a string"));
+        }
+        self.position += 1;
+        let mut text = String::new();
+        loop {
+            let c = self
+                .peek()
+                .ok_or_else(|| self.error("Unterminated string"))?;
+            self.position += c.len_utf8();
+            match c {
+                '"' => return Ok(text),
+                '\\' => {
+                    let escape = self
+                        .peek()
+                        .ok_or_else(|| self.error("Unterminated string"))?;
+                    self.position += 1;
+                    text.push(match escape {
+                        '"' | '\\' | '/' => escape,
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => {
+                            let high = self.hex()?;
+                            let code = if (0xd800..0xdc00).contains(&high) {
+                                if !self.text[self.position..].starts_with("\\u") {
+                                    return Err(self.error("Unpaired surrogate"));
+                                }
+                                self.position += 2;
+                                let low = self.hex()?;
+                                0x10000
+                                    + ((high - 0xd800) << 10)
+                                    + (low.wrapping_sub(0xdc00) & 0x3ff)
+                            } else {
+                                high
+                            };
+                            char::from_u32(code).ok_or_else(|| self.error("Invalid escape"))?
+                        }
+                        _ => return Err(self.error("Invalid escape")),
+                    });
+                }
+                c => text.push(c),
+            }
+        }
+    }
+
+    fn hex(&mut self) -> io::Result<u32> {
+        let digits = self
+            .text
+            .get(self.position..self.position + 4)
+            .ok_or_else(|| self.error("Invalid escape"))?;
+        let code = u32::from_str_radix(digits, 16).map_err(|_| self.error("Invalid escape"))?;
+        self.position += 4;
+        Ok(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{ClockEventType, FeeType};
+
+    #[test]
+    fn test_round_trip() -> io::Result<()> {
+        let mut session = Session::exhaustive();
+        session.tables[0].betting_structure = BettingStructure::FixedLimit;
+        session.name = "Quotes \" and \\ and\ttabs 🂡".to_string();
+        let TableEvent::Hand(hand) = &mut session.tables[0].events[0] else {
+            panic!("Expected a hand");
+        };
+        hand.fees.push(Fee {
+            fee_type: FeeType::CashDrop,
+            amount: 100,
+            seat: None,
+        });
+        hand.runouts.push([
+            Card::ThreeClubs,
+            Card::ThreeHearts,
+            Card::KingDiamonds,
+            Card::Xx,
+            Card::Xx,
+        ]);
+        hand.bomb_pot = Some(100);
+        session.annotations = Annotations::example();
+        let TableEvent::Hand(hand) = &mut session.tables[0].events[0] else {
+            panic!("Expected a hand");
+        };
+        hand.actions[0].timestamp = Some(hand.timestamp * 1000 + 2500);
+        hand.clock_events.push(ClockEvent {
+            event_type: ClockEventType::TimeBank,
+            seat: 1,
+            action: 1,
+            timestamp: None,
+        });
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        let text = String::from_utf8(bytes).expect("UTF-8");
+        assert!(Json.sniff(text.as_bytes()));
+        assert!(text.contains("\"hole_cards\": [[\"Ac\", \"As\"], [\"2c\", \"2s\"]],"));
+        assert!(text.contains("\"action_type\": \"raise\","));
+        assert!(text.contains("\"fee_type\": \"cash-drop\","));
+        assert!(text.contains("\"betting_structure\": \"fixed-limit\","));
+        assert!(text.contains("\"event_type\": \"time-bank\","));
+        assert!(text.contains("\"label\": \"red\","));
+        assert_eq!(read_session(text.as_bytes())?, session);
+        // Annotations written before table ids belong to the table that played their hand.
+        let older = text.replace("\"table_id\": 1,", "");
+        assert_ne!(older, text);
+        assert_eq!(read_session(older.as_bytes())?, session);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tournament_round_trip() -> io::Result<()> {
+        let mut session = Session::sit_and_go();
+        session.tournament.as_mut().expect("Tournament").finishes = vec![Finish {
+            player_id: 1,
+            position: 1,
+            prize: 2000,
+        }];
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        let text = String::from_utf8(bytes).expect("UTF-8");
+        assert!(text.contains("\"level_change\": {\n"));
+        assert!(text.contains("\"payouts\": [2000, 1000],"));
+        assert_eq!(read_session(text.as_bytes())?, session);
+        Ok(())
+    }
+
+    #[test]
+    fn test_omaha_round_trip() -> io::Result<()> {
+        let session = Session::omaha(Variant::Omaha6);
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        let text = String::from_utf8(bytes).expect("UTF-8");
+        assert!(text.contains("\"variant\": \"omaha-6\","));
+        assert_eq!(read_session(text.as_bytes())?, session);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse() -> io::Result<()> {
+        let mut parser = Parser {
+            text: r#" {"a": [1, true, null], "b": {}, "c": "\u00e9\ud83c\udca1"} "#,
+            position: 0,
+        };
+        assert_eq!(
+            parser.value()?,
+            Value::object(vec![
+                (
+                    "a",
+                    Value::Array(vec![Value::Number(1), Value::Boolean(true), Value::Null])
+                ),
+                ("b", Value::Object(Vec::new())),
+                ("c", Value::string("é🂡")),
+            ])
+        );
+        assert!(read_session("{\"version\": 1.5}".as_bytes()).is_err());
+        assert!(read_session("[1, 2".as_bytes()).is_err());
+        Ok(())
+    }
+}
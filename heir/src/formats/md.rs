@@ -0,0 +1,872 @@
+use crate::formats::{concatenated_cards, sniff_text, HandHistoryImporter, Import};
+use crate::game::{
+    Action, Annotations, BettingStructure, Board, ClockEvent, Decimal, Fee, Finish, Hand,
+    HandAnnotation, Id, Level, LevelChange, Millis, Player, PlayerNote, SeatUpdate, Session,
+    StackUpdate, Table, TableEvent, Variant,
+};
+use crate::types::card::Card;
+use crate::types::version::Version;
+use std::io::{self, Read, Write};
+
+/// The [`HandHistoryImporter`] for `.heir.md` files.
+pub struct HeirMd;
+
+impl HandHistoryImporter for HeirMd {
+    fn name(&self) -> &'static str {
+        "heir.md"
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        let text = sniff_text(head);
+        text.starts_with("# ") && text.contains("\n- Version: ")
+    }
+
+    fn import(&self, reader: &mut dyn Read) -> io::Result<Import> {
+        Ok(Import {
+            session: read_session(reader)?,
+            warnings: Vec::new(),
+        })
+    }
+}
+
+/// Writes a [`Session`] in the `.heir.md` format.
+///
+/// The session is a level one heading and each [`Table`] a level two heading, followed by
+/// their fields as `- Key: value` items. Seats, hands and updates are level three headings,
+/// with a hand's actions as an ordered list. Amounts are written in cents. Annotations are
+/// `>` comments under the hand, action or `- Player id: label` item they belong to, a hand's
+/// or action's starting with its tags as `Tags: a, b` when it has any.
+pub fn write_session<W: Write>(writer: &mut W, session: &Session) -> io::Result<()> {
+    writeln!(writer, "# {}", session.name)?;
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "- Version: {}.{}",
+        session.version.major(),
+        session.version.minor()
+    )?;
+    writeln!(writer, "- Session: {}", session.id)?;
+    writeln!(writer, "- Hero: {}", session.hero_id)?;
+    if let Some(tournament) = &session.tournament {
+        writeln!(writer, "- Buy-in: {}+{}", tournament.buy_in, tournament.fee)?;
+        writeln!(writer, "- Starting stack: {}", tournament.starting_stack)?;
+        writeln!(writer, "- Start: {}", tournament.start)?;
+        writeln!(writer, "- Entrants: {}", tournament.entrants)?;
+        for (i, level) in tournament.levels.iter().enumerate() {
+            writeln!(
+                writer,
+                "- Level {}: {} ante {} for {} seconds",
+                i + 1,
+                blinds_text(&level.blinds),
+                level.ante,
+                level.duration
+            )?;
+        }
+        writeln!(writer, "- Payouts: {}", blinds_text(&tournament.payouts))?;
+        for finish in &tournament.finishes {
+            writeln!(
+                writer,
+                "- Finish {}: player {}, prize {}",
+                finish.position, finish.player_id, finish.prize
+            )?;
+        }
+    }
+    for note in &session.annotations.players {
+        let label = note.label.map_or("none".to_string(), |l| l.to_string());
+        writeln!(writer, "- Player {}: {}", note.player_id, label)?;
+        write_comment(writer, &note.note)?;
+    }
+    for table in &session.tables {
+        write_table(writer, table, &session.annotations)?;
+    }
+    Ok(())
+}
+
+/// Writes an annotation's tags and note as `>` comment lines. The tags line is written
+/// without tags when there would be no line at all or the note could be taken for tags.
+fn write_annotation<W: Write>(writer: &mut W, annotation: &HandAnnotation) -> io::Result<()> {
+    if let Some(tag) = annotation
+        .tags
+        .iter()
+        .find(|t| t.is_empty() || t.contains(',') || t.contains('\n'))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Tag {:?} cannot be written as a comment.", tag),
+        ));
+    }
+    if !annotation.tags.is_empty()
+        || annotation.note.is_empty()
+        || annotation.note.starts_with("Tags:")
+    {
+        writeln!(
+            writer,
+            "{}",
+            format!("> Tags: {}", annotation.tags.join(", ")).trim_end()
+        )?;
+    }
+    write_comment(writer, &annotation.note)
+}
+
+fn write_comment<W: Write>(writer: &mut W, note: &str) -> io::Result<()> {
+    for line in note.lines() {
+        writeln!(writer, "{}", format!("> {}", line).trim_end())?;
+    }
+    Ok(())
+}
+
+/// Amounts joined by "/", as blinds are written.
+fn blinds_text(amounts: &[Decimal]) -> String {
+    let amounts: Vec<String> = amounts.iter().map(u64::to_string).collect();
+    amounts.join("/")
+}
+
+fn write_table<W: Write>(
+    writer: &mut W,
+    table: &Table,
+    annotations: &Annotations,
+) -> io::Result<()> {
+    writeln!(writer)?;
+    writeln!(writer, "## {}", table.name)?;
+    writeln!(writer)?;
+    writeln!(writer, "- Table: {}", table.id)?;
+    writeln!(writer, "- Location: {}", table.location)?;
+    writeln!(writer, "- Size: {}", table.table_size)?;
+    writeln!(
+        writer,
+        "- Rake: {}% capped at {}",
+        table.rake_percentage, table.rake_cap
+    )?;
+    writeln!(writer, "- Blinds: {}", blinds_text(&table.blinds))?;
+    if table.betting_structure != BettingStructure::NoLimit {
+        writeln!(writer, "- Betting: {}", table.betting_structure)?;
+    }
+    if table.variant != Variant::Holdem {
+        writeln!(writer, "- Variant: {}", table.variant)?;
+    }
+    writeln!(writer)?;
+    writeln!(writer, "### Seats")?;
+    writeln!(writer)?;
+    for (seat, player) in table.initial_context.iter().enumerate() {
+        writeln!(writer, "- Seat {}: {}", seat, player_text(player))?;
+    }
+    for event in &table.events {
+        writeln!(writer)?;
+        match event {
+            TableEvent::Hand(hand) => write_hand(writer, table.id, hand, annotations)?,
+            TableEvent::StackUpdate(update) => {
+                writeln!(writer, "### Stack update")?;
+                writeln!(writer)?;
+                writeln!(writer, "- Seat {}: {}", update.seat, update.stack)?;
+            }
+            TableEvent::SeatUpdate(update) => {
+                writeln!(writer, "### Seat update")?;
+                writeln!(writer)?;
+                let player = update.player.as_ref().map_or("empty".into(), player_text);
+                writeln!(writer, "- Seat {}: {}", update.seat, player)?;
+            }
+            TableEvent::LevelChange(change) => {
+                writeln!(writer, "### Level {}", change.level)?;
+                writeln!(writer)?;
+                writeln!(writer, "- Blinds: {}", blinds_text(&change.blinds))?;
+                writeln!(writer, "- Ante: {}", change.ante)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_hand<W: Write>(
+    writer: &mut W,
+    table_id: Id,
+    hand: &Hand,
+    annotations: &Annotations,
+) -> io::Result<()> {
+    if let Some(annotation) = annotations.hands.iter().find(|a| {
+        (a.table_id, a.hand_id) == (table_id, hand.id)
+            && a.action.is_some_and(|i| i >= hand.actions.len())
+    }) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Hand {} has no action {} to annotate.",
+                hand.id,
+                annotation.action.expect("Action")
+            ),
+        ));
+    }
+    let hole_cards: Vec<String> = hand
+        .hole_cards
+        .iter()
+        .map(|cards| cards.iter().map(Card::to_string).collect())
+        .collect();
+    writeln!(writer, "### Hand {}", hand.id)?;
+    writeln!(writer)?;
+    if let Some(annotation) = annotations.hand(table_id, hand.id, None) {
+        write_annotation(writer, annotation)?;
+        writeln!(writer)?;
+    }
+    writeln!(writer, "- Timestamp: {}", hand.timestamp)?;
+    writeln!(writer, "- Button: {}", hand.button_position)?;
+    if let Some(amount) = hand.bomb_pot {
+        writeln!(writer, "- Bomb pot: {}", amount)?;
+    }
+    writeln!(
+        writer,
+        "{}",
+        format!("- Hole cards: {}", hole_cards.join(" ")).trim_end()
+    )?;
+    writeln!(
+        writer,
+        "{}",
+        format!("- Board: {}", board_text(&hand.board)).trim_end()
+    )?;
+    for runout in &hand.runouts {
+        writeln!(writer, "- Runout: {}", board_text(runout))?;
+    }
+    if !hand.actions.is_empty() {
+        writeln!(writer)?;
+    }
+    for (i, action) in hand.actions.iter().enumerate() {
+        write!(
+            writer,
+            "{}. {} {}",
+            i + 1,
+            action.action_type,
+            action.bet_amount
+        )?;
+        match action.timestamp {
+            Some(timestamp) => writeln!(writer, " at {}", timestamp)?,
+            None => writeln!(writer)?,
+        }
+        if let Some(annotation) = annotations.hand(table_id, hand.id, Some(i)) {
+            write_annotation(writer, annotation)?;
+        }
+    }
+    if !hand.fees.is_empty() {
+        writeln!(writer)?;
+    }
+    for fee in &hand.fees {
+        match fee.seat {
+            Some(seat) => writeln!(
+                writer,
+                "- Fee: {} {} seat {}",
+                fee.fee_type, fee.amount, seat
+            )?,
+            None => writeln!(writer, "- Fee: {} {}", fee.fee_type, fee.amount)?,
+        }
+    }
+    if !hand.clock_events.is_empty() {
+        writeln!(writer)?;
+    }
+    // Clock events name the action they came before, numbered as the actions are.
+    for event in &hand.clock_events {
+        write!(
+            writer,
+            "- Clock: {} seat {} before {}",
+            event.event_type,
+            event.seat,
+            event.action + 1
+        )?;
+        match event.timestamp {
+            Some(timestamp) => writeln!(writer, " at {}", timestamp)?,
+            None => writeln!(writer)?,
+        }
+    }
+    Ok(())
+}
+
+/// The dealt cards of a board (e.g. "3c3hKc").
+fn board_text(board: &Board) -> String {
+    let dealt = board
+        .iter()
+        .rposition(|&c| c != Card::Xx)
+        .map_or(0, |i| i + 1);
+    board[..dealt].iter().map(Card::to_string).collect()
+}
+
+/// A player as "Name (id) stack".
+fn player_text(player: &Player) -> String {
+    format!("{} ({}) {}", player.name, player.id, player.stack)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Session,
+    Table,
+    Seats,
+    Hand,
+    StackUpdate,
+    SeatUpdate,
+    Level,
+}
+
+/// Reads a [`Session`] in the `.heir.md` format.
+pub fn read_session<R: Read>(mut reader: R) -> io::Result<Session> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut session = Session {
+        version: Version::CURRENT,
+        id: 0,
+        name: String::new(),
+        tables: Vec::new(),
+        hero_id: 0,
+        tournament: None,
+        annotations: Annotations::default(),
+    };
+    let mut section = Section::Session;
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        section = read_line(&mut session, section, line).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Line {}: {}", number + 1, e),
+            )
+        })?;
+    }
+    Ok(session)
+}
+
+/// Reads a line into the session, returning the section the next line is in.
+fn read_line(session: &mut Session, section: Section, line: &str) -> Result<Section, String> {
+    if let Some(text) = line.strip_prefix('>') {
+        read_comment(session, section, text.strip_prefix(' ').unwrap_or(text))?;
+        return Ok(section);
+    }
+    let number = |text: &str| {
+        text.trim()
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid number {:?}.", text))
+    };
+    let small = |text: &str| {
+        text.trim()
+            .parse::<u8>()
+            .map_err(|_| format!("Invalid number {:?}.", text))
+    };
+    let medium = |text: &str| {
+        text.trim()
+            .parse::<u32>()
+            .map_err(|_| format!("Invalid number {:?}.", text))
+    };
+    let amounts = |text: &str| -> Result<Vec<Decimal>, String> {
+        match text {
+            "" => Ok(Vec::new()),
+            text => text.split('/').map(number).collect(),
+        }
+    };
+
+    if let Some(name) = line.strip_prefix("# ") {
+        session.name = name.to_string();
+        return Ok(Section::Session);
+    }
+    if let Some(name) = line.strip_prefix("## ") {
+        session.tables.push(Table {
+            id: 0,
+            name: name.to_string(),
+            location: String::new(),
+            table_size: 0,
+            rake_percentage: 0,
+            rake_cap: 0,
+            blinds: Vec::new(),
+            betting_structure: BettingStructure::NoLimit,
+            variant: Variant::Holdem,
+            initial_context: Vec::new(),
+            events: Vec::new(),
+        });
+        return Ok(Section::Table);
+    }
+    if let Some(heading) = line.strip_prefix("### ") {
+        let table = session
+            .tables
+            .last_mut()
+            .ok_or("Heading before the first table.")?;
+        return Ok(match heading {
+            "Seats" => Section::Seats,
+            "Stack update" => Section::StackUpdate,
+            "Seat update" => Section::SeatUpdate,
+            _ if heading.starts_with("Level ") => {
+                table.events.push(TableEvent::LevelChange(LevelChange {
+                    level: medium(&heading["Level ".len()..])?,
+                    blinds: Vec::new(),
+                    ante: 0,
+                }));
+                Section::Level
+            }
+            _ => {
+                let id = heading
+                    .strip_prefix("Hand ")
+                    .ok_or_else(|| format!("Unknown heading {:?}.", heading))?;
+                table.events.push(TableEvent::Hand(Hand {
+                    id: number(id)?,
+                    button_position: 0,
+                    hole_cards: Vec::new(),
+                    actions: Vec::new(),
+                    timestamp: 0,
+                    board: [Card::Xx; 5],
+                    runouts: Vec::new(),
+                    bomb_pot: None,
+                    fees: Vec::new(),
+                    clock_events: Vec::new(),
+                }));
+                Section::Hand
+            }
+        });
+    }
+
+    let cards = |text: &str| concatenated_cards(text.trim()).map_err(|e| e.to_string());
+    let parse_board = |text: &str| -> Result<Board, String> {
+        let dealt = cards(text)?;
+        if dealt.len() > 5 {
+            return Err(format!("Invalid board {:?}.", text));
+        }
+        let mut board = [Card::Xx; 5];
+        board[..dealt.len()].copy_from_slice(&dealt);
+        Ok(board)
+    };
+    let Some(table) = session.tables.last_mut() else {
+        let (key, value) = item(line)?;
+        match key {
+            "Version" => {
+                let (major, minor) = value.split_once('.').ok_or("Invalid version.")?;
+                session.version =
+                    Version::new(small(major)?, small(minor)?).map_err(|e| e.to_string())?;
+            }
+            "Session" => session.id = number(value)?,
+            "Hero" => session.hero_id = number(value)?,
+            _ if key.starts_with("Player ") => session.annotations.players.push(PlayerNote {
+                player_id: number(&key["Player ".len()..])?,
+                label: match value {
+                    "none" => None,
+                    label => Some(label.parse().map_err(|e: io::Error| e.to_string())?),
+                },
+                note: String::new(),
+            }),
+            _ => {
+                let tournament = session.tournament.get_or_insert_with(Default::default);
+                match key {
+                    "Buy-in" => {
+                        let (buy_in, fee) = value
+                            .split_once('+')
+                            .ok_or_else(|| format!("Invalid buy-in {:?}.", value))?;
+                        tournament.buy_in = number(buy_in)?;
+                        tournament.fee = number(fee)?;
+                    }
+                    "Starting stack" => tournament.starting_stack = number(value)?,
+                    "Start" => tournament.start = number(value)?,
+                    "Entrants" => tournament.entrants = medium(value)?,
+                    "Payouts" => tournament.payouts = amounts(value)?,
+                    _ if key.starts_with("Level ") => {
+                        let invalid = || format!("Invalid level {:?}.", value);
+                        let (blinds, rest) = value.split_once(" ante ").ok_or_else(invalid)?;
+                        let (ante, duration) = rest.split_once(" for ").ok_or_else(invalid)?;
+                        let duration = duration.strip_suffix(" seconds").ok_or_else(invalid)?;
+                        tournament.levels.push(Level {
+                            blinds: amounts(blinds)?,
+                            ante: number(ante)?,
+                            duration: medium(duration)?,
+                        });
+                    }
+                    _ if key.starts_with("Finish ") => {
+                        let invalid = || format!("Invalid finish {:?}.", value);
+                        let (player, prize) = value
+                            .strip_prefix("player ")
+                            .and_then(|v| v.split_once(", prize "))
+                            .ok_or_else(invalid)?;
+                        tournament.finishes.push(Finish {
+                            player_id: number(player)?,
+                            position: medium(&key["Finish ".len()..])?,
+                            prize: number(prize)?,
+                        });
+                    }
+                    _ => return Err(format!("Unknown session field {:?}.", key)),
+                }
+            }
+        }
+        return Ok(section);
+    };
+    match section {
+        Section::Session => return Err("Session field after a table.".to_string()),
+        Section::Table => {
+            let (key, value) = item(line)?;
+            match key {
+                "Table" => table.id = number(value)?,
+                "Location" => table.location = value.to_string(),
+                "Size" => table.table_size = small(value)?,
+                "Rake" => {
+                    let (percentage, cap) = value
+                        .split_once("% capped at ")
+                        .ok_or_else(|| format!("Invalid rake {:?}.", value))?;
+                    table.rake_percentage = small(percentage)?;
+                    table.rake_cap = number(cap)?;
+                }
+                "Blinds" => table.blinds = amounts(value)?,
+                "Betting" => {
+                    table.betting_structure = value.parse().map_err(|e: io::Error| e.to_string())?
+                }
+                "Variant" => table.variant = value.parse().map_err(|e: io::Error| e.to_string())?,
+                _ => return Err(format!("Unknown table field {:?}.", key)),
+            }
+        }
+        Section::Seats => {
+            let (seat, player) = seat_item(line)?;
+            if seat as usize != table.initial_context.len() {
+                return Err(format!("Seat {} is out of order.", seat));
+            }
+            table.initial_context.push(parse_player(player)?);
+        }
+        Section::StackUpdate => {
+            let (seat, stack) = seat_item(line)?;
+            let stack = stack
+                .parse()
+                .map_err(|_| format!("Invalid stack {:?}.", stack))?;
+            table
+                .events
+                .push(TableEvent::StackUpdate(StackUpdate { seat, stack }));
+        }
+        Section::SeatUpdate => {
+            let (seat, player) = seat_item(line)?;
+            let player = match player {
+                "empty" => None,
+                player => Some(parse_player(player)?),
+            };
+            table
+                .events
+                .push(TableEvent::SeatUpdate(SeatUpdate { seat, player }));
+        }
+        Section::Level => {
+            let Some(TableEvent::LevelChange(change)) = table.events.last_mut() else {
+                unreachable!("Level sections start with a level change");
+            };
+            let (key, value) = item(line)?;
+            match key {
+                "Blinds" => change.blinds = amounts(value)?,
+                "Ante" => change.ante = number(value)?,
+                _ => return Err(format!("Unknown level field {:?}.", key)),
+            }
+        }
+        Section::Hand => {
+            let variant = table.variant;
+            let Some(TableEvent::Hand(hand)) = table.events.last_mut() else {
+                unreachable!("Hand sections start with a hand");
+            };
+            if let Some((_, action)) = line
+                .split_once(". ")
+                .filter(|(n, _)| n.chars().all(|c| c.is_ascii_digit()))
+            {
+                let (action, timestamp) = timed(action)?;
+                let (action_type, amount) = action
+                    .split_once(' ')
+                    .ok_or_else(|| format!("Invalid action {:?}.", action))?;
+                hand.actions.push(Action {
+                    action_type: action_type.parse().map_err(|e: io::Error| e.to_string())?,
+                    bet_amount: amount
+                        .parse()
+                        .map_err(|_| format!("Invalid amount {:?}.", amount))?,
+                    timestamp,
+                });
+                return Ok(section);
+            }
+            let (key, value) = item(line)?;
+            match key {
+                "Timestamp" => hand.timestamp = number(value)?,
+                "Button" => hand.button_position = small(value)?,
+                "Bomb pot" => hand.bomb_pot = Some(number(value)?),
+                "Hole cards" => {
+                    hand.hole_cards = value
+                        .split_whitespace()
+                        .map(|held| {
+                            let held_cards = cards(held)?;
+                            if held_cards.len() != variant.hole_cards() {
+                                return Err(format!("Invalid hole cards {:?}.", held));
+                            }
+                            Ok(held_cards)
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "Board" => hand.board = parse_board(value)?,
+                "Runout" => hand.runouts.push(parse_board(value)?),
+                "Fee" => {
+                    let fields: Vec<&str> = value.split_whitespace().collect();
+                    let (fee_type, amount, seat) = match fields[..] {
+                        [fee_type, amount] => (fee_type, amount, None),
+                        [fee_type, amount, "seat", seat] => (fee_type, amount, Some(small(seat)?)),
+                        _ => return Err(format!("Invalid fee {:?}.", value)),
+                    };
+                    hand.fees.push(Fee {
+                        fee_type: fee_type.parse().map_err(|e: io::Error| e.to_string())?,
+                        amount: number(amount)?,
+                        seat,
+                    });
+                }
+                "Clock" => {
+                    let (value, timestamp) = timed(value)?;
+                    let fields: Vec<&str> = value.split_whitespace().collect();
+                    let [event_type, "seat", seat, "before", action] = fields[..] else {
+                        return Err(format!("Invalid clock event {:?}.", value));
+                    };
+                    hand.clock_events.push(ClockEvent {
+                        event_type: event_type.parse().map_err(|e: io::Error| e.to_string())?,
+                        seat: small(seat)?,
+                        action: match number(action)? {
+                            0 => return Err(format!("Invalid clock event {:?}.", value)),
+                            action => action as usize - 1,
+                        },
+                        timestamp,
+                    });
+                }
+                _ => return Err(format!("Unknown hand field {:?}.", key)),
+            }
+        }
+    }
+    Ok(section)
+}
+
+/// Adds a comment line to the player note or hand annotation it follows. The first line of a
+/// hand's or action's annotation may hold its tags.
+fn read_comment(session: &mut Session, section: Section, text: &str) -> Result<(), String> {
+    let add_line = |note: &mut String| {
+        if !note.is_empty() {
+            note.push('\n');
+        }
+        note.push_str(text);
+    };
+    match section {
+        Section::Session => {
+            let note = session
+                .annotations
+                .players
+                .last_mut()
+                .ok_or("Comment before the first player.")?;
+            add_line(&mut note.note);
+        }
+        Section::Hand => {
+            let table = session
+                .tables
+                .last()
+                .expect("Hand sections belong to a table");
+            let Some(TableEvent::Hand(hand)) = table.events.last() else {
+                unreachable!("Hand sections start with a hand");
+            };
+            let action = hand.actions.len().checked_sub(1);
+            let hands = &mut session.annotations.hands;
+            match hands.last_mut() {
+                Some(last)
+                    if (last.table_id, last.hand_id, last.action)
+                        == (table.id, hand.id, action) =>
+                {
+                    add_line(&mut last.note)
+                }
+                _ => {
+                    let mut annotation = HandAnnotation {
+                        table_id: table.id,
+                        hand_id: hand.id,
+                        action,
+                        tags: Vec::new(),
+                        note: String::new(),
+                    };
+                    match text.strip_prefix("Tags:").map(str::trim_start) {
+                        Some("") => {}
+                        Some(tags) => {
+                            annotation.tags = tags.split(", ").map(str::to_string).collect()
+                        }
+                        None => add_line(&mut annotation.note),
+                    }
+                    hands.push(annotation);
+                }
+            }
+        }
+        _ => return Err("Comments belong to a player, hand or action.".to_string()),
+    }
+    Ok(())
+}
+
+/// Splits an optional " at <milliseconds>" time off the end of an action or clock event.
+fn timed(text: &str) -> Result<(&str, Option<Millis>), String> {
+    match text.rsplit_once(" at ") {
+        Some((text, timestamp)) => {
+            let timestamp = timestamp
+                .parse()
+                .map_err(|_| format!("Invalid time {:?}.", timestamp))?;
+            Ok((text, Some(timestamp)))
+        }
+        None => Ok((text, None)),
+    }
+}
+
+/// Splits a "- Key: value" item.
+fn item(line: &str) -> Result<(&str, &str), String> {
+    line.strip_prefix("- ")
+        .and_then(|l| l.split_once(':'))
+        .map(|(key, value)| (key, value.trim()))
+        .ok_or_else(|| format!("Expected an item, found {:?}.", line))
+}
+
+/// Splits a "- Seat n: value" item.
+fn seat_item(line: &str) -> Result<(u8, &str), String> {
+    let (key, value) = item(line)?;
+    let seat = key
+        .strip_prefix("Seat ")
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| format!("Expected a seat, found {:?}.", key))?;
+    Ok((seat, value))
+}
+
+/// Parses a player written as "Name (id) stack".
+fn parse_player(text: &str) -> Result<Player, String> {
+    let invalid = || format!("Invalid player {:?}.", text);
+    let (name, rest) = text.rsplit_once(" (").ok_or_else(invalid)?;
+    let (id, stack) = rest.split_once(") ").ok_or_else(invalid)?;
+    Ok(Player {
+        id: id.parse().map_err(|_| invalid())?,
+        name: name.to_string(),
+        stack: stack.parse().map_err(|_| invalid())?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{ClockEventType, FeeType};
+
+    #[test]
+    fn test_round_trip() -> io::Result<()> {
+        let mut session = Session::exhaustive();
+        session.tables[0].betting_structure = BettingStructure::PotLimit;
+        let TableEvent::Hand(hand) = &mut session.tables[0].events[0] else {
+            panic!("Expected a hand");
+        };
+        hand.fees.push(Fee {
+            fee_type: FeeType::InsurancePayout,
+            amount: 200,
+            seat: Some(1),
+        });
+        hand.runouts.push([
+            Card::ThreeClubs,
+            Card::ThreeHearts,
+            Card::KingDiamonds,
+            Card::Xx,
+            Card::Xx,
+        ]);
+        hand.bomb_pot = Some(100);
+        hand.actions[1].timestamp = Some(1_724_293_481_250);
+        hand.clock_events.push(ClockEvent {
+            event_type: ClockEventType::TimeBank,
+            seat: 1,
+            action: 1,
+            timestamp: Some(1_724_293_479_000),
+        });
+        hand.clock_events.push(ClockEvent {
+            event_type: ClockEventType::Disconnect,
+            seat: 0,
+            action: 5,
+            timestamp: None,
+        });
+        session.annotations = Annotations::example();
+        session.tables[0]
+            .events
+            .push(TableEvent::SeatUpdate(SeatUpdate {
+                seat: 1,
+                player: None,
+            }));
+
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        let text = String::from_utf8(bytes).expect("UTF-8");
+        assert!(HeirMd.sniff(text.as_bytes()));
+        assert!(text.contains("\n\n- Timestamp: 1724293476\n- Button: 1\n- Bomb pot: 100\n"));
+        assert!(text
+            .contains("\n- Hole cards: AcAs 2c2s\n- Board: 3c3hKc\n- Runout: 3c3hKd\n\n1. raise"));
+        assert!(text.contains("\n- Blinds: 50/100\n- Betting: pot-limit\n"));
+        assert!(text.contains("\n- Fee: insurance-payout 200 seat 1\n"));
+        assert!(text.contains("\n2. call 300 at 1724293481250\n"));
+        assert!(text.contains(
+            "\n- Player 1002: red\n> Raises light on paired boards.\n- Player 1001: none\n"
+        ));
+        assert!(text.contains(
+            "### Hand 9001\n\n> Tags: review, 3-bet pot\n> Should seat 0 defend the flop?\n\
+             > Ask the team.\n\n- Timestamp:"
+        ));
+        assert!(text.contains("\n4. raise 1800\n> Thin raise.\n5. fold 600\n"));
+        assert!(text.contains(
+            "\n- Clock: time-bank seat 1 before 2 at 1724293479000\n- Clock: disconnect seat 0 before 6\n"
+        ));
+        assert!(text.contains("\n- Seat 0: Player 1003 (1003) 15000\n"));
+        assert_eq!(read_session(text.as_bytes())?, session);
+        Ok(())
+    }
+
+    #[test]
+    fn test_annotations_round_trip() -> io::Result<()> {
+        // A second table that plays hands with the same ids.
+        let mut session = Session::exhaustive();
+        let mut table = session.tables[0].clone();
+        table.id = 2;
+        session.tables.push(table);
+        session.annotations = Annotations::example();
+        session.annotations.hands[1].table_id = 2;
+        // Annotations without tags or a note, and notes that look like tags, are kept.
+        session.annotations.hands.push(HandAnnotation {
+            table_id: 2,
+            hand_id: 9002,
+            action: None,
+            tags: vec![],
+            note: String::new(),
+        });
+        session.annotations.hands.push(HandAnnotation {
+            table_id: 2,
+            hand_id: 9002,
+            action: Some(0),
+            tags: vec![],
+            note: "Tags: none yet".to_string(),
+        });
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        let text = String::from_utf8(bytes).expect("UTF-8");
+        assert_eq!(text.matches("> Thin raise.").count(), 1);
+        assert_eq!(read_session(text.as_bytes())?, session);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tournament_round_trip() -> io::Result<()> {
+        let mut session = Session::sit_and_go();
+        session.tournament.as_mut().expect("Tournament").finishes = vec![Finish {
+            player_id: 1,
+            position: 1,
+            prize: 2000,
+        }];
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        let text = String::from_utf8(bytes).expect("UTF-8");
+        assert!(text.contains("\n- Buy-in: 1000+100\n"));
+        assert!(text.contains("\n- Level 2: 20/40 ante 5 for 600 seconds\n"));
+        assert!(text.contains("\n- Finish 1: player 1, prize 2000\n"));
+        assert!(text.contains("\n### Level 2\n\n- Blinds: 20/40\n- Ante: 5\n"));
+        assert_eq!(read_session(text.as_bytes())?, session);
+        Ok(())
+    }
+
+    #[test]
+    fn test_omaha_round_trip() -> io::Result<()> {
+        let session = Session::omaha(Variant::Omaha);
+        let mut bytes = Vec::new();
+        write_session(&mut bytes, &session)?;
+        let text = String::from_utf8(bytes).expect("UTF-8");
+        assert!(text.contains("\n- Variant: omaha\n"));
+        assert!(text.contains("\n- Hole cards: AcAsKdQd 2c2s9d6d\n"));
+        assert_eq!(read_session(text.as_bytes())?, session);
+        let text = text.replace("AcAsKdQd", "AcAs");
+        assert!(read_session(text.as_bytes()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reports_line() {
+        let text = "# Session\n\n- Version: 0.1\n\n## Table\n\n- Size: ten\n";
+        let error = read_session(text.as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "Line 7: Invalid number \"ten\".");
+    }
+}
@@ -0,0 +1,261 @@
+pub mod acpc;
+pub mod bin;
+pub(crate) mod builder;
+pub mod ggpoker;
+pub mod ipoker;
+pub mod json;
+pub mod md;
+pub mod phh;
+pub mod poker888;
+pub mod pokerstars;
+pub mod registry;
+pub mod winamax;
+
+use crate::game::{Decimal, Id, Session, Timestamp};
+use crate::types::card::Card;
+use std::io::{self, Read};
+
+/// A site or format whose hand histories can be imported into a [`Session`].
+///
+/// Importers are collected in a [`registry::Registry`], which uses [`sniff`] to pick the
+/// importer for a file.
+///
+/// [`sniff`]: HandHistoryImporter::sniff
+pub trait HandHistoryImporter {
+    /// The name of the site or format.
+    fn name(&self) -> &'static str;
+
+    /// Whether the start of a file looks like this importer's format.
+    fn sniff(&self, head: &[u8]) -> bool;
+
+    /// Reads hand histories, skipping hands that cannot be represented with a warning.
+    fn import(&self, reader: &mut dyn Read) -> io::Result<Import>;
+}
+
+/// A [`Session`] read from a site's hand histories, along with warnings about hands that
+/// were skipped or could not be fully represented.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Import {
+    pub session: Session,
+    pub warnings: Vec<String>,
+}
+
+/// The start of a file as text, without a byte order mark or leading whitespace.
+///
+/// The head may end part way through a character, which is dropped.
+pub(crate) fn sniff_text(head: &[u8]) -> &str {
+    let text = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        Err(e) => std::str::from_utf8(&head[..e.valid_up_to()]).unwrap_or_default(),
+    };
+    text.trim_start_matches('\u{feff}').trim_start()
+}
+
+/// Derives a stable [`Id`] for a name seen on a site, using 64-bit FNV-1a.
+pub(crate) fn site_id(site: &str, name: &str) -> Id {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in site.bytes().chain([0]).chain(name.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// Parses a money or chip amount such as "$1,234.56" or "0.5€" into cents.
+pub(crate) fn parse_amount(text: &str) -> io::Result<Decimal> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid amount: {:?}.", text),
+        )
+    };
+    let digits: String = text
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, '$' | '€' | '£' | ',' | ' '))
+        .collect();
+    let (whole, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid());
+    }
+    let whole: Decimal = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| invalid())?
+    };
+    let mut cents = 0;
+    for (i, c) in fraction.chars().take(2).enumerate() {
+        let digit = c.to_digit(10).ok_or_else(invalid)? as Decimal;
+        cents += digit * if i == 0 { 10 } else { 1 };
+    }
+    Ok(whole * 100 + cents)
+}
+
+/// Parses whitespace or comma separated cards, e.g. "Ah Kd" or "Ah, Kd".
+pub(crate) fn parse_cards(text: &str) -> io::Result<Vec<Card>> {
+    text.split([' ', ','])
+        .filter(|c| !c.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Parses cards written without separators, e.g. "AcAs" or "????".
+pub(crate) fn concatenated_cards(text: &str) -> io::Result<Vec<Card>> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid cards: {:?}.", text),
+        )
+    };
+    if !text.is_ascii() || !text.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| text[i..i + 2].parse().map_err(|_| invalid()))
+        .collect()
+}
+
+/// Parses every bracketed group of cards in a line, e.g. "[Ks 7d 2c] [9h]".
+pub(crate) fn bracketed_cards(line: &str) -> io::Result<Vec<Card>> {
+    let mut cards = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('[') {
+        let end = rest[start..].find(']').ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unclosed cards in {:?}.", line),
+            )
+        })? + start;
+        cards.extend(parse_cards(&rest[start + 1..end])?);
+        rest = &rest[end + 1..];
+    }
+    Ok(cards)
+}
+
+/// Parses a "YYYY/MM/DD HH:MM:SS" (or dash separated) date and time as UTC.
+pub(crate) fn parse_datetime(text: &str) -> io::Result<Timestamp> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid date: {:?}.", text),
+        )
+    };
+    let mut fields = text
+        .trim()
+        .split(['/', '-', ' ', ':', 'T'])
+        .filter(|f| !f.is_empty())
+        .map(|f| f.parse::<u32>().map_err(|_| invalid()));
+    let mut next = || fields.next().unwrap_or_else(|| Err(invalid()));
+    let (year, month, day) = (next()?, next()?, next()?);
+    let (hour, minute, second) = (next()?, next()?, next()?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+    Ok(timestamp_from_civil(
+        year as i64,
+        month,
+        day,
+        hour,
+        minute,
+        second,
+    ))
+}
+
+/// Splits a UNIX timestamp into UTC `(year, month, day, hour, minute, second)`.
+pub(crate) fn civil_from_timestamp(timestamp: Timestamp) -> (i64, u32, u32, u32, u32, u32) {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = (timestamp % 86_400) as u32;
+    // Howard Hinnant's days-to-civil algorithm.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    (
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds / 60 % 60,
+        seconds % 60,
+    )
+}
+
+/// Converts a UTC date and time into a UNIX timestamp.
+pub(crate) fn timestamp_from_civil(
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+) -> Timestamp {
+    let year = year - (month <= 2) as i64;
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    (days * 86_400 + (hour * 3_600 + minute * 60 + second) as i64).max(0) as Timestamp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_civil_round_trip() {
+        assert_eq!(civil_from_timestamp(0), (1970, 1, 1, 0, 0, 0));
+        assert_eq!(civil_from_timestamp(1724293476), (2024, 8, 22, 2, 24, 36));
+        assert_eq!(civil_from_timestamp(951_782_400), (2000, 2, 29, 0, 0, 0));
+        for timestamp in [0, 1724293476, 951_782_400, 4_102_444_799] {
+            let (y, mo, d, h, mi, s) = civil_from_timestamp(timestamp);
+            assert_eq!(timestamp_from_civil(y, mo, d, h, mi, s), timestamp);
+        }
+    }
+
+    #[test]
+    fn test_parse_amount() -> io::Result<()> {
+        assert_eq!(parse_amount("$0.05")?, 5);
+        assert_eq!(parse_amount("$0.5")?, 50);
+        assert_eq!(parse_amount("$1,234.56")?, 123_456);
+        assert_eq!(parse_amount("10€")?, 1_000);
+        assert_eq!(parse_amount(".25")?, 25);
+        assert!(parse_amount("$").is_err());
+        assert!(parse_amount("1.x").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_datetime() -> io::Result<()> {
+        assert_eq!(parse_datetime("2024/08/22 02:24:36")?, 1724293476);
+        assert_eq!(parse_datetime("2024-08-22 2:24:36")?, 1724293476);
+        assert!(parse_datetime("2024/13/22 02:24:36").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bracketed_cards() -> io::Result<()> {
+        assert_eq!(
+            bracketed_cards("*** TURN *** [Ks 7d 2c] [9h]")?,
+            vec![
+                Card::KingSpades,
+                Card::SevenDiamonds,
+                Card::TwoClubs,
+                Card::NineHearts
+            ]
+        );
+        assert_eq!(
+            bracketed_cards("** Dealing flop ** [ Kh, 2c ]")?,
+            vec![Card::KingHearts, Card::TwoClubs]
+        );
+        assert!(bracketed_cards("[Ks 7d").is_err());
+        Ok(())
+    }
+}
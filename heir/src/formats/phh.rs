@@ -0,0 +1,829 @@
+use crate::engine::replay::{Replay, SeatState, Street};
+use crate::engine::state::TableState;
+use crate::formats::builder::{HandBuilder, SessionBuilder, SiteAction};
+use crate::formats::{
+    civil_from_timestamp, concatenated_cards, sniff_text, timestamp_from_civil,
+    HandHistoryImporter, Import,
+};
+use crate::game::{
+    ActionType, BettingStructure, Decimal, Hand, Player, Session, Table, TableEvent, TableSize,
+    Variant,
+};
+use crate::types::card::Card;
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+
+const SITE: &str = "PHH";
+
+/// The [`HandHistoryImporter`] for Poker Hand History (`.phh` and `.phhs`) files.
+pub struct Phh;
+
+impl HandHistoryImporter for Phh {
+    fn name(&self) -> &'static str {
+        SITE
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        let text = sniff_text(head);
+        text.contains("starting_stacks") && text.contains("actions")
+    }
+
+    fn import(&self, reader: &mut dyn Read) -> io::Result<Import> {
+        import(reader)
+    }
+}
+
+/// Imports Poker Hand History hands, either a single `.phh` hand or a `.phhs` file with a
+/// hand in each `[section]`.
+///
+/// PHH lists players from the first seat left of the button round to the button, which also
+/// holds heads-up, and its amounts are read as they are in heir's smallest unit. No-limit and
+/// fixed-limit Texas hold'em, no-limit short deck and pot-limit Omaha (`variant = "NT"`,
+/// `"FT"`, `"NS"` and `"PO"`) are supported, with antes when every player posts the same
+/// one, which are imported as a [`LevelChange`](crate::game::LevelChange).
+pub fn import<R: Read>(mut reader: R) -> io::Result<Import> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let document = parse_toml(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut session = SessionBuilder::new(SITE);
+    let hands = if document.sections.is_empty() {
+        vec![(String::new(), document.keys)]
+    } else {
+        document.sections
+    };
+    for (section, keys) in &hands {
+        if let Err(e) = parse_hand(&mut session, keys, section) {
+            let label = match get(keys, "hand") {
+                Some(Value::Integer(id)) => id.to_string(),
+                _ => section.clone(),
+            };
+            session.warn(format!("Hand {} skipped: {}", label, e));
+        }
+    }
+    Ok(session.finish())
+}
+
+fn get<'a>(keys: &'a [(String, Value)], key: &str) -> Option<&'a Value> {
+    keys.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+/// An amount such as "300", which must be a whole number.
+fn parse_amount(token: &str) -> Result<Decimal, String> {
+    token
+        .parse()
+        .map_err(|_| format!("Invalid amount {:?}.", token))
+}
+
+/// Parses concatenated cards such as "AcAs" or "????".
+fn parse_cards(text: &str) -> Result<Vec<Card>, String> {
+    concatenated_cards(text).map_err(|e| e.to_string())
+}
+
+fn parse_hand(
+    session: &mut SessionBuilder,
+    keys: &[(String, Value)],
+    section: &str,
+) -> Result<(), String> {
+    let integers = |key: &str| -> Result<Option<Vec<u64>>, String> {
+        get(keys, key)
+            .map(|value| {
+                value
+                    .as_array()
+                    .and_then(|values| values.iter().map(Value::as_u64).collect())
+                    .ok_or_else(|| format!("Expected {} to be whole numbers.", key))
+            })
+            .transpose()
+    };
+    let (variant, betting_structure) = match get(keys, "variant").and_then(Value::as_str) {
+        Some("NT") => (Variant::Holdem, BettingStructure::NoLimit),
+        Some("FT") => (Variant::Holdem, BettingStructure::FixedLimit),
+        Some("NS") => (Variant::ShortDeck, BettingStructure::NoLimit),
+        Some("PO") => (Variant::Omaha, BettingStructure::PotLimit),
+        Some(variant) => return Err(format!("Unsupported variant {:?}.", variant)),
+        None => return Err("Missing variant.".to_string()),
+    };
+    let stacks = integers("starting_stacks")?.ok_or("Missing starting stacks.")?;
+    let n = stacks.len();
+    if n < 2 {
+        return Err("Expected at least two players.".to_string());
+    }
+    let positional = |key: &str| -> Result<Vec<u64>, String> {
+        let values = integers(key)?.unwrap_or_else(|| vec![0; n]);
+        if values.len() != n {
+            return Err(format!("Expected {} values in {}.", n, key));
+        }
+        Ok(values)
+    };
+    let antes = positional("antes")?;
+    let ante = antes[0];
+    if antes.iter().any(|&a| a != ante) {
+        return Err("Only equal antes from every player are supported.".to_string());
+    }
+    let blinds_or_straddles = positional("blinds_or_straddles")?;
+    let names: Vec<String> = match get(keys, "players") {
+        Some(players) => players
+            .as_array()
+            .and_then(|p| p.iter().map(|p| p.as_str().map(str::to_string)).collect())
+            .filter(|p: &Vec<String>| p.len() == n)
+            .ok_or("Expected a name for every player.")?,
+        None => (1..=n).map(|i| format!("p{}", i)).collect(),
+    };
+    let seat_numbers: Vec<usize> = match integers("seats")? {
+        Some(seats) if seats.len() == n && seats.iter().all(|&s| s >= 1) => {
+            seats.iter().map(|&s| s as usize - 1).collect()
+        }
+        Some(_) => return Err("Expected a seat from 1 for every player.".to_string()),
+        None => (0..n).collect(),
+    };
+    let seat_count = get(keys, "seat_count").and_then(Value::as_u64).unwrap_or(0);
+    let table_size = seat_numbers
+        .iter()
+        .map(|&s| s as u64 + 1)
+        .max()
+        .unwrap_or(0)
+        .max(seat_count);
+    let table_size = TableSize::try_from(table_size).map_err(|_| "Too many seats.")?;
+
+    let mut seats: Vec<Option<Player>> = vec![None; table_size as usize];
+    for (i, &seat) in seat_numbers.iter().enumerate() {
+        if seats[seat].is_some() {
+            return Err(format!("Seat {} is taken twice.", seat + 1));
+        }
+        seats[seat] = Some(session.player(&names[i], stacks[i]));
+    }
+    let button = seat_numbers[n - 1] as u8;
+    // Blinds are posted from the small blind, which heads-up is the button.
+    let posters: Vec<usize> = if n == 2 { vec![1, 0] } else { (0..n).collect() };
+    let mut blinds: Vec<Decimal> = posters.iter().map(|&p| blinds_or_straddles[p]).collect();
+    while blinds.last() == Some(&0) {
+        blinds.pop();
+    }
+    if blinds.contains(&0) {
+        return Err("Only consecutive blinds and straddles are supported.".to_string());
+    }
+
+    let field = |key: &str| get(keys, key).and_then(Value::as_u64);
+    let timestamp = match field("year") {
+        Some(year) => {
+            let time = match get(keys, "time") {
+                Some(Value::DateTime(time) | Value::String(time)) => time
+                    .split(':')
+                    .map(|t| t.split('.').next().unwrap_or(t).parse().ok())
+                    .collect::<Option<Vec<u32>>>()
+                    .filter(|t| t.len() == 3)
+                    .ok_or_else(|| format!("Invalid time {:?}.", time))?,
+                _ => vec![0; 3],
+            };
+            let month = field("month").unwrap_or(1) as u32;
+            let day = field("day").unwrap_or(1) as u32;
+            timestamp_from_civil(year as i64, month, day, time[0], time[1], time[2])
+        }
+        None => 0,
+    };
+    let id = field("hand").or_else(|| section.parse().ok()).unwrap_or(0);
+
+    let mut hand = HandBuilder::new(id, timestamp, button, blinds.clone(), seats)?
+        .variant(variant)?
+        .betting_structure(betting_structure)?
+        .ante(ante)?;
+    for (&p, &blind) in posters.iter().zip(&blinds) {
+        hand.post(&names[p], blind.min(stacks[p].saturating_sub(ante)))?;
+    }
+    let player = |token: &str| {
+        token
+            .strip_prefix('p')
+            .and_then(|i| i.parse::<usize>().ok())
+            .filter(|i| (1..=n).contains(i))
+            .map(|i| names[i - 1].as_str())
+            .ok_or_else(|| format!("Unknown player {:?}.", token))
+    };
+    let actions = get(keys, "actions")
+        .and_then(Value::as_array)
+        .ok_or("Missing actions.")?;
+    let mut board = Vec::new();
+    for action in actions {
+        let action = action.as_str().ok_or("Expected actions to be strings.")?;
+        let action = action.split('#').next().unwrap_or_default();
+        match action.split_whitespace().collect::<Vec<_>>()[..] {
+            ["d", "dh", p, cards] | [p, "sm", cards] => {
+                let cards = parse_cards(cards)?;
+                if cards.iter().any(|c| c.is_known()) {
+                    hand.show(player(p)?, &cards)?;
+                }
+            }
+            ["d", "db", cards] => {
+                board.extend(parse_cards(cards)?);
+                hand.board(&board);
+            }
+            [_, "sm"] => {}
+            [p, "f"] => hand.act(player(p)?, SiteAction::Fold, false)?,
+            [p, "cc"] => hand.act(player(p)?, SiteAction::CheckOrCall, false)?,
+            [p, "cbr", amount] => {
+                hand.act(player(p)?, SiteAction::To(parse_amount(amount)?), false)?
+            }
+            _ => return Err(format!("Unsupported action {:?}.", action.trim())),
+        }
+    }
+
+    let hand = hand.finish()?;
+    let table = match get(keys, "table") {
+        Some(Value::String(name)) => name.clone(),
+        Some(Value::Integer(number)) => number.to_string(),
+        _ => SITE.to_string(),
+    };
+    session.push_built(&table, hand);
+    Ok(())
+}
+
+/// Writes every [`Hand`] of a [`Session`] as a `.phhs` file, in sections numbered from 1.
+pub fn write_session<W: Write>(writer: &mut W, session: &Session) -> io::Result<()> {
+    let mut section = 1;
+    for table in &session.tables {
+        write_sections(writer, table, &mut section)?;
+    }
+    Ok(())
+}
+
+/// Writes every [`Hand`] at a [`Table`] as a `.phhs` file, in sections numbered from 1.
+pub fn write_table<W: Write>(writer: &mut W, table: &Table) -> io::Result<()> {
+    write_sections(writer, table, &mut 1)
+}
+
+fn write_sections<W: Write>(writer: &mut W, table: &Table, section: &mut usize) -> io::Result<()> {
+    let mut state = TableState::new(table);
+    for event in &table.events {
+        if let TableEvent::Hand(hand) = event {
+            if *section > 1 {
+                writeln!(writer)?;
+            }
+            writeln!(writer, "[{}]", section)?;
+            write_hand(writer, table, &state, hand)?;
+            *section += 1;
+        }
+        state
+            .apply(table, event)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    }
+    Ok(())
+}
+
+/// Writes a single [`Hand`] dealt to the seats and at the stakes of a [`TableState`] as a
+/// `.phh` document.
+///
+/// No-limit and fixed-limit hold'em, no-limit short deck and pot-limit Omaha tables can be
+/// written, as the `"NT"`, `"FT"`, `"NS"` and `"PO"` variants. Hands run on more than one
+/// board and bomb pots cannot be written.
+pub fn write_hand<W: Write>(
+    writer: &mut W,
+    table: &Table,
+    state: &TableState,
+    hand: &Hand,
+) -> io::Result<()> {
+    if !hand.runouts.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Hand {} is run on more than one board.", hand.id),
+        ));
+    }
+    if hand.bomb_pot.is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Hand {} is a bomb pot.", hand.id),
+        ));
+    }
+    let variant = match (table.variant, table.betting_structure) {
+        (Variant::Holdem, BettingStructure::NoLimit) => "NT",
+        (Variant::Holdem, BettingStructure::FixedLimit) => "FT",
+        (Variant::ShortDeck, BettingStructure::NoLimit) => "NS",
+        (Variant::Omaha, BettingStructure::PotLimit) => "PO",
+        (variant, structure) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("PHH has no {} {} variant.", structure, variant),
+            ))
+        }
+    };
+    let replay = Replay::hand_with(table, &state.stakes, &state.seats, hand)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let n = replay.seats.len();
+    let first = replay
+        .seats
+        .iter()
+        .position(|s| s.seat > replay.button)
+        .unwrap_or(0);
+    let order: Vec<&SeatState> = (0..n).map(|k| &replay.seats[(first + k) % n]).collect();
+    let player = |seat: u8| {
+        order
+            .iter()
+            .position(|s| s.seat == seat)
+            .expect("Seat is dealt")
+            + 1
+    };
+
+    let mut antes = vec![0; n];
+    for ante in &replay.antes {
+        antes[player(ante.seat) - 1] = ante.amount;
+    }
+    let mut blinds = vec![0; n];
+    for blind in &replay.blinds {
+        blinds[player(blind.seat) - 1] = blind.amount;
+    }
+    let mut actions: Vec<String> = order
+        .iter()
+        .enumerate()
+        .map(|(i, s)| format!("d dh p{} {}", i + 1, cards(&s.hole_cards)))
+        .collect();
+    let mut dealt = Street::Preflop;
+    let mut deal = |actions: &mut Vec<String>, street: Street| {
+        while dealt < street {
+            let next = dealt.next().expect("Streets before the river have a next");
+            let board = &hand.board[dealt.board_len()..next.board_len()];
+            actions.push(format!("d db {}", cards(board)));
+            dealt = next;
+        }
+    };
+    for step in &replay.steps {
+        deal(&mut actions, step.street);
+        let verb = match step.action.action_type {
+            ActionType::Fold => "f".to_string(),
+            ActionType::Check | ActionType::Call => "cc".to_string(),
+            ActionType::AllIn if step.raise == 0 => "cc".to_string(),
+            ActionType::Bet | ActionType::Raise | ActionType::AllIn => format!("cbr {}", step.to),
+        };
+        actions.push(format!("p{} {}", player(step.seat), verb));
+    }
+    deal(&mut actions, replay.street);
+    if replay.showdown.len() > 1 {
+        for &(seat, _) in &replay.showdown {
+            let hole_cards = &replay.seat(seat).expect("Seat is dealt").hole_cards;
+            if hole_cards.iter().all(|c| c.is_known()) {
+                actions.push(format!("p{} sm {}", player(seat), cards(hole_cards)));
+            } else {
+                actions.push(format!("p{} sm", player(seat)));
+            }
+        }
+    }
+
+    writeln!(writer, "variant = {}", quote(variant))?;
+    writeln!(writer, "antes = {}", list(antes))?;
+    writeln!(writer, "blinds_or_straddles = {}", list(blinds))?;
+    if table.betting_structure == BettingStructure::FixedLimit {
+        writeln!(writer, "small_bet = {}", replay.big_blind)?;
+        writeln!(writer, "big_bet = {}", 2 * replay.big_blind)?;
+    } else {
+        writeln!(writer, "min_bet = {}", replay.big_blind)?;
+    }
+    writeln!(
+        writer,
+        "starting_stacks = {}",
+        list(order.iter().map(|s| s.player.stack))
+    )?;
+    writeln!(writer, "actions = [")?;
+    for action in &actions {
+        writeln!(writer, "  {},", quote(action))?;
+    }
+    writeln!(writer, "]")?;
+    writeln!(
+        writer,
+        "players = {}",
+        list(order.iter().map(|s| quote(&s.player.name)))
+    )?;
+    writeln!(writer, "seats = {}", list(order.iter().map(|s| s.seat + 1)))?;
+    writeln!(writer, "seat_count = {}", table.table_size)?;
+    writeln!(writer, "table = {}", quote(&table.name))?;
+    if !table.location.is_empty() {
+        writeln!(writer, "casino = {}", quote(&table.location))?;
+    }
+    writeln!(writer, "hand = {}", hand.id)?;
+    let (year, month, day, hour, minute, second) = civil_from_timestamp(hand.timestamp);
+    writeln!(writer, "year = {}", year)?;
+    writeln!(writer, "month = {}", month)?;
+    writeln!(writer, "day = {}", day)?;
+    writeln!(writer, "time = {:02}:{:02}:{:02}", hour, minute, second)?;
+    writeln!(writer, "time_zone = \"UTC\"")?;
+    writeln!(
+        writer,
+        "finishing_stacks = {}",
+        list(order.iter().map(|s| s.stack))
+    )?;
+    Ok(())
+}
+
+/// Concatenated cards, with "??" for any that are not known.
+fn cards(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|c| {
+            if c.is_known() {
+                c.to_string()
+            } else {
+                "??".to_string()
+            }
+        })
+        .collect()
+}
+
+fn list<T: Display>(values: impl IntoIterator<Item = T>) -> String {
+    let values: Vec<String> = values.into_iter().map(|v| v.to_string()).collect();
+    format!("[{}]", values.join(", "))
+}
+
+/// A TOML basic string.
+fn quote(text: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// A TOML value, with just enough of TOML supported to read PHH files.
+#[derive(Clone, PartialEq, Debug)]
+enum Value {
+    String(String),
+    /// Wide enough for any `u64` as well as negative numbers.
+    Integer(i128),
+    Float(f64),
+    Boolean(bool),
+    /// A date, time or date-time literal as written.
+    DateTime(String),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Integer(i) => u64::try_from(*i).ok(),
+            _ => None,
+        }
+    }
+
+    fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+/// The top-level keys of a TOML document followed by those of each `[section]`.
+struct Document {
+    keys: Vec<(String, Value)>,
+    sections: Vec<(String, Vec<(String, Value)>)>,
+}
+
+fn parse_toml(text: &str) -> Result<Document, String> {
+    let mut parser = Parser {
+        rest: text,
+        line: 1,
+    };
+    let mut document = Document {
+        keys: Vec::new(),
+        sections: Vec::new(),
+    };
+    loop {
+        parser.skip(true);
+        if parser.rest.is_empty() {
+            return Ok(document);
+        }
+        if let Some(rest) = parser.rest.strip_prefix('[') {
+            let end = rest
+                .find(']')
+                .filter(|&end| !rest[..end].contains('\n'))
+                .ok_or_else(|| parser.error("Unclosed section header"))?;
+            let name = rest[..end].trim().trim_matches('"').to_string();
+            document.sections.push((name, Vec::new()));
+            parser.rest = &rest[end + 1..];
+        } else {
+            let end = parser
+                .rest
+                .find('=')
+                .filter(|&end| !parser.rest[..end].contains('\n'))
+                .ok_or_else(|| parser.error("Expected a key"))?;
+            let key = parser.rest[..end].trim().trim_matches('"').to_string();
+            parser.rest = &parser.rest[end + 1..];
+            parser.skip(false);
+            let value = parser.value()?;
+            match document.sections.last_mut() {
+                Some((_, keys)) => keys.push((key, value)),
+                None => document.keys.push((key, value)),
+            }
+        }
+        parser.skip(false);
+        if !(parser.rest.is_empty() || parser.rest.starts_with(['\n', '\r'])) {
+            return Err(parser.error("Expected a new line"));
+        }
+    }
+}
+
+struct Parser<'a> {
+    rest: &'a str,
+    line: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> String {
+        format!("{} on line {}.", message, self.line)
+    }
+
+    /// Skips spaces and comments, and line breaks if `lines` is set.
+    fn skip(&mut self, lines: bool) {
+        loop {
+            self.rest = self.rest.trim_start_matches([' ', '\t']);
+            if self.rest.starts_with('#') {
+                let end = self.rest.find(['\r', '\n']).unwrap_or(self.rest.len());
+                self.rest = &self.rest[end..];
+            } else if let Some(rest) = self
+                .rest
+                .strip_prefix("\r\n")
+                .or_else(|| self.rest.strip_prefix('\n'))
+                .filter(|_| lines)
+            {
+                self.rest = rest;
+                self.line += 1;
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        if let Some(rest) = self.rest.strip_prefix('"') {
+            let mut value = String::new();
+            let mut chars = rest.char_indices();
+            loop {
+                let c = match chars.next() {
+                    None | Some((_, '\n')) => return Err(self.error("Unclosed string")),
+                    Some((i, '"')) => {
+                        self.rest = &rest[i + 1..];
+                        return Ok(Value::String(value));
+                    }
+                    Some((_, '\\')) => match chars.next().map(|(_, c)| c) {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('u') => {
+                            let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("Invalid unicode escape"))?
+                        }
+                        _ => return Err(self.error("Invalid escape")),
+                    },
+                    Some((_, c)) => c,
+                };
+                value.push(c);
+            }
+        }
+        if let Some(rest) = self.rest.strip_prefix('\'') {
+            let end = rest
+                .find(['\'', '\n'])
+                .filter(|&end| rest[end..].starts_with('\''))
+                .ok_or_else(|| self.error("Unclosed string"))?;
+            self.rest = &rest[end + 1..];
+            return Ok(Value::String(rest[..end].to_string()));
+        }
+        if let Some(rest) = self.rest.strip_prefix('[') {
+            self.rest = rest;
+            let mut values = Vec::new();
+            loop {
+                self.skip(true);
+                if let Some(rest) = self.rest.strip_prefix(']') {
+                    self.rest = rest;
+                    return Ok(Value::Array(values));
+                }
+                values.push(self.value()?);
+                self.skip(true);
+                if let Some(rest) = self.rest.strip_prefix(',') {
+                    self.rest = rest;
+                } else if !self.rest.starts_with(']') {
+                    return Err(self.error("Expected , or ]"));
+                }
+            }
+        }
+        let end = self
+            .rest
+            .find(|c: char| {
+                !(c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.' | ':' | '_'))
+            })
+            .unwrap_or(self.rest.len());
+        let token = &self.rest[..end];
+        self.rest = &self.rest[end..];
+        let number = token.replace('_', "");
+        match token {
+            "" => Err(self.error("Expected a value")),
+            "true" => Ok(Value::Boolean(true)),
+            "false" => Ok(Value::Boolean(false)),
+            _ if token.contains(':') || token.get(4..5) == Some("-") => {
+                Ok(Value::DateTime(token.to_string()))
+            }
+            _ => number
+                .parse()
+                .map(Value::Integer)
+                .or_else(|_| number.parse().map(Value::Float))
+                .map_err(|_| self.error(&format!("Invalid value {:?}", token))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::site_id;
+
+    const HAND: &str = r#"
+variant = "NT"
+antes = [0, 0, 0]
+blinds_or_straddles = [50, 100, 0]
+min_bet = 100
+starting_stacks = [10000, 5000, 8000]
+actions = [
+  "d dh p1 ????",
+  "d dh p2 ????",
+  "d dh p3 AcAs",  # Carol
+  "p3 cbr 300",
+  "p1 f",
+  "p2 cc",
+  "d db 3c3hKc",
+  "p2 cc",
+  "p3 cbr 400",
+  "p2 cbr 4700",
+  "p3 cc",
+  "d db 7d",
+  "d db 2s",
+  "p2 sm KdKh",
+  "p3 sm AcAs",
+]
+players = ["Alice", "Bob", 'Carol']
+table = "Main"
+hand = 42
+year = 2024
+month = 8
+day = 22
+time = 02:24:36
+"#;
+
+    fn hands(session: &Session) -> Vec<&Hand> {
+        session
+            .tables
+            .iter()
+            .flat_map(|t| &t.events)
+            .filter_map(|e| match e {
+                TableEvent::Hand(hand) => Some(hand),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_import() -> io::Result<()> {
+        let import = Phh.import(&mut HAND.as_bytes())?;
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        let table = &import.session.tables[0];
+        assert_eq!(table.name, "Main");
+        assert_eq!(table.blinds, vec![50, 100]);
+        assert_eq!(table.initial_context[2].id, site_id(SITE, "Carol"));
+        let hand = hands(&import.session)[0];
+        assert_eq!(hand.id, 42);
+        assert_eq!(hand.timestamp, 1724293476);
+        assert_eq!(hand.button_position, 2);
+        assert_eq!(hand.actions[0].action_type, ActionType::Raise);
+        assert_eq!(hand.actions[3].action_type, ActionType::Check);
+        assert_eq!(hand.actions[4].action_type, ActionType::Bet);
+        assert_eq!(hand.actions[5].action_type, ActionType::AllIn);
+        assert_eq!(hand.actions[5].bet_amount, 4700);
+        assert_eq!(hand.actions[6].action_type, ActionType::Call);
+        assert_eq!(hand.board[4], Card::TwoSpades);
+        assert_eq!(
+            hand.hole_cards,
+            vec![
+                vec![Card::Unknown; 2],
+                vec![Card::KingDiamonds, Card::KingHearts],
+                vec![Card::AceClubs, Card::AceSpades]
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_rejects_unequal_antes() -> io::Result<()> {
+        let history = HAND.replace("antes = [0, 0, 0]", "antes = [0, 0, 25]");
+        let import = import(history.as_bytes())?;
+        assert_eq!(
+            import.warnings,
+            vec!["Hand 42 skipped: Only equal antes from every player are supported."]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip() -> io::Result<()> {
+        let import = import(HAND.as_bytes())?;
+        let mut written = Vec::new();
+        write_session(&mut written, &import.session)?;
+        let text = String::from_utf8(written).unwrap();
+        assert!(text.starts_with("[1]\nvariant = \"NT\"\n"));
+        assert!(text.contains("blinds_or_straddles = [50, 100, 0]\n"));
+        assert!(text.contains("  \"p2 cbr 4700\",\n"));
+        assert!(text.contains("finishing_stacks = [9950, 10050, 3000]\n"));
+        assert_eq!(
+            hands(&import.session),
+            hands(&self::import(text.as_bytes())?.session)
+        );
+
+        let session = Session::exhaustive();
+        let mut written = Vec::new();
+        write_session(&mut written, &session)?;
+        let text = String::from_utf8(written).unwrap();
+        // Heads-up, the button is the second player and posts the small blind.
+        assert!(text.contains("blinds_or_straddles = [100, 50]\n"));
+        let import = self::import(text.as_bytes())?;
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        let (original, read) = (hands(&session), hands(&import.session));
+        assert_eq!(original[0], read[0]);
+        assert_eq!(original[1].actions, read[1].actions);
+        assert_eq!(original[1].board, read[1].board);
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip_level_change() -> io::Result<()> {
+        let session = Session::sit_and_go();
+        let mut written = Vec::new();
+        write_session(&mut written, &session)?;
+        let text = String::from_utf8(written).unwrap();
+        let second = text.split("\n[2]\n").nth(1).unwrap();
+        assert!(second.contains("antes = [5, 5, 5]\n"));
+        assert!(second.contains("blinds_or_straddles = [20, 40, 0]\n"));
+        assert!(second.contains("min_bet = 40\n"));
+        let import = self::import(text.as_bytes())?;
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        // The ante is imported as a level change before the second hand, at a table of its
+        // own as its blinds differ.
+        let table = &import.session.tables[1];
+        assert!(table.events.iter().any(|e| matches!(
+            e,
+            TableEvent::LevelChange(level) if level.blinds == [20, 40] && level.ante == 5
+        )));
+        let (original, read) = (hands(&session), hands(&import.session));
+        assert_eq!(original[1].hole_cards, read[1].hole_cards);
+        assert_eq!(original[1].actions, read[1].actions);
+        assert_eq!(original[1].board, read[1].board);
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip_omaha() -> io::Result<()> {
+        let session = Session::omaha(Variant::Omaha);
+        let mut written = Vec::new();
+        write_session(&mut written, &session)?;
+        let text = String::from_utf8(written).unwrap();
+        assert!(text.starts_with("[1]\nvariant = \"PO\"\n"));
+        let import = self::import(text.as_bytes())?;
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        let table = &import.session.tables[0];
+        assert_eq!(table.variant, Variant::Omaha);
+        assert_eq!(table.betting_structure, BettingStructure::PotLimit);
+        let (original, read) = (hands(&session), hands(&import.session));
+        assert_eq!(original[0], read[0]);
+        assert_eq!(original[1].hole_cards[0], read[1].hole_cards[0]);
+        assert_eq!(original[1].actions, read[1].actions);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_toml() {
+        let document = parse_toml("a = \"x\\\"y\" # note\n[1]\nb = [1,\n 2.5, true,]\n").unwrap();
+        assert_eq!(
+            document.keys,
+            vec![("a".to_string(), Value::String("x\"y".to_string()))]
+        );
+        assert_eq!(
+            document.sections[0].1[0].1,
+            Value::Array(vec![
+                Value::Integer(1),
+                Value::Float(2.5),
+                Value::Boolean(true)
+            ])
+        );
+        assert!(parse_toml("a = [1, 2").is_err());
+        assert!(parse_toml("a = 1 b = 2").is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use crate::formats::builder::{split_actor, BuiltHand, HandBuilder, SessionBuilder, SiteAction};
+use crate::formats::{
+    bracketed_cards, parse_amount, sniff_text, timestamp_from_civil, HandHistoryImporter, Import,
+};
+use crate::game::{Decimal, TableSize};
+use std::io::{self, Read};
+
+const SITE: &str = "888poker";
+
+const VERBS: [&str; 9] = [
+    "posts",
+    "folds",
+    "checks",
+    "calls",
+    "bets",
+    "raises",
+    "is all-In",
+    "shows",
+    "collected",
+];
+
+/// The [`HandHistoryImporter`] for 888poker hand histories.
+pub struct Poker888;
+
+impl HandHistoryImporter for Poker888 {
+    fn name(&self) -> &'static str {
+        SITE
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        let text = sniff_text(head);
+        text.starts_with("#Game No : ") || text.contains("***** 888poker Hand History")
+    }
+
+    fn import(&self, reader: &mut dyn Read) -> io::Result<Import> {
+        import(reader)
+    }
+}
+
+/// Imports 888poker No Limit Hold'em hand histories.
+///
+/// 888poker writes bets and raises as the amount added by the action, not the total.
+pub fn import<R: Read>(mut reader: R) -> io::Result<Import> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut session = SessionBuilder::new(SITE);
+    let mut block: Vec<&str> = Vec::new();
+    for line in text.lines().map(|l| l.trim_end_matches('\r')) {
+        if line.starts_with("#Game No : ") && !block.is_empty() {
+            import_hand(&mut session, &block);
+            block.clear();
+        }
+        if !line.trim().is_empty() {
+            block.push(line);
+        }
+    }
+    if !block.is_empty() {
+        import_hand(&mut session, &block);
+    }
+    Ok(session.finish())
+}
+
+fn import_hand(session: &mut SessionBuilder, lines: &[&str]) {
+    if let Err(e) = parse_hand(session, lines) {
+        let id = lines[0].strip_prefix("#Game No : ").unwrap_or(lines[0]);
+        session.warn(format!("Hand {} skipped: {}", id, e));
+    }
+}
+
+/// Parses a bracketed amount such as "[$0.08]" or "[ $0.27 ]".
+fn bracketed_amount(text: &str) -> Result<Decimal, String> {
+    parse_amount(text.trim().trim_start_matches('[').trim_end_matches(']'))
+        .map_err(|e| e.to_string())
+}
+
+fn parse_hand(session: &mut SessionBuilder, lines: &[&str]) -> Result<(), String> {
+    let malformed = |line: &str| format!("Malformed line {:?}.", line);
+    let line = |index: usize| lines.get(index).copied().unwrap_or_default();
+    let cards = |line: &str| bracketed_cards(line).map_err(|e| e.to_string());
+
+    let id = line(0)
+        .strip_prefix("#Game No : ")
+        .and_then(|id| id.trim().parse().ok())
+        .ok_or_else(|| malformed(line(0)))?;
+
+    // $0.01/$0.02 Blinds No Limit Holdem - *** 13 09 2020 18:44:53
+    let (game, date) = line(2)
+        .split_once(" - *** ")
+        .ok_or_else(|| malformed(line(2)))?;
+    let (stakes, game) = game
+        .split_once(" Blinds ")
+        .ok_or_else(|| malformed(line(2)))?;
+    if game != "No Limit Holdem" {
+        return Err("Only No Limit Hold'em is supported.".to_string());
+    }
+    let blinds = stakes
+        .split('/')
+        .map(|b| parse_amount(b).map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let fields: Vec<u32> = date
+        .split([' ', ':'])
+        .filter(|f| !f.is_empty())
+        .map(|f| f.parse().map_err(|_| malformed(line(2))))
+        .collect::<Result<_, _>>()?;
+    let [day, month, year, hour, minute, second] = fields[..] else {
+        return Err(malformed(line(2)));
+    };
+    let timestamp = timestamp_from_civil(year as i64, month, day, hour, minute, second);
+
+    // Table Kiev 6 Max (Real Money)
+    let (table, size) = line(3)
+        .strip_prefix("Table ")
+        .and_then(|t| t.rsplit_once(" Max"))
+        .and_then(|(t, _)| t.rsplit_once(' '))
+        .ok_or_else(|| malformed(line(3)))?;
+    let table_size: TableSize = size.parse().map_err(|_| malformed(line(3)))?;
+    let button = line(4)
+        .strip_prefix("Seat ")
+        .and_then(|b| b.strip_suffix(" is the button"))
+        .and_then(|b| b.parse::<u8>().ok())
+        .filter(|&b| b >= 1)
+        .ok_or_else(|| malformed(line(4)))?
+        - 1;
+
+    let mut seats = vec![None; table_size as usize];
+    let seat_lines = lines[6..].iter().take_while(|l| l.starts_with("Seat "));
+    for seat_line in seat_lines.clone() {
+        let (number, rest) = seat_line[5..]
+            .split_once(": ")
+            .ok_or_else(|| malformed(seat_line))?;
+        let (name, chips) = rest
+            .rsplit_once(" ( ")
+            .ok_or_else(|| malformed(seat_line))?;
+        let seat: usize = number.parse().map_err(|_| malformed(seat_line))?;
+        let slot = seat
+            .checked_sub(1)
+            .and_then(|s| seats.get_mut(s))
+            .ok_or_else(|| malformed(seat_line))?;
+        let chips = parse_amount(chips.trim_end_matches(" )")).map_err(|e| e.to_string())?;
+        *slot = Some(session.player(name, chips));
+    }
+
+    let mut hand = HandBuilder::new(id, timestamp, button, blinds.clone(), seats)?;
+    let mut hero = None;
+    let mut board = Vec::new();
+    for line in lines[6 + seat_lines.count()..].iter() {
+        if let Some(street) = line.strip_prefix("** Dealing ") {
+            if !street.starts_with("down cards") {
+                board.extend(cards(street)?);
+                hand.board(&board);
+            }
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("Dealt to ") {
+            if let Some((name, _)) = rest.split_once(" [") {
+                hand.show(name, &cards(line)?)?;
+                hero = Some(name.to_string());
+            }
+            continue;
+        }
+        let Some((name, verb, rest)) = split_actor(line, &VERBS) else {
+            continue;
+        };
+        match verb {
+            "posts" if rest.starts_with("small blind") || rest.starts_with("big blind") => {
+                let (_, value) = rest.split_once('[').ok_or_else(|| malformed(line))?;
+                hand.post(name, bracketed_amount(value)?)?
+            }
+            "posts" => return Err(format!("Unsupported post {:?}.", rest)),
+            "folds" => hand.act(name, SiteAction::Fold, false)?,
+            "checks" => hand.act(name, SiteAction::Check, false)?,
+            "calls" => hand.act(name, SiteAction::Call, false)?,
+            "bets" | "raises" => hand.act(name, SiteAction::By(bracketed_amount(rest)?), false)?,
+            "is all-In" => hand.act(name, SiteAction::By(bracketed_amount(rest)?), true)?,
+            "shows" => hand.show(name, &cards(rest)?)?,
+            "collected" => hand.collect(name, bracketed_amount(rest)?)?,
+            _ => {}
+        }
+    }
+
+    let BuiltHand {
+        seats,
+        hand,
+        warnings,
+        ..
+    } = hand.finish()?;
+    if let Some(hero) = hero {
+        session.hero(&hero);
+    }
+    for warning in warnings {
+        session.warn(warning);
+    }
+    session.push(table, table_size, &blinds, seats, hand);
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::formats::site_id;
+    use crate::game::{ActionType, TableEvent};
+    use crate::types::card::Card;
+
+    pub(crate) const HISTORY: &str = "\
+#Game No : 678797521
+***** 888poker Hand History for Game 678797521 *****
+$0.01/$0.02 Blinds No Limit Holdem - *** 13 09 2020 18:44:53
+Table Kiev 6 Max (Real Money)
+Seat 4 is the button
+Total number of players : 3
+Seat 1: Player1 ( $1.69 )
+Seat 4: Hero ( $2 )
+Seat 6: Player6 ( $2.93 )
+Player6 posts small blind [$0.01]
+Player1 posts big blind [$0.02]
+** Dealing down cards **
+Dealt to Hero [ 6d, 6c ]
+Hero raises [$0.06]
+Player6 raises [$0.17]
+Player1 folds
+Hero calls [$0.12]
+** Dealing flop ** [ 8h, 3s, 7c ]
+Player6 bets [$0.20]
+Hero is all-In  [$1.82]
+Player6 calls [$1.62]
+** Dealing turn ** [ 2d ]
+** Dealing river ** [ Ks ]
+** Summary **
+Player6 shows [ Ah, Kh ]
+Hero shows [ 6d, 6c ]
+Player6 collected [ $4.02 ]
+";
+
+    #[test]
+    fn test_import() -> io::Result<()> {
+        let import = Poker888.import(&mut HISTORY.as_bytes())?;
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        let session = import.session;
+        assert_eq!(session.hero_id, site_id(SITE, "Hero"));
+        let table = &session.tables[0];
+        assert_eq!(table.name, "Kiev");
+        assert_eq!(table.table_size, 6);
+        let TableEvent::Hand(hand) = &table.events.last().unwrap() else {
+            panic!("Expected a hand");
+        };
+        assert_eq!(hand.id, 678797521);
+        assert_eq!(hand.timestamp, 1600022693);
+        assert_eq!(hand.button_position, 3);
+        // Player6 had posted 1 cent, so "raises [$0.17]" is to 18 cents.
+        assert_eq!(hand.actions[1].bet_amount, 18);
+        assert_eq!(hand.actions[5].action_type, ActionType::AllIn);
+        assert_eq!(hand.actions[5].bet_amount, 182);
+        assert_eq!(hand.board[4], Card::KingSpades);
+        assert_eq!(hand.hole_cards[2], [Card::AceHearts, Card::KingHearts]);
+        Ok(())
+    }
+}
@@ -0,0 +1,486 @@
+use crate::engine::replay::{Replay, Step, Street};
+use crate::engine::state::TableState;
+use crate::formats::civil_from_timestamp;
+use crate::game::{
+    ActionType, BettingStructure, ClockEventType, Decimal, Hand, Id, Session, Table, TableEvent,
+    Variant,
+};
+use crate::types::card::Card;
+use std::io::{self, Write};
+
+/// Writes every [`Hand`] of a [`Session`] as PokerStars hand history text, dealing to the hero.
+pub fn write_session<W: Write>(writer: &mut W, session: &Session) -> io::Result<()> {
+    for table in &session.tables {
+        write_table(writer, table, Some(session.hero_id))?;
+    }
+    Ok(())
+}
+
+/// Writes every [`Hand`] at a [`Table`], tracking seats and stacks through its events.
+pub fn write_table<W: Write>(writer: &mut W, table: &Table, hero: Option<Id>) -> io::Result<()> {
+    let mut state = TableState::new(table);
+    for event in &table.events {
+        if let TableEvent::Hand(hand) = event {
+            write_hand(writer, table, &state, hand, hero)?;
+        }
+        state
+            .apply(table, event)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    }
+    Ok(())
+}
+
+/// Writes a single [`Hand`] dealt to the seats and at the stakes of a [`TableState`] as
+/// PokerStars hand history text.
+///
+/// Only the hero's hole cards are dealt face up; other known hands are revealed at showdown.
+/// Hands run on more than one board cannot be written.
+pub fn write_hand<W: Write>(
+    writer: &mut W,
+    table: &Table,
+    state: &TableState,
+    hand: &Hand,
+    hero: Option<Id>,
+) -> io::Result<()> {
+    if !hand.runouts.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Hand {} is run on more than one board.", hand.id),
+        ));
+    }
+    let replay = Replay::hand_with(table, &state.stakes, &state.seats, hand)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let name = |seat: u8| {
+        replay
+            .seat(seat)
+            .map_or("", |s| s.player.name.as_str())
+            .to_string()
+    };
+
+    let (year, month, day, hour, minute, second) = civil_from_timestamp(hand.timestamp);
+    writeln!(
+        writer,
+        "PokerStars Hand #{}:  {} {} ({}/{} USD) - {}/{:02}/{:02} {}:{:02}:{:02} UTC",
+        hand.id,
+        match table.variant {
+            Variant::Holdem => "Hold'em",
+            Variant::Omaha => "Omaha",
+            Variant::Omaha5 => "5 Card Omaha",
+            Variant::Omaha6 => "6 Card Omaha",
+            Variant::ShortDeck => "6+ Hold'em",
+        },
+        match table.betting_structure {
+            BettingStructure::NoLimit => "No Limit",
+            BettingStructure::PotLimit => "Pot Limit",
+            BettingStructure::FixedLimit => "Limit",
+        },
+        stake(state.stakes.blinds.first().copied().unwrap_or(0)),
+        stake(replay.big_blind),
+        year,
+        month,
+        day,
+        hour,
+        minute,
+        second
+    )?;
+    writeln!(
+        writer,
+        "Table '{}' {}-max Seat #{} is the button",
+        table.name,
+        table.table_size,
+        hand.button_position as u32 + 1
+    )?;
+    for seat in &replay.seats {
+        writeln!(
+            writer,
+            "Seat {}: {} ({} in chips)",
+            seat.seat as u32 + 1,
+            seat.player.name,
+            money(seat.player.stack)
+        )?;
+    }
+    for ante in &replay.antes {
+        writeln!(
+            writer,
+            "{}: posts the ante {}",
+            name(ante.seat),
+            money(ante.amount)
+        )?;
+    }
+    for (k, blind) in replay.blinds.iter().enumerate() {
+        let kind = match k {
+            0 => "small blind",
+            1 => "big blind",
+            _ => "straddle",
+        };
+        writeln!(
+            writer,
+            "{}: posts {} {}{}",
+            name(blind.seat),
+            kind,
+            money(blind.amount),
+            all_in_suffix(replay.seat(blind.seat).is_some_and(|s| {
+                s.all_in && replay.steps.iter().all(|step| step.seat != blind.seat)
+            }))
+        )?;
+    }
+    writeln!(writer, "*** HOLE CARDS ***")?;
+    if let Some(seat) = replay.seats.iter().find(|s| Some(s.player.id) == hero) {
+        if seat.hole_cards.iter().all(|c| c.is_known()) {
+            writeln!(
+                writer,
+                "Dealt to {} [{}]",
+                seat.player.name,
+                cards(&seat.hole_cards)
+            )?;
+        }
+    }
+
+    let last_betting_street = replay.steps.last().map_or(Street::Preflop, |s| s.street);
+    let mut street = Street::Preflop;
+    loop {
+        if street != Street::Preflop {
+            let dealt = street.board_len();
+            let header = match street {
+                Street::Flop => "FLOP",
+                Street::Turn => "TURN",
+                _ => "RIVER",
+            };
+            if street == Street::Flop {
+                writeln!(writer, "*** {} *** [{}]", header, cards(&hand.board[..3]))?;
+            } else {
+                writeln!(
+                    writer,
+                    "*** {} *** [{}] [{}]",
+                    header,
+                    cards(&hand.board[..dealt - 1]),
+                    hand.board[dealt - 1]
+                )?;
+            }
+        }
+        let clock = |writer: &mut W, action: usize| -> io::Result<()> {
+            for event in hand.clock_events.iter().filter(|e| e.action == action) {
+                let text = match event.event_type {
+                    ClockEventType::TimeBank => "has requested TIME",
+                    ClockEventType::Disconnect => "is disconnected",
+                    ClockEventType::Timeout => "has timed out",
+                };
+                writeln!(writer, "{} {}", name(event.seat), text)?;
+            }
+            Ok(())
+        };
+        for (i, step) in replay.steps.iter().enumerate() {
+            if step.street == street {
+                clock(writer, i)?;
+                writeln!(writer, "{}: {}", name(step.seat), describe(step))?;
+            }
+        }
+        if street == last_betting_street {
+            clock(writer, replay.steps.len())?;
+            if let Some((seat, amount)) = replay.uncalled {
+                writeln!(
+                    writer,
+                    "Uncalled bet ({}) returned to {}",
+                    money(amount),
+                    name(seat)
+                )?;
+            }
+        }
+        match street.next() {
+            Some(next) if next <= replay.street => street = next,
+            _ => break,
+        }
+    }
+
+    if !replay.showdown.is_empty() {
+        writeln!(writer, "*** SHOW DOWN ***")?;
+        for (seat, rank) in &replay.showdown {
+            match (rank, replay.seat(*seat)) {
+                (Some(rank), Some(state)) => writeln!(
+                    writer,
+                    "{}: shows [{}] ({})",
+                    name(*seat),
+                    cards(&state.hole_cards),
+                    rank
+                )?,
+                _ => writeln!(writer, "{}: mucks hand", name(*seat))?,
+            }
+        }
+    }
+    for (k, pot) in replay.pots.iter().enumerate() {
+        let source = match (replay.pots.len(), k) {
+            (1, _) => "pot".to_string(),
+            (_, 0) => "main pot".to_string(),
+            (2, _) => "side pot".to_string(),
+            (_, k) => format!("side pot-{}", k),
+        };
+        for (seat, amount) in &pot.winners {
+            writeln!(
+                writer,
+                "{} collected {} from {}",
+                name(*seat),
+                money(*amount),
+                source
+            )?;
+        }
+    }
+    if replay.showdown.is_empty() {
+        if let Some(seat) = replay.seats.iter().find(|s| !s.folded) {
+            writeln!(writer, "{}: doesn't show hand", seat.player.name)?;
+        }
+    }
+
+    writeln!(writer, "*** SUMMARY ***")?;
+    write!(writer, "Total pot {}", money(replay.total_pot()))?;
+    if replay.pots.len() > 1 {
+        write!(writer, " Main pot {}.", money(replay.pots[0].amount))?;
+        for (k, pot) in replay.pots.iter().enumerate().skip(1) {
+            if replay.pots.len() == 2 {
+                write!(writer, " Side pot {}.", money(pot.amount))?;
+            } else {
+                write!(writer, " Side pot-{} {}.", k, money(pot.amount))?;
+            }
+        }
+    }
+    writeln!(writer, " | Rake {}", money(replay.rake))?;
+    if replay.street != Street::Preflop {
+        writeln!(writer, "Board [{}]", cards(replay.dealt_board()))?;
+    }
+    for seat in &replay.seats {
+        write!(
+            writer,
+            "Seat {}: {}",
+            seat.seat as u32 + 1,
+            seat.player.name
+        )?;
+        if seat.seat == replay.button {
+            write!(writer, " (button)")?;
+        }
+        match replay.blinds.iter().position(|b| b.seat == seat.seat) {
+            Some(0) => write!(writer, " (small blind)")?,
+            Some(1) => write!(writer, " (big blind)")?,
+            _ => {}
+        }
+        let collected = replay.collected(seat.seat);
+        let showdown = replay.showdown.iter().find(|(s, _)| *s == seat.seat);
+        if seat.folded {
+            let folded_on = replay
+                .steps
+                .iter()
+                .find(|s| s.seat == seat.seat && s.action.action_type == ActionType::Fold)
+                .map_or(Street::Preflop, |s| s.street);
+            if folded_on == Street::Preflop {
+                write!(writer, " folded before Flop")?;
+                if seat.committed == 0 {
+                    write!(writer, " (didn't bet)")?;
+                }
+            } else {
+                write!(writer, " folded on the {}", folded_on)?;
+            }
+        } else {
+            match showdown {
+                Some((_, Some(rank))) if collected > 0 => write!(
+                    writer,
+                    " showed [{}] and won ({}) with {}",
+                    cards(&seat.hole_cards),
+                    money(collected),
+                    rank
+                )?,
+                Some((_, Some(rank))) => write!(
+                    writer,
+                    " showed [{}] and lost with {}",
+                    cards(&seat.hole_cards),
+                    rank
+                )?,
+                Some((_, None)) => write!(writer, " mucked")?,
+                None => write!(writer, " collected ({})", money(collected))?,
+            }
+        }
+        writeln!(writer)?;
+    }
+    writeln!(writer)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Describes a [`Step`] the way PokerStars prints actions (e.g. "raises $2 to $3").
+fn describe(step: &Step) -> String {
+    let suffix = all_in_suffix(step.all_in);
+    match step.action.action_type {
+        ActionType::Fold => "folds".to_string(),
+        ActionType::Check => "checks".to_string(),
+        _ if step.raise == 0 => format!("calls {}{}", money(step.amount), suffix),
+        // A raise from nothing is an opening bet.
+        _ if step.raise == step.to => format!("bets {}{}", money(step.amount), suffix),
+        _ => format!(
+            "raises {} to {}{}",
+            money(step.raise),
+            money(step.to),
+            suffix
+        ),
+    }
+}
+
+fn all_in_suffix(all_in: bool) -> &'static str {
+    if all_in {
+        " and is all-in"
+    } else {
+        ""
+    }
+}
+
+/// Formats cents as dollars, omitting the cents of whole dollar amounts (e.g. "$1", "$0.50").
+fn money(cents: Decimal) -> String {
+    if cents.is_multiple_of(100) {
+        format!("${}", cents / 100)
+    } else {
+        format!("${}.{:02}", cents / 100, cents % 100)
+    }
+}
+
+/// Formats cents as dollars with cents, as used by the stakes in the header.
+fn stake(cents: Decimal) -> String {
+    format!("${}.{:02}", cents / 100, cents % 100)
+}
+
+fn cards(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ClockEvent;
+
+    fn export(session: &Session) -> String {
+        let mut buffer = Vec::new();
+        write_session(&mut buffer, session).expect("Export succeeds");
+        String::from_utf8(buffer).expect("Valid UTF-8")
+    }
+
+    #[test]
+    fn test_export_fold_on_flop() {
+        let mut session = Session::exhaustive();
+        session.hero_id = 1001;
+        let text = export(&session);
+        let hand = text.split("\n\n\n").next().unwrap();
+        let expected = "\
+PokerStars Hand #9001:  Hold'em No Limit ($0.50/$1.00 USD) - 2024/08/22 2:24:36 UTC
+Table 'Table 1' 2-max Seat #2 is the button
+Seat 1: Player 1001 ($100 in chips)
+Seat 2: Player 1002 ($100 in chips)
+Player 1002: posts small blind $0.50
+Player 1001: posts big blind $1
+*** HOLE CARDS ***
+Dealt to Player 1001 [Ac As]
+Player 1002: raises $2 to $3
+Player 1001: calls $2
+*** FLOP *** [3c 3h Kc]
+Player 1001: bets $6
+Player 1002: raises $12 to $18
+Player 1001: folds
+Uncalled bet ($12) returned to Player 1002
+Player 1002 collected $17.97 from pot
+Player 1002: doesn't show hand
+*** SUMMARY ***
+Total pot $18 | Rake $0.03
+Board [3c 3h Kc]
+Seat 1: Player 1001 (big blind) folded on the Flop
+Seat 2: Player 1002 (button) (small blind) collected ($17.97)";
+        assert_eq!(hand, expected);
+    }
+
+    #[test]
+    fn test_export_showdown() {
+        let text = export(&Session::exhaustive());
+        let hand = text.split("\n\n\n").nth(1).unwrap();
+        assert!(hand.starts_with("PokerStars Hand #9002:"));
+        assert!(hand.contains("Seat 1: Player 1003 ($150 in chips)"));
+        assert!(hand.contains("Seat 2: Player 1002 ($200 in chips)"));
+        assert!(!hand.contains("Dealt to"));
+        assert!(hand.contains("*** RIVER *** [7h 7s 7c 7d] [8h]"));
+        assert!(hand.contains("Player 1003: shows [Ac As] (four of a kind, Sevens)"));
+        assert!(hand.contains("Player 1002: mucks hand"));
+        assert!(hand.contains("Player 1003 collected $33.97 from pot"));
+        assert!(hand.contains(
+            "Seat 1: Player 1003 (button) (small blind) showed [Ac As] and won ($33.97) with four of a kind, Sevens"
+        ));
+        assert!(hand.contains("Seat 2: Player 1002 (big blind) mucked"));
+    }
+
+    #[test]
+    fn test_export_level_change() {
+        let text = export(&Session::sit_and_go());
+        let hand = text.split("\n\n\n").nth(1).unwrap();
+        // The second hand is played at level 2, not at the table's initial blinds.
+        assert!(hand.starts_with("PokerStars Hand #2:  Hold'em No Limit ($0.20/$0.40 USD)"));
+        assert!(hand.contains("Player 1: posts the ante $0.05\n"));
+        assert!(
+            hand.contains("Player 3: posts small blind $0.20\nPlayer 1: posts big blind $0.40\n")
+        );
+    }
+
+    #[test]
+    fn test_export_bomb_pot() {
+        let mut session = Session::exhaustive();
+        session.hero_id = 1001;
+        let TableEvent::Hand(hand) = &mut session.tables[0].events[0] else {
+            panic!("Expected a hand");
+        };
+        hand.bomb_pot = Some(100);
+        hand.actions.drain(..2);
+        let text = export(&session);
+        let hand = text.split("\n\n\n").next().unwrap();
+        assert!(hand.contains(
+            "Player 1001: posts the ante $1\nPlayer 1002: posts the ante $1\n*** HOLE CARDS ***\n"
+        ));
+        assert!(!hand.contains("blind"));
+        assert!(hand.contains("Dealt to Player 1001 [Ac As]\n*** FLOP *** [3c 3h Kc]\n"));
+        assert!(hand.contains("Player 1002 collected $13.97 from pot"));
+    }
+
+    #[test]
+    fn test_export_clock_events() {
+        let mut session = Session::exhaustive();
+        let TableEvent::Hand(hand) = &mut session.tables[0].events[0] else {
+            panic!("Expected a hand");
+        };
+        hand.clock_events = vec![
+            ClockEvent {
+                event_type: ClockEventType::TimeBank,
+                seat: 0,
+                action: 4,
+                timestamp: None,
+            },
+            ClockEvent {
+                event_type: ClockEventType::Disconnect,
+                seat: 0,
+                action: 4,
+                timestamp: None,
+            },
+            ClockEvent {
+                event_type: ClockEventType::Timeout,
+                seat: 0,
+                action: 4,
+                timestamp: None,
+            },
+        ];
+        let text = export(&session);
+        assert!(text.contains(
+            "Player 1002: raises $12 to $18\nPlayer 1001 has requested TIME\n\
+             Player 1001 is disconnected\nPlayer 1001 has timed out\nPlayer 1001: folds\n"
+        ));
+    }
+
+    #[test]
+    fn test_money() {
+        assert_eq!(money(50), "$0.50");
+        assert_eq!(money(100), "$1");
+        assert_eq!(money(1797), "$17.97");
+        assert_eq!(stake(100), "$1.00");
+    }
+}
@@ -0,0 +1,312 @@
+use crate::engine::state::TableState;
+use crate::formats::acpc::AcpcLog;
+use crate::formats::bin::HeirBin;
+use crate::formats::builder::seat_updates;
+use crate::formats::ggpoker::GgPoker;
+use crate::formats::ipoker::IPoker;
+use crate::formats::json::Json;
+use crate::formats::md::HeirMd;
+use crate::formats::phh::Phh;
+use crate::formats::poker888::Poker888;
+use crate::formats::winamax::Winamax;
+use crate::formats::{HandHistoryImporter, Import};
+use crate::game::{Annotations, Player, Session};
+use crate::types::version::Version;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// How many bytes from the start of a file are given to [`HandHistoryImporter::sniff`].
+pub const SNIFF_LEN: usize = 4096;
+
+/// A set of [`HandHistoryImporter`]s, used to detect and import files of any known format.
+///
+/// [`Registry::default`] holds every importer in this crate.
+pub struct Registry {
+    importers: Vec<Box<dyn HandHistoryImporter>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Registry::empty();
+        registry.register(HeirBin);
+        registry.register(HeirMd);
+        registry.register(Json);
+        registry.register(GgPoker);
+        registry.register(Winamax);
+        registry.register(Poker888);
+        registry.register(IPoker);
+        registry.register(Phh);
+        registry.register(AcpcLog::default());
+        registry
+    }
+}
+
+impl Registry {
+    /// A registry without any importers.
+    pub fn empty() -> Self {
+        Registry {
+            importers: Vec::new(),
+        }
+    }
+
+    /// Adds an importer, which is tried after those already registered.
+    pub fn register<I: HandHistoryImporter + 'static>(&mut self, importer: I) {
+        self.importers.push(Box::new(importer));
+    }
+
+    pub fn importers(&self) -> impl Iterator<Item = &dyn HandHistoryImporter> {
+        self.importers.iter().map(|i| i.as_ref())
+    }
+
+    /// The first importer that recognises the start of a file.
+    pub fn detect(&self, head: &[u8]) -> Option<&dyn HandHistoryImporter> {
+        let head = &head[..head.len().min(SNIFF_LEN)];
+        self.importers().find(|i| i.sniff(head))
+    }
+
+    /// Imports hand histories in whichever registered format they are in.
+    pub fn import(&self, reader: &mut dyn Read) -> io::Result<Import> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let importer = self.detect(&bytes).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Unrecognised hand history format.",
+            )
+        })?;
+        importer.import(&mut bytes.as_slice())
+    }
+
+    /// Imports every file in a folder and its subfolders into a single [`Session`].
+    ///
+    /// Files are read in path order. Files that are not in a registered format or fail to
+    /// import are skipped with a warning, and every warning is prefixed by its file's path.
+    pub fn import_dir(&self, dir: &Path) -> io::Result<Import> {
+        let mut paths = Vec::new();
+        collect_files(dir, &mut paths)?;
+        paths.sort();
+        let name = dir
+            .file_name()
+            .map_or_else(|| dir.display().to_string(), |n| n.to_string_lossy().into());
+        self.import_files(name, &paths)
+    }
+
+    /// Imports the given files into a single [`Session`] with the given name.
+    pub fn import_files(&self, name: String, paths: &[PathBuf]) -> io::Result<Import> {
+        let mut merged = Import {
+            session: Session {
+                version: Version::CURRENT,
+                id: 0,
+                name,
+                tables: Vec::new(),
+                hero_id: 0,
+                tournament: None,
+                annotations: Annotations::default(),
+            },
+            warnings: Vec::new(),
+        };
+        for path in paths {
+            let bytes = fs::read(path)?;
+            let Some(importer) = self.detect(&bytes) else {
+                merged.warnings.push(format!(
+                    "{}: Unrecognised hand history format, skipped.",
+                    path.display()
+                ));
+                continue;
+            };
+            match importer.import(&mut bytes.as_slice()) {
+                Ok(import) => {
+                    let warnings = import.warnings.iter();
+                    merged
+                        .warnings
+                        .extend(warnings.map(|w| format!("{}: {}", path.display(), w)));
+                    merge(&mut merged.session, import.session);
+                }
+                Err(e) => merged.warnings.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+        Ok(merged)
+    }
+}
+
+fn collect_files(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, paths)?;
+        } else {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Adds a [`Session`]'s tables to another, continuing tables with the same [`Id`].
+///
+/// A continued table gets the seat and stack updates from where it left off to the
+/// continuation's initial context. Annotations on the same hand, action or player are
+/// combined, keeping the first label and joining tags and notes.
+///
+/// [`Id`]: crate::game::Id
+pub fn merge(session: &mut Session, other: Session) {
+    if session.hero_id == 0 {
+        session.hero_id = other.hero_id;
+    }
+    if session.tournament.is_none() {
+        session.tournament = other.tournament;
+    }
+    for table in other.tables {
+        let Some(existing) = session.tables.iter_mut().find(|t| t.id == table.id) else {
+            session.tables.push(table);
+            continue;
+        };
+        let mut state = TableState::new(existing);
+        for event in &existing.events {
+            // A table that does not replay still has its seats updated.
+            let _ = state.apply(existing, event);
+        }
+        let seats: Vec<Option<Player>> = table.initial_context.into_iter().map(Some).collect();
+        existing.events.extend(seat_updates(&state.seats, &seats));
+        existing.events.extend(table.events);
+    }
+    for annotation in other.annotations.hands {
+        let existing = session.annotations.hands.iter_mut().find(|a| {
+            (a.table_id, a.hand_id, a.action)
+                == (annotation.table_id, annotation.hand_id, annotation.action)
+        });
+        let Some(existing) = existing else {
+            session.annotations.hands.push(annotation);
+            continue;
+        };
+        for tag in annotation.tags {
+            if !existing.tags.contains(&tag) {
+                existing.tags.push(tag);
+            }
+        }
+        join_note(&mut existing.note, annotation.note);
+    }
+    for note in other.annotations.players {
+        let existing = session
+            .annotations
+            .players
+            .iter_mut()
+            .find(|n| n.player_id == note.player_id);
+        let Some(existing) = existing else {
+            session.annotations.players.push(note);
+            continue;
+        };
+        existing.label = existing.label.or(note.label);
+        join_note(&mut existing.note, note.note);
+    }
+}
+
+/// Appends a note on its own lines, unless it is empty or already there.
+fn join_note(note: &mut String, other: String) {
+    if other.is_empty() || *note == other {
+        return;
+    }
+    if !note.is_empty() {
+        note.push('\n');
+    }
+    note.push_str(&other);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::{poker888, winamax};
+    use crate::game::{Annotations, Label, TableEvent};
+
+    #[test]
+    fn test_detect() {
+        let registry = Registry::default();
+        let name = |text: &str| registry.detect(text.as_bytes()).map(|i| i.name());
+        assert_eq!(name(winamax::tests::HISTORY), Some("Winamax"));
+        assert_eq!(name(poker888::tests::HISTORY), Some("888poker"));
+        assert_eq!(name("\u{feff}Poker Hand #HD1: Hold'em"), Some("GGPoker"));
+        assert_eq!(name("<?xml version=\"1.0\"?>\n<session>"), Some("iPoker"));
+        assert_eq!(
+            name("variant = \"NT\"\nstarting_stacks = [1, 2]\nactions = []"),
+            Some("PHH")
+        );
+        assert_eq!(
+            name("# comment\nSTATE:0:cc:Ah9d|Kc7s:0|0:a|b"),
+            Some("ACPC")
+        );
+        assert_eq!(name("HEIR\x01"), Some("heir.bin"));
+        assert_eq!(name("# Session\n\n- Version: 0.1\n"), Some("heir.md"));
+        assert_eq!(
+            name("{\n  \"version\": \"0.1\",\n  \"tables\": []"),
+            Some("JSON")
+        );
+        assert_eq!(name("PokerStars Hand #1:"), None);
+        assert!(Registry::empty().detect(b"Poker Hand #HD1").is_none());
+    }
+
+    #[test]
+    fn test_import_dir() -> io::Result<()> {
+        let dir = std::env::temp_dir().join(format!("heir-registry-{}", std::process::id()));
+        fs::create_dir_all(dir.join("888"))?;
+        fs::write(dir.join("a.txt"), winamax::tests::HISTORY)?;
+        fs::write(dir.join("888/b.txt"), poker888::tests::HISTORY)?;
+        fs::write(dir.join("c.txt"), "Not a hand history")?;
+        // The same Winamax table again, with the stacks the first hand started with.
+        let next = winamax::tests::HISTORY.replace("#18567765", "#18567766");
+        fs::write(dir.join("d.txt"), next)?;
+        let import = Registry::default().import_dir(&dir);
+        fs::remove_dir_all(&dir)?;
+        let import = import?;
+
+        assert_eq!(import.warnings.len(), 1, "{:?}", import.warnings);
+        assert!(import.warnings[0].contains("c.txt: Unrecognised"));
+        let session = import.session;
+        assert_eq!(session.tables.len(), 2);
+        assert_eq!(session.tables[0].name, "Kiev");
+        let nice = &session.tables[1];
+        assert_eq!(nice.name, "Nice 04");
+        let events: Vec<_> = nice
+            .events
+            .iter()
+            .map(|e| match e {
+                TableEvent::Hand(_) => "hand",
+                TableEvent::StackUpdate(_) => "stack",
+                TableEvent::SeatUpdate(_) => "seat",
+                TableEvent::LevelChange(_) => "level",
+            })
+            .collect();
+        assert_eq!(events, vec!["hand", "stack", "stack", "hand"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_annotations() {
+        let mut session = Session::exhaustive();
+        session.annotations = Annotations::example();
+        let mut other = Session::exhaustive();
+        other.tables.clear();
+        other.annotations = Annotations::example();
+        other.annotations.hands[0].tags = vec!["3-bet pot".to_string(), "bluff".to_string()];
+        other.annotations.hands[1].action = Some(4);
+        other.annotations.players[0].label = Some(Label::Blue);
+        other.annotations.players[1].label = Some(Label::Green);
+        other.annotations.players[1].note = "Tilts.".to_string();
+        merge(&mut session, other);
+
+        let annotations = &session.annotations;
+        assert_eq!(annotations.hands.len(), 3);
+        let hand = annotations.hand(1, 9001, None).expect("Annotation");
+        assert_eq!(hand.tags, vec!["review", "3-bet pot", "bluff"]);
+        assert_eq!(hand.note, Annotations::example().hands[0].note);
+        assert!(annotations.hand(1, 9001, Some(4)).is_some());
+        assert_eq!(
+            annotations.player(1002).expect("Note").label,
+            Some(Label::Red)
+        );
+        let note = annotations.player(1001).expect("Note");
+        assert_eq!(
+            (note.label, note.note.as_str()),
+            (Some(Label::Green), "Tilts.")
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use crate::formats::builder::{split_actor, BuiltHand, HandBuilder, SessionBuilder, SiteAction};
+use crate::formats::{
+    bracketed_cards, parse_amount, parse_datetime, site_id, sniff_text, HandHistoryImporter, Import,
+};
+use crate::game::{Fee, FeeType, TableSize};
+use std::io::{self, Read};
+
+const SITE: &str = "Winamax";
+
+const VERBS: [&str; 7] = [
+    "posts", "folds", "checks", "calls", "bets", "raises", "shows",
+];
+
+/// The [`HandHistoryImporter`] for Winamax hand histories.
+pub struct Winamax;
+
+impl HandHistoryImporter for Winamax {
+    fn name(&self) -> &'static str {
+        SITE
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        sniff_text(head).starts_with("Winamax Poker - ")
+    }
+
+    fn import(&self, reader: &mut dyn Read) -> io::Result<Import> {
+        import(reader)
+    }
+}
+
+/// Imports Winamax No Limit Hold'em hand histories.
+pub fn import<R: Read>(mut reader: R) -> io::Result<Import> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut session = SessionBuilder::new(SITE);
+    let mut block: Vec<&str> = Vec::new();
+    for line in text.lines().map(|l| l.trim_end_matches('\r')) {
+        if line.starts_with("Winamax Poker - ") && !block.is_empty() {
+            import_hand(&mut session, &block);
+            block.clear();
+        }
+        if !line.trim().is_empty() {
+            block.push(line);
+        }
+    }
+    if !block.is_empty() {
+        import_hand(&mut session, &block);
+    }
+    Ok(session.finish())
+}
+
+fn import_hand(session: &mut SessionBuilder, lines: &[&str]) {
+    if let Err(e) = parse_hand(session, lines) {
+        let id = lines[0]
+            .split_once("HandId: #")
+            .and_then(|(_, id)| id.split(' ').next())
+            .unwrap_or(lines[0]);
+        session.warn(format!("Hand {} skipped: {}", id, e));
+    }
+}
+
+fn parse_hand(session: &mut SessionBuilder, lines: &[&str]) -> Result<(), String> {
+    let malformed = |line: &str| format!("Malformed line {:?}.", line);
+    let amount = |text: &str| parse_amount(text).map_err(|e| e.to_string());
+    let cards = |line: &str| bracketed_cards(line).map_err(|e| e.to_string());
+
+    // Winamax Poker - CashGame - HandId: #1-2-3 - Holdem no limit (0.01€/0.02€) - 2020/12/28 10:45:15 UTC
+    let header = lines[0];
+    let hand_id = header
+        .split_once("HandId: #")
+        .and_then(|(_, id)| id.split(' ').next())
+        .ok_or_else(|| malformed(header))?;
+    let id = hand_id.parse().unwrap_or_else(|_| site_id(SITE, hand_id));
+    if !header.contains("Holdem no limit") {
+        return Err("Only No Limit Hold'em is supported.".to_string());
+    }
+    let (game, date) = header.rsplit_once(" - ").ok_or_else(|| malformed(header))?;
+    let timestamp = parse_datetime(date.trim_end_matches(" UTC")).map_err(|e| e.to_string())?;
+    let stakes = game
+        .rsplit_once('(')
+        .and_then(|(_, s)| s.split_once(')'))
+        .map(|(s, _)| s)
+        .ok_or_else(|| malformed(header))?;
+    let blinds = stakes
+        .split('/')
+        .map(amount)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Table: 'Nice 04' 5-max (real money) Seat #3 is the button
+    let table_line = lines.get(1).copied().unwrap_or_default();
+    let (table, rest) = table_line
+        .strip_prefix("Table: '")
+        .and_then(|t| t.rsplit_once('\''))
+        .ok_or_else(|| malformed(table_line))?;
+    let (size, rest) = rest
+        .trim()
+        .split_once("-max")
+        .ok_or_else(|| malformed(table_line))?;
+    let table_size: TableSize = size.parse().map_err(|_| malformed(table_line))?;
+    let button = rest
+        .split_once("Seat #")
+        .and_then(|(_, b)| b.split_whitespace().next())
+        .and_then(|b| b.parse::<u8>().ok())
+        .filter(|&b| b >= 1)
+        .ok_or_else(|| malformed(table_line))?
+        - 1;
+
+    let mut seats = vec![None; table_size as usize];
+    for line in lines[2..].iter().take_while(|l| l.starts_with("Seat ")) {
+        let (number, rest) = line[5..].split_once(": ").ok_or_else(|| malformed(line))?;
+        let (name, chips) = rest.rsplit_once(" (").ok_or_else(|| malformed(line))?;
+        let chips = chips.split([',', ')']).next().unwrap_or_default();
+        let seat: usize = number.parse().map_err(|_| malformed(line))?;
+        let slot = seat
+            .checked_sub(1)
+            .and_then(|s| seats.get_mut(s))
+            .ok_or_else(|| malformed(line))?;
+        *slot = Some(session.player(name, amount(chips)?));
+    }
+
+    let mut hand = HandBuilder::new(id, timestamp, button, blinds.clone(), seats)?;
+    let mut hero = None;
+    let mut summary = false;
+    for line in &lines[2..] {
+        if let Some(street) = line.strip_prefix("*** ") {
+            match street.split(" ***").next().unwrap_or_default() {
+                "SUMMARY" => summary = true,
+                "FLOP" | "TURN" | "RIVER" => hand.board(&cards(line)?),
+                _ => {}
+            }
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("Dealt to ") {
+            if let Some((name, _)) = rest.split_once(" [") {
+                hand.show(name, &cards(line)?)?;
+                hero = Some(name.to_string());
+            }
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("Total pot ") {
+            if let Some((_, rake)) = rest.split_once("| Rake ") {
+                let rake = amount(rake.split(' ').next().unwrap_or_default())?;
+                if rake > 0 {
+                    hand.fee(Fee {
+                        fee_type: FeeType::Rake,
+                        amount: rake,
+                        seat: None,
+                    });
+                }
+            }
+            continue;
+        }
+        if summary {
+            // Seat 1: Player1 (button) showed [Ah Kd] and won 0.15€ with One pair : Kings
+            if let Some((name, _)) = line
+                .strip_prefix("Seat ")
+                .and_then(|l| l.split_once(": "))
+                .and_then(|(_, rest)| rest.split_once(" showed ["))
+            {
+                let name = name.split(" (").next().unwrap_or(name);
+                hand.show(name, &cards(line)?)?;
+            }
+            continue;
+        }
+        if let Some((name, rest)) = line.split_once(" collected ") {
+            hand.collect(name, amount(rest.split(" from ").next().unwrap_or(rest))?)?;
+            continue;
+        }
+        let Some((name, verb, rest)) = split_actor(line, &VERBS) else {
+            continue;
+        };
+        let all_in = rest.ends_with(" and is all-in");
+        let rest = rest.trim_end_matches(" and is all-in");
+        match verb {
+            "posts" if rest.starts_with("small blind") || rest.starts_with("big blind") => {
+                hand.post(name, amount(rest.rsplit(' ').next().unwrap_or_default())?)?
+            }
+            "posts" => return Err(format!("Unsupported post {:?}.", rest)),
+            "folds" => hand.act(name, SiteAction::Fold, all_in)?,
+            "checks" => hand.act(name, SiteAction::Check, all_in)?,
+            "calls" => hand.act(name, SiteAction::Call, all_in)?,
+            "bets" => hand.act(name, SiteAction::By(amount(rest)?), all_in)?,
+            "raises" => {
+                let to = rest.rsplit(' ').next().unwrap_or_default();
+                hand.act(name, SiteAction::To(amount(to)?), all_in)?
+            }
+            "shows" => hand.show(name, &cards(rest)?)?,
+            _ => {}
+        }
+    }
+
+    let BuiltHand {
+        seats,
+        hand,
+        warnings,
+        ..
+    } = hand.finish()?;
+    if let Some(hero) = hero {
+        session.hero(&hero);
+    }
+    for warning in warnings {
+        session.warn(warning);
+    }
+    session.push(table, table_size, &blinds, seats, hand);
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::game::{ActionType, TableEvent};
+    use crate::types::card::Card;
+
+    pub(crate) const HISTORY: &str = "\
+Winamax Poker - CashGame - HandId: #18567765-350-1609152315 - Holdem no limit (0.01€/0.02€) - 2020/12/28 10:45:15 UTC
+Table: 'Nice 04' 5-max (real money) Seat #3 is the button
+Seat 1: Big Fish (2.05€)
+Seat 2: Hero (2€)
+Seat 3: Player3 (1.92€)
+*** ANTE/BLINDS ***
+Big Fish posts small blind 0.01€
+Hero posts big blind 0.02€
+Dealt to Hero [7d 4h]
+*** PRE-FLOP ***
+Player3 folds
+Big Fish raises 0.04€ to 0.06€
+Hero calls 0.04€
+*** FLOP *** [Kh 2c 8s]
+Big Fish bets 0.07€
+Hero raises 0.14€ to 0.21€
+Big Fish calls 0.14€
+*** TURN *** [Kh 2c 8s][9h]
+Big Fish checks
+Hero bets 1.73€ and is all-in
+Big Fish folds
+Hero collected 0.53€ from pot
+*** SUMMARY ***
+Total pot 0.54€ | Rake 0.01€
+Board: [Kh 2c 8s 9h]
+Seat 2: Hero (big blind) won 0.53€
+";
+
+    #[test]
+    fn test_import() -> io::Result<()> {
+        let import = Winamax.import(&mut HISTORY.as_bytes())?;
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        let session = import.session;
+        assert_eq!(session.hero_id, site_id(SITE, "Hero"));
+        let table = &session.tables[0];
+        assert_eq!(table.name, "Nice 04");
+        assert_eq!(table.blinds, vec![1, 2]);
+        let TableEvent::Hand(hand) = &table.events[0] else {
+            panic!("Expected a hand");
+        };
+        assert_eq!(hand.button_position, 2);
+        assert_eq!(hand.hole_cards[1], [Card::SevenDiamonds, Card::FourHearts]);
+        assert_eq!(hand.board[3], Card::NineHearts);
+        assert_eq!(hand.actions[4].action_type, ActionType::Raise);
+        assert_eq!(hand.actions[4].bet_amount, 21);
+        assert_eq!(hand.actions[7].action_type, ActionType::AllIn);
+        assert_eq!(hand.actions[7].bet_amount, 173);
+        assert_eq!(hand.fees[0].amount, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_skips_antes() -> io::Result<()> {
+        let history = "\
+Winamax Poker - CashGame - HandId: #1-2-3 - Holdem no limit (0.01€/0.02€) - 2020/12/28 10:45:15 UTC
+Table: 'Nice 04' 5-max (real money) Seat #1 is the button
+Seat 1: A (2€)
+Seat 2: B (2€)
+*** ANTE/BLINDS ***
+A posts ante 0.01€
+";
+        let import = import(history.as_bytes())?;
+        assert!(import.session.tables.is_empty());
+        assert_eq!(
+            import.warnings,
+            vec!["Hand 1-2-3 skipped: Unsupported post \"ante 0.01€\"."]
+        );
+        Ok(())
+    }
+}
@@ -1,4 +1,8 @@
+use crate::types::card::{Card, Rank};
+use crate::types::version::Version;
 use std::fmt;
+use std::io;
+use std::str::FromStr;
 
 /// A Session represents a collection of [`Table`]s along with some metadata.
 /// Note that this struct nor its children verify the data logic, it's just a format.
@@ -10,10 +14,27 @@ pub struct Session {
     pub name: String,
     pub tables: Vec<Table>,
     pub hero_id: Id,
+    /// The tournament the tables belong to, or `None` for cash games.
+    pub tournament: Option<Tournament>,
+    pub annotations: Annotations,
+}
+
+impl Session {
+    /// The id of the first table that played a hand of the given id.
+    pub fn hand_table(&self, hand_id: Id) -> Option<Id> {
+        self.tables
+            .iter()
+            .find(|t| {
+                t.events
+                    .iter()
+                    .any(|e| matches!(e, TableEvent::Hand(hand) if hand.id == hand_id))
+            })
+            .map(|t| t.id)
+    }
 }
 
 /// u32 alias for all identifiable types.
-type Id = u64;
+pub type Id = u64;
 
 /// A Table is a continuous collection of [`Hand`]s along with an initial context and some metadata.
 #[derive(Clone, PartialEq, Debug)]
@@ -25,21 +46,149 @@ pub struct Table {
     pub rake_percentage: RakePercentage,
     pub rake_cap: RakeCap,
     pub blinds: Vec<Decimal>,
+    pub betting_structure: BettingStructure,
+    pub variant: Variant,
     pub initial_context: Vec<Player>,
     pub events: Vec<TableEvent>,
 }
 
+/// The poker game dealt at a [`Table`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Variant {
+    #[default]
+    Holdem,
+    /// Omaha with four hole cards, of which a hand uses exactly two with three board cards.
+    Omaha,
+    /// Omaha with five hole cards.
+    Omaha5,
+    /// Omaha with six hole cards.
+    Omaha6,
+    /// Hold'em dealt from a 36 card deck without the twos to fives, where a flush beats a
+    /// full house and the ace also plays low below the six.
+    ShortDeck,
+}
+
+impl Variant {
+    const NAMES: [(Variant, &'static str); 5] = [
+        (Variant::Holdem, "holdem"),
+        (Variant::Omaha, "omaha"),
+        (Variant::Omaha5, "omaha-5"),
+        (Variant::Omaha6, "omaha-6"),
+        (Variant::ShortDeck, "short-deck"),
+    ];
+
+    /// The number of hole cards dealt to each player.
+    pub fn hole_cards(self) -> usize {
+        match self {
+            Variant::Holdem | Variant::ShortDeck => 2,
+            Variant::Omaha => 4,
+            Variant::Omaha5 => 5,
+            Variant::Omaha6 => 6,
+        }
+    }
+
+    /// Whether a hand is made of exactly two hole cards and three board cards.
+    pub fn is_omaha(self) -> bool {
+        matches!(self, Variant::Omaha | Variant::Omaha5 | Variant::Omaha6)
+    }
+
+    /// Whether the variant's deck holds a known card.
+    pub fn deals(self, card: Card) -> bool {
+        match (self, card.rank()) {
+            (_, None) => false,
+            (Variant::ShortDeck, Some(rank)) => rank >= Rank::Six,
+            (_, Some(_)) => true,
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, name) = Variant::NAMES
+            .iter()
+            .find(|(v, _)| v == self)
+            .expect("Named");
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Variant {
+    type Err = io::Error;
+
+    /// Parses the lowercase name produced by `Display` (e.g. "omaha-5").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Variant::NAMES
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|(variant, _)| *variant)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid variant: {:?}.", s),
+                )
+            })
+    }
+}
+
+/// How much a player may bet or raise at a [`Table`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum BettingStructure {
+    #[default]
+    NoLimit,
+    /// Bets and raises up to the size of the pot.
+    PotLimit,
+    /// Bets and raises of the big blind preflop and on the flop and twice that on the turn
+    /// and river, up to a bet and three raises a street.
+    FixedLimit,
+}
+
+impl BettingStructure {
+    const NAMES: [(BettingStructure, &'static str); 3] = [
+        (BettingStructure::NoLimit, "no-limit"),
+        (BettingStructure::PotLimit, "pot-limit"),
+        (BettingStructure::FixedLimit, "fixed-limit"),
+    ];
+}
+
+impl fmt::Display for BettingStructure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, name) = BettingStructure::NAMES
+            .iter()
+            .find(|(s, _)| s == self)
+            .expect("Named");
+        f.write_str(name)
+    }
+}
+
+impl FromStr for BettingStructure {
+    type Err = io::Error;
+
+    /// Parses the lowercase name produced by `Display` (e.g. "pot-limit").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BettingStructure::NAMES
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|(structure, _)| *structure)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid betting structure: {:?}.", s),
+                )
+            })
+    }
+}
+
 /// An alias for the number of seats at a [`Table`] (e.g. 6-max, 9-max).
-type TableSize = u8;
+pub type TableSize = u8;
 
 /// The rake (generally at a given [`Table`]) as a percentage 0-100.
-type RakePercentage = u8;
+pub type RakePercentage = u8;
 
 /// The Table's rake cap as [`Decimal`].
-type RakeCap = Decimal;
+pub type RakeCap = Decimal;
 
 /// A Decimal is a u64 representing a number of cents.
-type Decimal = u64;
+pub type Decimal = u64;
 
 /// A context for a player in a seat at a [`Table`].
 #[derive(Clone, PartialEq, Debug)]
@@ -55,6 +204,7 @@ pub enum TableEvent {
     Hand(Hand),
     StackUpdate(StackUpdate),
     SeatUpdate(SeatUpdate),
+    LevelChange(LevelChange),
 }
 
 /// A Hand (not pair of hole cards) that occurs at a [`Table`].
@@ -66,25 +216,55 @@ pub struct Hand {
     pub actions: Vec<Action>,
     pub timestamp: Timestamp,
     pub board: Board,
+    /// Further boards when the hand is run more than once, each taking an even share of every
+    /// pot with `board`. A runout repeats the cards it shares with `board`.
+    pub runouts: Vec<Board>,
+    /// The amount every player posts when the hand is a bomb pot, which is dealt without
+    /// blinds or antes and starts the betting on the flop.
+    pub bomb_pot: Option<Decimal>,
+    pub fees: Vec<Fee>,
+    /// Time bank activations, disconnections and timeouts, in the order they happened.
+    pub clock_events: Vec<ClockEvent>,
+}
+
+impl Hand {
+    /// The time a seat spent in its time bank, from each activation to the timed action that
+    /// followed it. Activations without a time on both sides count nothing.
+    pub fn time_bank_used(&self, seat: u8) -> Millis {
+        self.clock_events
+            .iter()
+            .filter(|e| e.event_type == ClockEventType::TimeBank && e.seat == seat)
+            .filter_map(|e| {
+                let action = self.actions.get(e.action)?.timestamp?;
+                action.checked_sub(e.timestamp?)
+            })
+            .sum()
+    }
 }
 
-/// A Vec hodling two [`Card`]s for each [`Player`] with a known starting [`Hand`].
-type HoleCards = Vec<[Card; 2]>;
+/// A Vec holding the [`Card`]s of each [`Player`] with a known starting [`Hand`], as many as
+/// the [`Table`]'s [`Variant`] deals.
+pub type HoleCards = Vec<Vec<Card>>;
 
 /// A u64 representing a UNIX timestamp.
-type Timestamp = u64;
+pub type Timestamp = u64;
+
+/// A u64 representing a UNIX timestamp in milliseconds.
+pub type Millis = u64;
 
 /// The position of the button at a [`Table`] during a given [`Hand`].
-type ButtonPosition = u8;
+pub type ButtonPosition = u8;
 
 /// 0-5 [`Card`]s on the board.
-type Board = [Card; 5];
+pub type Board = [Card; 5];
 
 /// The action of a [`Player`] at a given point in a [`Hand`].
 #[derive(Clone, PartialEq, Debug)]
 pub struct Action {
     pub action_type: ActionType,
     pub bet_amount: u32,
+    /// When the action was taken, if recorded.
+    pub timestamp: Option<Millis>,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -97,6 +277,274 @@ pub enum ActionType {
     AllIn,
 }
 
+impl ActionType {
+    const NAMES: [(ActionType, &'static str); 6] = [
+        (ActionType::Fold, "fold"),
+        (ActionType::Check, "check"),
+        (ActionType::Bet, "bet"),
+        (ActionType::Call, "call"),
+        (ActionType::Raise, "raise"),
+        (ActionType::AllIn, "all-in"),
+    ];
+}
+
+impl fmt::Display for ActionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, name) = ActionType::NAMES
+            .iter()
+            .find(|(t, _)| t == self)
+            .expect("Named");
+        f.write_str(name)
+    }
+}
+
+impl FromStr for ActionType {
+    type Err = io::Error;
+
+    /// Parses the lowercase name produced by `Display` (e.g. "raise", "all-in").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ActionType::NAMES
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|(t, _)| t.clone())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid action type: {:?}.", s),
+                )
+            })
+    }
+}
+
+/// A change to a [`Player`]'s clock or connection during a [`Hand`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct ClockEvent {
+    pub event_type: ClockEventType,
+    pub seat: u8,
+    /// The index of the [`Action`] the event happened before, or the number of actions when
+    /// it happened after the last.
+    pub action: usize,
+    pub timestamp: Option<Millis>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClockEventType {
+    /// The player ran out of time for an action and started using their time bank.
+    TimeBank,
+    Disconnect,
+    /// The player ran out of time, and the site acted for them.
+    Timeout,
+}
+
+impl ClockEventType {
+    const NAMES: [(ClockEventType, &'static str); 3] = [
+        (ClockEventType::TimeBank, "time-bank"),
+        (ClockEventType::Disconnect, "disconnect"),
+        (ClockEventType::Timeout, "timeout"),
+    ];
+}
+
+impl fmt::Display for ClockEventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, name) = ClockEventType::NAMES
+            .iter()
+            .find(|(t, _)| t == self)
+            .expect("Named");
+        f.write_str(name)
+    }
+}
+
+impl FromStr for ClockEventType {
+    type Err = io::Error;
+
+    /// Parses the lowercase name produced by `Display` (e.g. "time-bank").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ClockEventType::NAMES
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|(t, _)| *t)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid clock event type: {:?}.", s),
+                )
+            })
+    }
+}
+
+/// Tags and notes added to a [`Session`] when reviewing it, apart from what was played.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Annotations {
+    /// At most one per [`Hand`] and per [`Action`] of a hand, each hand identified by its
+    /// table and hand ids.
+    pub hands: Vec<HandAnnotation>,
+    /// Notes on players by [`Player`] id, kept with every session the players appear in.
+    pub players: Vec<PlayerNote>,
+}
+
+impl Annotations {
+    pub fn is_empty(&self) -> bool {
+        self.hands.is_empty() && self.players.is_empty()
+    }
+
+    /// The annotation of a hand at a table, or of one of its actions.
+    pub fn hand(
+        &self,
+        table_id: Id,
+        hand_id: Id,
+        action: Option<usize>,
+    ) -> Option<&HandAnnotation> {
+        self.hands
+            .iter()
+            .find(|a| (a.table_id, a.hand_id, a.action) == (table_id, hand_id, action))
+    }
+
+    pub fn player(&self, player_id: Id) -> Option<&PlayerNote> {
+        self.players.iter().find(|n| n.player_id == player_id)
+    }
+}
+
+/// Tags and a note on a [`Hand`] or one of its [`Action`]s.
+#[derive(Clone, PartialEq, Debug)]
+pub struct HandAnnotation {
+    pub table_id: Id,
+    pub hand_id: Id,
+    /// The index of the annotated action, or `None` for the whole hand.
+    pub action: Option<usize>,
+    pub tags: Vec<String>,
+    pub note: String,
+}
+
+/// A colour label and a note on a [`Player`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct PlayerNote {
+    pub player_id: Id,
+    pub label: Option<Label>,
+    pub note: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Label {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl Label {
+    const NAMES: [(Label, &'static str); 6] = [
+        (Label::Red, "red"),
+        (Label::Orange, "orange"),
+        (Label::Yellow, "yellow"),
+        (Label::Green, "green"),
+        (Label::Blue, "blue"),
+        (Label::Purple, "purple"),
+    ];
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, name) = Label::NAMES.iter().find(|(l, _)| l == self).expect("Named");
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Label {
+    type Err = io::Error;
+
+    /// Parses the lowercase name produced by `Display` (e.g. "red").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Label::NAMES
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|(l, _)| *l)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid label: {:?}.", s),
+                )
+            })
+    }
+}
+
+/// A site charge or payout recorded with a [`Hand`].
+/// Recorded pot deductions replace the rake derived from the [`Table`]'s rake settings.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Fee {
+    pub fee_type: FeeType,
+    pub amount: Decimal,
+    /// The seat charged or paid, for fees that belong to a single [`Player`].
+    pub seat: Option<u8>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FeeType {
+    /// Taken from the pot by the site.
+    Rake,
+    /// Taken from the pot to fund a jackpot.
+    Jackpot,
+    /// Taken from the pot to fund a site promotion (e.g. GGPoker's Bingo or Fortune).
+    Promotion,
+    /// Taken from the pot as tax.
+    Tax,
+    /// Added to the pot by the site.
+    CashDrop,
+    /// Paid by a [`Player`] to insure an all-in.
+    InsurancePremium,
+    /// Paid to a [`Player`] by all-in insurance.
+    InsurancePayout,
+}
+
+impl FeeType {
+    /// Whether the fee is taken out of the pot before it is awarded.
+    pub fn is_pot_deduction(self) -> bool {
+        matches!(
+            self,
+            FeeType::Rake | FeeType::Jackpot | FeeType::Promotion | FeeType::Tax
+        )
+    }
+
+    const NAMES: [(FeeType, &'static str); 7] = [
+        (FeeType::Rake, "rake"),
+        (FeeType::Jackpot, "jackpot"),
+        (FeeType::Promotion, "promotion"),
+        (FeeType::Tax, "tax"),
+        (FeeType::CashDrop, "cash-drop"),
+        (FeeType::InsurancePremium, "insurance-premium"),
+        (FeeType::InsurancePayout, "insurance-payout"),
+    ];
+}
+
+impl fmt::Display for FeeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, name) = FeeType::NAMES
+            .iter()
+            .find(|(t, _)| t == self)
+            .expect("Named");
+        f.write_str(name)
+    }
+}
+
+impl FromStr for FeeType {
+    type Err = io::Error;
+
+    /// Parses the lowercase name produced by `Display` (e.g. "rake", "cash-drop").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FeeType::NAMES
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|(t, _)| *t)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid fee type: {:?}.", s),
+                )
+            })
+    }
+}
+
 /// An update to a [`Player`]'s stack outside of a [`Hand`] (e.g. top-up or rathole).
 #[derive(Clone, PartialEq, Debug)]
 pub struct StackUpdate {
@@ -111,12 +559,59 @@ pub struct SeatUpdate {
     pub player: Option<Player>,
 }
 
+/// A change of a tournament [`Table`]'s blinds and ante, for the hands that follow.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LevelChange {
+    /// The level's number, counting from 1.
+    pub level: u32,
+    pub blinds: Vec<Decimal>,
+    pub ante: Decimal,
+}
+
+/// A tournament played over a [`Session`]'s tables.
+///
+/// Blinds rise through [`LevelChange`]s at each table. Players moving between tables leave
+/// one through a [`SeatUpdate`] and join the other through another, and eliminated players
+/// leave their seat through a [`SeatUpdate`] once a hand empties their stack.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Tournament {
+    pub buy_in: Decimal,
+    pub fee: Decimal,
+    pub starting_stack: Decimal,
+    /// When the first level starts.
+    pub start: Timestamp,
+    pub entrants: u32,
+    pub levels: Vec<Level>,
+    /// Prizes by finishing position, first place first.
+    pub payouts: Vec<Decimal>,
+    /// Finishing positions as recorded by the site, best first.
+    pub finishes: Vec<Finish>,
+}
+
+/// A blind level in a [`Tournament`]'s schedule.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Level {
+    pub blinds: Vec<Decimal>,
+    pub ante: Decimal,
+    /// The length of the level in seconds.
+    pub duration: u32,
+}
+
+/// A [`Player`]'s finishing position in a [`Tournament`] and the prize it paid.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Finish {
+    pub player_id: Id,
+    /// The position, counting from 1 for the winner.
+    pub position: u32,
+    pub prize: Decimal,
+}
+
 #[cfg(test)]
 impl Session {
     /// Returns an [`Session`] that covers all possible [`Table`] entries for testing purposes.
     pub fn exhaustive() -> Self {
         Self {
-            version: env!("CARGO_PKG_VERSION").to_string(),
+            version: Version::new(0, 1).expect("Valid version"),
             id: 1738,
             name: "Exhaustive Session".to_string(),
             tables: vec![Table {
@@ -127,6 +622,8 @@ impl Session {
                 rake_percentage: 5,
                 rake_cap: 3,
                 blinds: vec![50, 100],
+                betting_structure: BettingStructure::NoLimit,
+                variant: Variant::Holdem,
                 initial_context: vec![
                     Player {
                         id: 1001,
@@ -144,29 +641,34 @@ impl Session {
                         id: 9001,
                         button_position: 1,
                         hole_cards: vec![
-                            [Card::AceClubs, Card::AceSpades],
-                            [Card::TwoClubs, Card::TwoSpades],
+                            vec![Card::AceClubs, Card::AceSpades],
+                            vec![Card::TwoClubs, Card::TwoSpades],
                         ],
                         actions: vec![
                             Action {
                                 action_type: ActionType::Raise,
                                 bet_amount: 300,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Call,
                                 bet_amount: 300,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Bet,
                                 bet_amount: 600,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Raise,
                                 bet_amount: 1800,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Fold,
                                 bet_amount: 600,
+                                timestamp: None,
                             },
                         ],
                         timestamp: 1724293476,
@@ -177,6 +679,10 @@ impl Session {
                             Card::Xx,
                             Card::Xx,
                         ],
+                        runouts: vec![],
+                        bomb_pot: None,
+                        fees: vec![],
+                        clock_events: vec![],
                     }),
                     TableEvent::StackUpdate(StackUpdate {
                         seat: 1,
@@ -194,53 +700,64 @@ impl Session {
                         id: 9002,
                         button_position: 0,
                         hole_cards: vec![
-                            [Card::AceClubs, Card::AceSpades],
-                            [Card::Unknown, Card::Unknown],
+                            vec![Card::AceClubs, Card::AceSpades],
+                            vec![Card::Unknown, Card::Unknown],
                         ],
                         actions: vec![
                             Action {
                                 action_type: ActionType::Call,
                                 bet_amount: 100,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Check,
                                 bet_amount: 100,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Check,
                                 bet_amount: 0,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Bet,
                                 bet_amount: 400,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Raise,
                                 bet_amount: 800,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Raise,
                                 bet_amount: 1600,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Call,
                                 bet_amount: 1600,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Check,
                                 bet_amount: 0,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Check,
                                 bet_amount: 0,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Check,
                                 bet_amount: 0,
+                                timestamp: None,
                             },
                             Action {
                                 action_type: ActionType::Check,
                                 bet_amount: 0,
+                                timestamp: None,
                             },
                         ],
                         timestamp: 1724293500,
@@ -251,10 +768,215 @@ impl Session {
                             Card::SevenDiamonds,
                             Card::EightHearts,
                         ],
+                        runouts: vec![],
+                        bomb_pot: None,
+                        fees: vec![],
+                        clock_events: vec![],
                     }),
                 ],
             }],
             hero_id: 0,
+            tournament: None,
+            annotations: Annotations::default(),
+        }
+    }
+
+    /// Returns [`Session::exhaustive`] as a pot-limit game of an Omaha variant, with the hole
+    /// cards filled out with diamonds and the second hand's flop bet kept within the pot.
+    pub fn omaha(variant: Variant) -> Self {
+        let mut session = Self::exhaustive();
+        session.version = Version::new(0, 4).expect("Valid version");
+        let table = &mut session.tables[0];
+        table.variant = variant;
+        table.betting_structure = BettingStructure::PotLimit;
+        let diamonds = [
+            [
+                Card::KingDiamonds,
+                Card::QueenDiamonds,
+                Card::JackDiamonds,
+                Card::TenDiamonds,
+            ],
+            [
+                Card::NineDiamonds,
+                Card::SixDiamonds,
+                Card::FiveDiamonds,
+                Card::FourDiamonds,
+            ],
+        ];
+        for event in &mut table.events {
+            let TableEvent::Hand(hand) = event else {
+                continue;
+            };
+            for (cards, extra) in hand.hole_cards.iter_mut().zip(&diamonds) {
+                let extra = extra.map(|c| {
+                    if cards[0].is_known() {
+                        c
+                    } else {
+                        Card::Unknown
+                    }
+                });
+                cards.extend_from_slice(&extra[..variant.hole_cards() - 2]);
+            }
+            for action in &mut hand.actions {
+                if action.action_type == ActionType::Bet && action.bet_amount == 400 {
+                    action.bet_amount = 200;
+                }
+            }
+        }
+        session
+    }
+
+    /// Returns a three player sit and go where the big blind's aces in seat 0 knock out the
+    /// button's kings and the small blind's queens in the second level.
+    pub fn sit_and_go() -> Self {
+        let player = |id: Id| Player {
+            id,
+            name: format!("Player {}", id),
+            stack: 1500,
+        };
+        let action = |action_type: ActionType, bet_amount: u32| Action {
+            action_type,
+            bet_amount,
+            timestamp: None,
+        };
+        Self {
+            version: Version::new(0, 1).expect("Valid version"),
+            id: 1739,
+            name: "Sit and Go".to_string(),
+            tables: vec![Table {
+                id: 1,
+                name: "Table 1".to_string(),
+                location: "North Avenue East 1205".to_string(),
+                table_size: 3,
+                rake_percentage: 0,
+                rake_cap: 0,
+                blinds: vec![10, 20],
+                betting_structure: BettingStructure::NoLimit,
+                variant: Variant::Holdem,
+                initial_context: vec![player(1), player(2), player(3)],
+                events: vec![
+                    TableEvent::LevelChange(LevelChange {
+                        level: 1,
+                        blinds: vec![10, 20],
+                        ante: 0,
+                    }),
+                    TableEvent::Hand(Hand {
+                        id: 1,
+                        button_position: 0,
+                        hole_cards: vec![vec![Card::Xx; 2]; 3],
+                        actions: vec![
+                            action(ActionType::Raise, 60),
+                            action(ActionType::Fold, 0),
+                            action(ActionType::Fold, 0),
+                        ],
+                        timestamp: 1724290060,
+                        board: [Card::Xx; 5],
+                        runouts: vec![],
+                        bomb_pot: None,
+                        fees: vec![],
+                        clock_events: vec![],
+                    }),
+                    TableEvent::LevelChange(LevelChange {
+                        level: 2,
+                        blinds: vec![20, 40],
+                        ante: 5,
+                    }),
+                    TableEvent::Hand(Hand {
+                        id: 2,
+                        button_position: 1,
+                        hole_cards: vec![
+                            vec![Card::AceClubs, Card::AceSpades],
+                            vec![Card::KingClubs, Card::KingSpades],
+                            vec![Card::QueenClubs, Card::QueenSpades],
+                        ],
+                        actions: vec![
+                            action(ActionType::AllIn, 1485),
+                            action(ActionType::AllIn, 1475),
+                            action(ActionType::Call, 1485),
+                        ],
+                        timestamp: 1724290700,
+                        board: [
+                            Card::TwoDiamonds,
+                            Card::SevenHearts,
+                            Card::NineClubs,
+                            Card::ThreeSpades,
+                            Card::FourDiamonds,
+                        ],
+                        runouts: vec![],
+                        bomb_pot: None,
+                        fees: vec![],
+                        clock_events: vec![],
+                    }),
+                    TableEvent::SeatUpdate(SeatUpdate {
+                        seat: 1,
+                        player: None,
+                    }),
+                    TableEvent::SeatUpdate(SeatUpdate {
+                        seat: 2,
+                        player: None,
+                    }),
+                ],
+            }],
+            hero_id: 1,
+            tournament: Some(Tournament {
+                buy_in: 1000,
+                fee: 100,
+                starting_stack: 1500,
+                start: 1724290000,
+                entrants: 3,
+                levels: vec![
+                    Level {
+                        blinds: vec![10, 20],
+                        ante: 0,
+                        duration: 600,
+                    },
+                    Level {
+                        blinds: vec![20, 40],
+                        ante: 5,
+                        duration: 600,
+                    },
+                ],
+                payouts: vec![2000, 1000],
+                finishes: vec![],
+            }),
+            annotations: Annotations::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Annotations {
+    /// Returns [`Annotations`] on the first hand of [`Session::exhaustive`] and its players.
+    pub fn example() -> Self {
+        Self {
+            hands: vec![
+                HandAnnotation {
+                    table_id: 1,
+                    hand_id: 9001,
+                    action: None,
+                    tags: vec!["review".to_string(), "3-bet pot".to_string()],
+                    note: "Should seat 0 defend the flop?\nAsk the team.".to_string(),
+                },
+                HandAnnotation {
+                    table_id: 1,
+                    hand_id: 9001,
+                    action: Some(3),
+                    tags: vec![],
+                    note: "Thin raise.".to_string(),
+                },
+            ],
+            players: vec![
+                PlayerNote {
+                    player_id: 1002,
+                    label: Some(Label::Red),
+                    note: "Raises light on paired boards.".to_string(),
+                },
+                PlayerNote {
+                    player_id: 1001,
+                    label: None,
+                    note: String::new(),
+                },
+            ],
         }
     }
 }
@@ -1 +1,11 @@
-mod types;
+pub mod anonymise;
+pub mod dealer;
+pub mod engine;
+pub mod formats;
+pub mod game;
+pub mod perspective;
+pub mod query;
+pub mod report;
+pub mod stats;
+pub mod tournament;
+pub mod types;
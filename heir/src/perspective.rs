@@ -0,0 +1,175 @@
+use crate::engine::position::{Position, Positions};
+use crate::engine::state::TableState;
+use crate::game::{Id, Session, Table, TableEvent};
+use crate::query::Query;
+use crate::report::{Report, Results};
+use crate::stats::{Counters, Stats};
+use crate::types::card::Card;
+
+/// A [`Session`] as its hero saw it, from an omniscient record of it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Perspective {
+    /// The session with only the hands the hero was dealt in and their annotations, with the
+    /// hole cards of every opponent who did not reach showdown hidden as [`Card::Unknown`],
+    /// and with notes only on the players the hero was dealt in with.
+    pub session: Session,
+    /// The hero's place in each kept hand that replays, in the order they were played.
+    pub hands: Vec<HeroHand>,
+    pub stats: Counters,
+    pub results: Results,
+}
+
+/// Where the hero sat in a hand and how it went for them.
+#[derive(Clone, PartialEq, Debug)]
+pub struct HeroHand {
+    pub table_id: Id,
+    pub hand_id: Id,
+    pub seat: u8,
+    /// `None` at tables too large to name every position.
+    pub position: Option<Position>,
+    pub hole_cards: Vec<Card>,
+    pub net: i64,
+}
+
+impl Perspective {
+    pub fn new(session: &Session) -> Self {
+        let hero = "hero".parse::<Query>().expect("Valid query");
+        let mut session = hero.filter(session);
+        for table in &mut session.tables {
+            hide_unshown(table, session.hero_id);
+        }
+        let mut hands = Vec::new();
+        let mut seen = Vec::new();
+        let mut stats = Stats::new();
+        let mut report = Report::new();
+        for table in &session.tables {
+            let mut state = TableState::new(table);
+            for event in &table.events {
+                let (Ok(Some(replay)), TableEvent::Hand(hand)) = (state.apply(table, event), event)
+                else {
+                    continue;
+                };
+                let Some(seat) = replay.seats.iter().find(|s| s.player.id == session.hero_id)
+                else {
+                    continue;
+                };
+                hands.push(HeroHand {
+                    table_id: table.id,
+                    hand_id: hand.id,
+                    seat: seat.seat,
+                    position: Positions::from_replay(&replay).and_then(|p| p.position(seat.seat)),
+                    hole_cards: seat.hole_cards.clone(),
+                    net: replay.net(seat.seat),
+                });
+                seen.extend(replay.seats.iter().map(|s| s.player.id));
+                stats.add_hand(&replay);
+                report.add_hand(table, &state.stakes, hand, &replay);
+            }
+        }
+        session
+            .annotations
+            .players
+            .retain(|n| seen.contains(&n.player_id));
+        Perspective {
+            hands,
+            stats: stats.player(session.hero_id).copied().unwrap_or_default(),
+            results: report
+                .player(session.hero_id)
+                .map(|r| r.total)
+                .unwrap_or_default(),
+            session,
+        }
+    }
+}
+
+/// Hides the hole cards of every dealt seat but the hero's and those that reached showdown,
+/// where every hand is taken to be shown. Hands that do not replay keep only the hero's.
+pub fn hide_unshown(table: &mut Table, hero_id: Id) {
+    let mut state = TableState::new(table);
+    for i in 0..table.events.len() {
+        let seats = state.seats.clone();
+        let shown: Vec<u8> = match state.apply(table, &table.events[i]) {
+            Ok(Some(replay)) => replay.showdown.iter().map(|&(seat, _)| seat).collect(),
+            _ => Vec::new(),
+        };
+        let TableEvent::Hand(hand) = &mut table.events[i] else {
+            continue;
+        };
+        // Hole cards are held in the order of the seats dealt in.
+        let dealt = seats
+            .iter()
+            .enumerate()
+            .filter_map(|(seat, p)| p.as_ref().map(|p| (seat as u8, p)))
+            .filter(|(_, p)| p.stack > 0);
+        for (cards, (seat, player)) in hand.hole_cards.iter_mut().zip(dealt) {
+            if player.id != hero_id && !shown.contains(&seat) {
+                cards.fill(Card::Unknown);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Annotations, HandAnnotation, PlayerNote};
+
+    #[test]
+    fn test_perspective() {
+        let mut session = Session::exhaustive();
+        session.hero_id = 1002;
+        let perspective = Perspective::new(&session);
+        assert_eq!(perspective.hands.len(), 2);
+        let first = &perspective.hands[0];
+        assert_eq!((first.hand_id, first.seat), (9001, 1));
+        assert_eq!(first.position, Some(Position::Button));
+        assert_eq!(first.hole_cards, vec![Card::TwoClubs, Card::TwoSpades]);
+        assert_eq!(first.net, 897);
+        let TableEvent::Hand(hand) = &perspective.session.tables[0].events[0] else {
+            panic!("Expected a hand");
+        };
+        // The opponent folded their aces without showing them.
+        assert_eq!(hand.hole_cards[0], vec![Card::Unknown, Card::Unknown]);
+        let mut stats = Stats::new();
+        stats.add_session(&session);
+        assert_eq!(Some(&perspective.stats), stats.player(1002));
+        assert_eq!(perspective.results.hands, 2);
+
+        // Player 1001 leaves before the second hand.
+        session.hero_id = 1001;
+        session.annotations = Annotations::example();
+        session.annotations.hands.push(HandAnnotation {
+            table_id: 1,
+            hand_id: 9002,
+            action: None,
+            tags: vec!["cooler".to_string()],
+            note: String::new(),
+        });
+        session.annotations.players.push(PlayerNote {
+            player_id: 1003,
+            label: None,
+            note: "Only plays aces.".to_string(),
+        });
+        let perspective = Perspective::new(&session);
+        assert_eq!(perspective.hands.len(), 1);
+        assert_eq!(perspective.stats.hands, 1);
+        // Only the first hand and the players in it keep their annotations.
+        let annotations = &perspective.session.annotations;
+        assert!(annotations.hands.iter().all(|a| a.hand_id == 9001));
+        assert_eq!(annotations.hands.len(), 2);
+        let noted: Vec<Id> = annotations.players.iter().map(|n| n.player_id).collect();
+        assert_eq!(noted, vec![1002, 1001]);
+    }
+
+    #[test]
+    fn test_perspective_keeps_stakes() {
+        let perspective = Perspective::new(&Session::sit_and_go());
+        assert_eq!(perspective.hands.len(), 2);
+        // At 20/40 with a 5 ante, the hero wins both other stacks, antes included.
+        assert_eq!(perspective.hands[1].net, 1490 + 1480);
+        assert!(perspective.session.tables[0]
+            .events
+            .iter()
+            .any(|e| matches!(e, TableEvent::LevelChange(l) if l.level == 2)));
+    }
+}
@@ -0,0 +1,910 @@
+use crate::engine::position::{Position, Positions};
+use crate::engine::replay::{Replay, Step, Street};
+use crate::engine::state::TableState;
+use crate::formats::builder::seat_updates;
+use crate::formats::timestamp_from_civil;
+use crate::game::{ActionType, Hand, Id, Player, Session, Table, TableEvent, Timestamp};
+use crate::types::card::{Card, Rank, Suit};
+use std::fmt;
+use std::str::FromStr;
+
+/// A filter over [`Hand`]s, parsed from an expression such as
+/// `hero 3bet and hero position = BTN and showdown`.
+///
+/// Expressions combine predicates with `and`, `or`, `not` and parentheses. Predicates about
+/// the whole hand are:
+///
+/// - `street >= flop`: the last street reached (`preflop`, `flop`, `turn` or `river`).
+/// - `showdown`: two or more players reached showdown.
+/// - `pot > 20bb`: the pot before rake, in chips or big blinds (`bb`).
+/// - `board has A?`, `board paired`: a board card matching a pattern (`?` for any rank or
+///   suit), or a board with a pair.
+/// - `flop ~ "x*c"`: the actions on a street reached, as `f`old, `x` check, `c`all, `b`et,
+///   `r`aise and `a`ll-in, matched against a pattern where `*` is any actions and `?` any one.
+/// - `time >= 2024-08-22`: the hand's time in UTC, as a date, a date and time
+///   (`2024-08-22T10:30`) or a UNIX timestamp.
+/// - `players >= 6`, `id = 9001`: the number of players dealt and the hand's [`Id`].
+///
+/// Predicates about a player start with `hero` (the session's hero) or `player <id>`, which
+/// alone require the player to be dealt in. They are followed by:
+///
+/// - `position = BTN`, `position in (SB, BB)`: the player's position.
+/// - `holds AKs`: hole cards such as `AA`, `AK`, `AKo`, `AKs`, `A?` or `AhKh`.
+/// - `net > 0`: the player's profit over the hand, in chips or big blinds.
+/// - `showdown`: the player reached showdown.
+/// - `fold`, `check`, `call`, `bet`, `raise`, `allin` or `3bet` (any `<n>bet`), optionally
+///   followed by a street: the player took the action. The blinds count as the first bet
+///   preflop, and `<n>bet` without a street means preflop.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Query {
+    expr: Expr,
+}
+
+/// A hand with the table context it was dealt in, as matched by a [`Query`].
+pub struct HandContext<'a> {
+    pub table: &'a Table,
+    /// The seats the hand was dealt to.
+    pub seats: &'a [Option<Player>],
+    pub hand: &'a Hand,
+    pub replay: &'a Replay,
+    pub hero_id: Id,
+}
+
+/// An error in a [`Query`] expression, at a byte offset into it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct QueryError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at character {}.", self.message, self.position + 1)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Predicate(Predicate),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Subject {
+    Hero,
+    Player(Id),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Compare {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Compare {
+    fn test<T: PartialOrd>(self, left: T, right: T) -> bool {
+        match self {
+            Compare::Eq => left == right,
+            Compare::Ne => left != right,
+            Compare::Lt => left < right,
+            Compare::Le => left <= right,
+            Compare::Gt => left > right,
+            Compare::Ge => left >= right,
+        }
+    }
+}
+
+/// An amount in chips, or in big blinds.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Amount {
+    value: f64,
+    big_blinds: bool,
+}
+
+impl Amount {
+    fn chips(self, replay: &Replay) -> f64 {
+        if self.big_blinds {
+            self.value * replay.big_blind as f64
+        } else {
+            self.value
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Verb {
+    Fold,
+    Check,
+    Call,
+    Bet,
+    Raise,
+    AllIn,
+    /// The n-th bet of a street, counting the blinds as the first preflop.
+    Level(u8),
+}
+
+/// A card pattern, where `None` matches any rank or suit.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct CardPattern {
+    rank: Option<Rank>,
+    suit: Option<Suit>,
+}
+
+impl CardPattern {
+    fn matches(self, card: Card) -> bool {
+        card.is_known()
+            && self.rank.is_none_or(|r| card.rank() == Some(r))
+            && self.suit.is_none_or(|s| card.suit() == Some(s))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Suitedness {
+    Any,
+    Suited,
+    Offsuit,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Predicate {
+    Dealt(Subject),
+    Acted(Subject, Verb, Option<Street>),
+    Position(Subject, Vec<Position>),
+    Holds(Subject, [CardPattern; 2], Suitedness),
+    Net(Subject, Compare, Amount),
+    Showdown(Option<Subject>),
+    Street(Compare, Street),
+    Pot(Compare, Amount),
+    BoardHas(CardPattern),
+    BoardPaired,
+    Sequence(Street, String),
+    Time(Compare, Timestamp),
+    Players(Compare, u64),
+    Id(Compare, Id),
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(text: &str) -> Result<Self, QueryError> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            index: 0,
+            end: text.len(),
+        };
+        let expr = parser.or()?;
+        if let Some(token) = parser.tokens.get(parser.index) {
+            return Err(parser.error_at(token.position, "Unexpected input"));
+        }
+        Ok(Query { expr })
+    }
+}
+
+impl Query {
+    /// Whether a hand matches the query.
+    pub fn matches(&self, context: &HandContext) -> bool {
+        self.expr.eval(context)
+    }
+
+    /// A copy of a [`Session`] with only the matching hands, dropping tables without any.
+    ///
+    /// Seat and stack updates, and the latest level change, are added so that every kept hand
+    /// is dealt to the seats and played at the stakes it was originally. Hands that do not
+    /// replay never match. Only the kept hands keep their annotations.
+    pub fn filter(&self, session: &Session) -> Session {
+        let mut tables = Vec::new();
+        for table in &session.tables {
+            let mut filtered = Table {
+                events: Vec::new(),
+                ..table.clone()
+            };
+            let mut state = TableState::new(table);
+            let mut kept = TableState::new(table);
+            let mut level = None;
+            for event in &table.events {
+                if let TableEvent::LevelChange(_) = event {
+                    level = Some(event);
+                }
+                let seats = state.seats.clone();
+                let Ok(Some(replay)) = state.apply(table, event) else {
+                    continue;
+                };
+                let TableEvent::Hand(hand) = event else {
+                    continue;
+                };
+                let context = HandContext {
+                    table,
+                    seats: &seats,
+                    hand,
+                    replay: &replay,
+                    hero_id: session.hero_id,
+                };
+                if self.matches(&context) {
+                    if let Some(level) = level.filter(|_| kept.stakes != state.stakes) {
+                        let _ = kept.apply(table, level);
+                        filtered.events.push(level.clone());
+                    }
+                    for update in seat_updates(&kept.seats, &seats) {
+                        // Updates cannot fail, only hands are replayed.
+                        let _ = kept.apply(table, &update);
+                        filtered.events.push(update);
+                    }
+                    let _ = kept.apply(table, event);
+                    filtered.events.push(event.clone());
+                }
+            }
+            if filtered
+                .events
+                .iter()
+                .any(|e| matches!(e, TableEvent::Hand(_)))
+            {
+                tables.push(filtered);
+            }
+        }
+        let kept = |table_id: Id, hand_id: Id| {
+            tables.iter().any(|t: &Table| {
+                t.id == table_id
+                    && t.events
+                        .iter()
+                        .any(|e| matches!(e, TableEvent::Hand(hand) if hand.id == hand_id))
+            })
+        };
+        let mut annotations = session.annotations.clone();
+        annotations.hands.retain(|a| kept(a.table_id, a.hand_id));
+        Session {
+            tables,
+            annotations,
+            ..session.clone()
+        }
+    }
+}
+
+impl Expr {
+    fn eval(&self, context: &HandContext) -> bool {
+        match self {
+            Expr::And(left, right) => left.eval(context) && right.eval(context),
+            Expr::Or(left, right) => left.eval(context) || right.eval(context),
+            Expr::Not(expr) => !expr.eval(context),
+            Expr::Predicate(predicate) => predicate.eval(context),
+        }
+    }
+}
+
+impl Predicate {
+    fn eval(&self, context: &HandContext) -> bool {
+        let replay = context.replay;
+        let seat = |subject: &Subject| {
+            let id = match subject {
+                Subject::Hero => context.hero_id,
+                Subject::Player(id) => *id,
+            };
+            replay.seats.iter().find(|s| s.player.id == id)
+        };
+        let board = || context.hand.board.iter().filter(|c| c.is_known());
+        match self {
+            Predicate::Dealt(subject) => seat(subject).is_some(),
+            Predicate::Acted(subject, verb, street) => seat(subject).is_some_and(|s| {
+                let street = match verb {
+                    Verb::Level(_) => street.or(Some(Street::Preflop)),
+                    _ => *street,
+                };
+                replay.steps.iter().enumerate().any(|(i, step)| {
+                    step.seat == s.seat
+                        && street.is_none_or(|street| step.street == street)
+                        && acted(&replay.steps, i, *verb)
+                })
+            }),
+            Predicate::Position(subject, positions) => seat(subject).is_some_and(|s| {
+                Positions::from_replay(replay)
+                    .and_then(|p| p.position(s.seat))
+                    .is_some_and(|p| positions.contains(&p))
+            }),
+            Predicate::Holds(subject, patterns, suitedness) => {
+                seat(subject).is_some_and(|s| holds(&s.hole_cards, patterns, *suitedness))
+            }
+            Predicate::Net(subject, compare, amount) => seat(subject)
+                .is_some_and(|s| compare.test(replay.net(s.seat) as f64, amount.chips(replay))),
+            Predicate::Showdown(subject) => {
+                replay.showdown.len() > 1
+                    && subject.as_ref().is_none_or(|subject| {
+                        seat(subject).is_some_and(|s| {
+                            replay.showdown.iter().any(|(seat, _)| *seat == s.seat)
+                        })
+                    })
+            }
+            Predicate::Street(compare, street) => compare.test(replay.street, *street),
+            Predicate::Pot(compare, amount) => {
+                compare.test(replay.total_pot() as f64, amount.chips(replay))
+            }
+            Predicate::BoardHas(pattern) => board().any(|&c| pattern.matches(c)),
+            Predicate::BoardPaired => {
+                let ranks: Vec<Rank> = board().filter_map(|c| c.rank()).collect();
+                ranks
+                    .iter()
+                    .enumerate()
+                    .any(|(i, r)| ranks[i + 1..].contains(r))
+            }
+            Predicate::Sequence(street, pattern) => {
+                let actions: Vec<char> = replay
+                    .steps
+                    .iter()
+                    .filter(|s| s.street == *street)
+                    .map(letter)
+                    .collect();
+                let pattern: Vec<char> = pattern.chars().collect();
+                replay.street >= *street && glob(&pattern, &actions)
+            }
+            Predicate::Time(compare, timestamp) => compare.test(context.hand.timestamp, *timestamp),
+            Predicate::Players(compare, count) => compare.test(replay.seats.len() as u64, *count),
+            Predicate::Id(compare, id) => compare.test(context.hand.id, *id),
+        }
+    }
+}
+
+/// Whether the step at an index is the given kind of action.
+fn acted(steps: &[Step], index: usize, verb: Verb) -> bool {
+    let step = &steps[index];
+    let aggressive = step.raise > 0;
+    let level = || {
+        let raises = steps[..=index]
+            .iter()
+            .filter(|s| s.street == step.street && s.raise > 0)
+            .count();
+        raises + (step.street == Street::Preflop) as usize
+    };
+    match verb {
+        Verb::Fold => step.action.action_type == ActionType::Fold,
+        Verb::Check => step.action.action_type == ActionType::Check,
+        Verb::Call => step.amount > 0 && !aggressive,
+        Verb::Bet => aggressive && level() == 1,
+        Verb::Raise => aggressive && level() > 1,
+        Verb::AllIn => step.all_in,
+        Verb::Level(n) => aggressive && level() == n as usize,
+    }
+}
+
+/// An action as a letter of a street's action sequence.
+fn letter(step: &Step) -> char {
+    match step.action.action_type {
+        _ if step.all_in => 'a',
+        ActionType::Fold => 'f',
+        ActionType::Check => 'x',
+        ActionType::Call => 'c',
+        ActionType::Bet => 'b',
+        ActionType::Raise => 'r',
+        ActionType::AllIn => 'a',
+    }
+}
+
+/// Matches a pattern where `*` is any run of actions and `?` any one action.
+fn glob(pattern: &[char], actions: &[char]) -> bool {
+    match pattern.split_first() {
+        None => actions.is_empty(),
+        Some(('*', rest)) => (0..=actions.len()).any(|i| glob(rest, &actions[i..])),
+        Some((&c, rest)) => actions
+            .split_first()
+            .is_some_and(|(&a, actions)| (c == '?' || c == a) && glob(rest, actions)),
+    }
+}
+
+fn holds(cards: &[Card], patterns: &[CardPattern; 2], suitedness: Suitedness) -> bool {
+    // Any two of the hole cards, which are both of them in hold'em.
+    (0..cards.len()).any(|i| {
+        (i + 1..cards.len()).any(|j| holds_pair([cards[i], cards[j]], patterns, suitedness))
+    })
+}
+
+fn holds_pair(cards: [Card; 2], patterns: &[CardPattern; 2], suitedness: Suitedness) -> bool {
+    let suited = cards[0].suit().is_some() && cards[0].suit() == cards[1].suit();
+    let suits_match = match suitedness {
+        Suitedness::Any => true,
+        Suitedness::Suited => suited,
+        Suitedness::Offsuit => !suited,
+    };
+    suits_match
+        && ((patterns[0].matches(cards[0]) && patterns[1].matches(cards[1]))
+            || (patterns[0].matches(cards[1]) && patterns[1].matches(cards[0])))
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Kind {
+    Word,
+    String,
+    Operator,
+    Open,
+    Close,
+    Comma,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Token<'a> {
+    kind: Kind,
+    text: &'a str,
+    position: usize,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token<'_>>, QueryError> {
+    let is_word = |c: char| c.is_alphanumeric() || "_+-:.?$".contains(c);
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let mut end = start + c.len_utf8();
+        let kind = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Kind::Open,
+            ')' => Kind::Close,
+            ',' => Kind::Comma,
+            '"' => {
+                let close = text[end..].find('"').ok_or(QueryError {
+                    position: start,
+                    message: "Unclosed string".to_string(),
+                })?;
+                end += close + 1;
+                while chars.next_if(|&(i, _)| i < end).is_some() {}
+                tokens.push(Token {
+                    kind: Kind::String,
+                    text: &text[start + 1..end - 1],
+                    position: start,
+                });
+                continue;
+            }
+            '=' | '~' => Kind::Operator,
+            '<' | '>' | '!' => {
+                if chars.next_if(|&(_, c)| c == '=').is_some() {
+                    end += 1;
+                } else if c == '!' {
+                    return Err(QueryError {
+                        position: start,
+                        message: "Expected \"!=\"".to_string(),
+                    });
+                }
+                Kind::Operator
+            }
+            c if is_word(c) => {
+                while let Some((i, c)) = chars.next_if(|&(_, c)| is_word(c)) {
+                    end = i + c.len_utf8();
+                }
+                Kind::Word
+            }
+            c => {
+                return Err(QueryError {
+                    position: start,
+                    message: format!("Unexpected {:?}", c),
+                })
+            }
+        };
+        tokens.push(Token {
+            kind,
+            text: &text[start..end],
+            position: start,
+        });
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    index: usize,
+    end: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error_at(&self, position: usize, message: &str) -> QueryError {
+        QueryError {
+            position,
+            message: message.to_string(),
+        }
+    }
+
+    /// An error at the next token, or at the end of the expression.
+    fn error(&self, message: &str) -> QueryError {
+        let position = self.tokens.get(self.index).map_or(self.end, |t| t.position);
+        self.error_at(position, message)
+    }
+
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.index).copied()
+    }
+
+    /// The next token if it is the given keyword, ignoring case.
+    fn keyword(&mut self, word: &str) -> bool {
+        let found = self
+            .peek()
+            .is_some_and(|t| t.kind == Kind::Word && t.text.eq_ignore_ascii_case(word));
+        self.index += found as usize;
+        found
+    }
+
+    fn expect(&mut self, kind: Kind, what: &str) -> Result<Token<'a>, QueryError> {
+        match self.peek() {
+            Some(token) if token.kind == kind => {
+                self.index += 1;
+                Ok(token)
+            }
+            _ => Err(self.error(&format!("Expected {}", what))),
+        }
+    }
+
+    fn word(&mut self, what: &str) -> Result<Token<'a>, QueryError> {
+        self.expect(Kind::Word, what)
+    }
+
+    fn or(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.and()?;
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.not()?;
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, QueryError> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        if self.peek().is_some_and(|t| t.kind == Kind::Open) {
+            self.index += 1;
+            let expr = self.or()?;
+            self.expect(Kind::Close, "\")\"")?;
+            return Ok(expr);
+        }
+        self.predicate().map(Expr::Predicate)
+    }
+
+    fn predicate(&mut self) -> Result<Predicate, QueryError> {
+        let start = self.index;
+        let word = self.word("a predicate")?;
+        Ok(match word.text.to_ascii_lowercase().as_str() {
+            "hero" => self.subject(Subject::Hero)?,
+            "player" => {
+                let id = self.number()?;
+                self.subject(Subject::Player(id))?
+            }
+            "showdown" => Predicate::Showdown(None),
+            "street" => Predicate::Street(self.compare()?, self.street()?),
+            "pot" => Predicate::Pot(self.compare()?, self.amount()?),
+            "board" if self.keyword("paired") => Predicate::BoardPaired,
+            "board" if self.keyword("has") => Predicate::BoardHas(self.card_pattern()?),
+            "board" => return Err(self.error("Expected \"has\" or \"paired\"")),
+            "time" => Predicate::Time(self.compare()?, self.time()?),
+            "players" => Predicate::Players(self.compare()?, self.number()?),
+            "id" => Predicate::Id(self.compare()?, self.number()?),
+            _ => {
+                self.index = start;
+                let street = self.street()?;
+                let operator = self.expect(Kind::Operator, "\"~\"")?;
+                if operator.text != "~" {
+                    return Err(self.error_at(operator.position, "Expected \"~\""));
+                }
+                let pattern = self.expect(Kind::String, "an action pattern")?;
+                if let Some(i) = pattern.text.find(|c| !"fxcbra*?".contains(c)) {
+                    return Err(self.error_at(pattern.position + 1 + i, "Invalid action letter"));
+                }
+                Predicate::Sequence(street, pattern.text.to_string())
+            }
+        })
+    }
+
+    fn subject(&mut self, subject: Subject) -> Result<Predicate, QueryError> {
+        let Some(token) = self.peek().filter(|t| t.kind == Kind::Word) else {
+            return Ok(Predicate::Dealt(subject));
+        };
+        let word = token.text.to_ascii_lowercase();
+        let verb = match word.as_str() {
+            "and" | "or" => return Ok(Predicate::Dealt(subject)),
+            "position" => {
+                self.index += 1;
+                let mut names = Vec::new();
+                if self.keyword("in") {
+                    self.expect(Kind::Open, "\"(\"")?;
+                    loop {
+                        names.push(self.position_name()?);
+                        if self.peek().is_some_and(|t| t.kind == Kind::Close) {
+                            self.index += 1;
+                            break;
+                        }
+                        self.expect(Kind::Comma, "\",\" or \")\"")?;
+                    }
+                } else {
+                    let operator = self.expect(Kind::Operator, "\"=\" or \"in\"")?;
+                    if operator.text != "=" {
+                        return Err(self.error_at(operator.position, "Expected \"=\" or \"in\""));
+                    }
+                    names.push(self.position_name()?);
+                }
+                return Ok(Predicate::Position(subject, names));
+            }
+            "holds" => {
+                self.index += 1;
+                return self.hole_cards(subject);
+            }
+            "net" => {
+                self.index += 1;
+                return Ok(Predicate::Net(subject, self.compare()?, self.amount()?));
+            }
+            "showdown" => {
+                self.index += 1;
+                return Ok(Predicate::Showdown(Some(subject)));
+            }
+            "fold" => Verb::Fold,
+            "check" => Verb::Check,
+            "call" => Verb::Call,
+            "bet" => Verb::Bet,
+            "raise" => Verb::Raise,
+            "allin" | "all-in" => Verb::AllIn,
+            level => match level.strip_suffix("bet").and_then(|n| n.parse().ok()) {
+                Some(n) if n >= 2 => Verb::Level(n),
+                _ => return Err(self.error("Expected a player predicate")),
+            },
+        };
+        self.index += 1;
+        let street = match self.peek() {
+            Some(t) if t.kind == Kind::Word && parse_street(t.text).is_some() => {
+                Some(self.street()?)
+            }
+            _ => None,
+        };
+        Ok(Predicate::Acted(subject, verb, street))
+    }
+
+    fn compare(&mut self) -> Result<Compare, QueryError> {
+        let token = self.expect(Kind::Operator, "a comparison")?;
+        Ok(match token.text {
+            "=" => Compare::Eq,
+            "!=" => Compare::Ne,
+            "<" => Compare::Lt,
+            "<=" => Compare::Le,
+            ">" => Compare::Gt,
+            ">=" => Compare::Ge,
+            _ => return Err(self.error_at(token.position, "Expected a comparison")),
+        })
+    }
+
+    fn number(&mut self) -> Result<u64, QueryError> {
+        let token = self.word("a number")?;
+        token
+            .text
+            .parse()
+            .map_err(|_| self.error_at(token.position, "Expected a number"))
+    }
+
+    fn amount(&mut self) -> Result<Amount, QueryError> {
+        let token = self.word("an amount")?;
+        let lower = token.text.to_ascii_lowercase();
+        let (value, big_blinds) = match lower.strip_suffix("bb") {
+            Some(value) => (value, true),
+            None => (lower.as_str(), false),
+        };
+        let value: f64 = value
+            .parse()
+            .ok()
+            .filter(|v: &f64| v.is_finite())
+            .ok_or_else(|| self.error_at(token.position, "Expected an amount"))?;
+        Ok(Amount { value, big_blinds })
+    }
+
+    fn street(&mut self) -> Result<Street, QueryError> {
+        let token = self.word("a street")?;
+        parse_street(token.text).ok_or_else(|| self.error_at(token.position, "Expected a street"))
+    }
+
+    fn position_name(&mut self) -> Result<Position, QueryError> {
+        let token = self.word("a position")?;
+        token
+            .text
+            .parse()
+            .map_err(|_| self.error_at(token.position, "Expected a position"))
+    }
+
+    fn card_pattern(&mut self) -> Result<CardPattern, QueryError> {
+        let token = self.word("a card pattern")?;
+        let mut chars = token.text.chars();
+        let pattern = card_pattern(chars.next(), chars.next().or(Some('?')));
+        match pattern {
+            Some(pattern) if chars.next().is_none() => Ok(pattern),
+            _ => Err(self.error_at(token.position, "Expected a card pattern")),
+        }
+    }
+
+    fn hole_cards(&mut self, subject: Subject) -> Result<Predicate, QueryError> {
+        let token = self.word("hole cards")?;
+        let invalid = || self.error_at(token.position, "Expected hole cards");
+        let chars: Vec<char> = token.text.chars().collect();
+        let (patterns, suitedness) = match chars[..] {
+            [a, b] => ([(a, '?'), (b, '?')], Suitedness::Any),
+            [a, b, s] if s.eq_ignore_ascii_case(&'s') => ([(a, '?'), (b, '?')], Suitedness::Suited),
+            [a, b, o] if o.eq_ignore_ascii_case(&'o') => {
+                ([(a, '?'), (b, '?')], Suitedness::Offsuit)
+            }
+            [a, s, b, t] => ([(a, s), (b, t)], Suitedness::Any),
+            _ => return Err(invalid()),
+        };
+        let pattern = |(rank, suit)| card_pattern(Some(rank), Some(suit)).ok_or_else(invalid);
+        Ok(Predicate::Holds(
+            subject,
+            [pattern(patterns[0])?, pattern(patterns[1])?],
+            suitedness,
+        ))
+    }
+
+    /// A date, a date and time, or a UNIX timestamp.
+    fn time(&mut self) -> Result<Timestamp, QueryError> {
+        let token = self.word("a time")?;
+        if let Ok(timestamp) = token.text.parse() {
+            return Ok(timestamp);
+        }
+        let fields: Option<Vec<u32>> = token
+            .text
+            .split(['-', 'T', 't', ':'])
+            .map(|f| f.parse().ok())
+            .collect();
+        match fields.as_deref() {
+            Some(&[year, month, day, ref time @ ..])
+                if (1..=12).contains(&month) && (1..=31).contains(&day) && time.len() <= 3 =>
+            {
+                let field = |i: usize| time.get(i).copied().unwrap_or(0);
+                Ok(timestamp_from_civil(
+                    year as i64,
+                    month,
+                    day,
+                    field(0),
+                    field(1),
+                    field(2),
+                ))
+            }
+            _ => Err(self.error_at(token.position, "Expected a time")),
+        }
+    }
+}
+
+fn parse_street(text: &str) -> Option<Street> {
+    Some(match text.to_ascii_lowercase().as_str() {
+        "preflop" => Street::Preflop,
+        "flop" => Street::Flop,
+        "turn" => Street::Turn,
+        "river" => Street::River,
+        _ => return None,
+    })
+}
+
+fn card_pattern(rank: Option<char>, suit: Option<char>) -> Option<CardPattern> {
+    let rank = match rank? {
+        '?' => None,
+        c => Some(Rank::from_char(c)?),
+    };
+    let suit = match suit? {
+        '?' => None,
+        c => Some(Suit::from_char(c)?),
+    };
+    Some(CardPattern { rank, suit })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Annotations;
+
+    /// The ids of the exhaustive session's hands that match, with player 1001 as hero.
+    fn matching(query: &str) -> Vec<Id> {
+        let mut session = Session::exhaustive();
+        session.hero_id = 1001;
+        let query: Query = query.parse().expect("Valid query");
+        query
+            .filter(&session)
+            .tables
+            .iter()
+            .flat_map(|t| &t.events)
+            .filter_map(|e| match e {
+                TableEvent::Hand(hand) => Some(hand.id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_predicates() {
+        assert_eq!(matching("hero"), vec![9001]);
+        assert_eq!(matching("hero call preflop and hero fold flop"), vec![9001]);
+        assert_eq!(matching("player 1002 raise preflop"), vec![9001]);
+        assert_eq!(matching("hero position = BB"), vec![9001]);
+        assert_eq!(matching("player 1003 position in (sb, btn)"), vec![9002]);
+        assert_eq!(matching("showdown"), vec![9002]);
+        assert_eq!(matching("player 1002 showdown"), vec![9002]);
+        assert_eq!(matching("street >= turn"), vec![9002]);
+        assert_eq!(matching("street = flop"), vec![9001]);
+        assert_eq!(matching("pot >= 30bb"), vec![9002]);
+        assert_eq!(matching("pot = 1800"), vec![9001]);
+        assert_eq!(matching("board has K?"), vec![9001]);
+        assert_eq!(matching("board has ?d"), vec![9002]);
+        assert_eq!(matching("board paired"), vec![9001, 9002]);
+        assert_eq!(matching("flop ~ \"brf\""), vec![9001]);
+        assert_eq!(matching("flop ~ \"x*c\" and turn ~ \"??\""), vec![9002]);
+        assert_eq!(matching("river ~ \"*\""), vec![9002]);
+        assert_eq!(matching("player 1003 3bet flop"), vec![9002]);
+        assert_eq!(matching("player 1003 3bet"), Vec::<Id>::new());
+        assert_eq!(matching("player 1002 bet"), Vec::<Id>::new());
+        assert_eq!(matching("player 1002 raise flop"), vec![9001, 9002]);
+        assert_eq!(matching("time >= 2024-08-22T02:25"), vec![9002]);
+        assert_eq!(matching("time < 1724293500"), vec![9001]);
+        assert_eq!(matching("hero holds AA and hero holds AcAs"), vec![9001]);
+        assert_eq!(matching("player 1002 holds 22o"), vec![9001]);
+        assert_eq!(matching("hero net < -5bb"), vec![9001]);
+        assert_eq!(matching("players = 2 and id != 9001"), vec![9002]);
+        assert_eq!(
+            matching("not showdown and (hero or player 1003)"),
+            vec![9001]
+        );
+    }
+
+    #[test]
+    fn test_filter_keeps_seats() {
+        let mut session = Session::exhaustive();
+        session.annotations = Annotations::example();
+        let filtered = "showdown".parse::<Query>().unwrap().filter(&session);
+        // Hand 9001 ends before showdown, so its annotations go with it.
+        assert!(filtered.annotations.hands.is_empty());
+        assert_eq!(filtered.annotations.players, session.annotations.players);
+        let table = &filtered.tables[0];
+        let mut state = TableState::new(table);
+        for event in &table.events {
+            state.apply(table, event).expect("Every kept hand replays");
+        }
+        assert!(matches!(table.events[0], TableEvent::SeatUpdate(_)));
+        assert!(matches!(table.events[1], TableEvent::StackUpdate(_)));
+        assert_eq!(table.events.len(), 3);
+        let none = "id = 1".parse::<Query>().unwrap().filter(&session);
+        assert!(none.tables.is_empty());
+    }
+
+    #[test]
+    fn test_filter_keeps_stakes() {
+        let session = Session::sit_and_go();
+        let filtered = "showdown".parse::<Query>().unwrap().filter(&session);
+        let table = &filtered.tables[0];
+        // Only the second hand is kept, after the level change to 20/40 with a 5 ante.
+        let TableEvent::LevelChange(level) = &table.events[0] else {
+            panic!("Expected a level change");
+        };
+        assert_eq!(level.level, 2);
+        let mut state = TableState::new(table);
+        let replays: Vec<_> = table
+            .events
+            .iter()
+            .filter_map(|e| state.apply(table, e).expect("Every kept hand replays"))
+            .collect();
+        assert_eq!(replays.len(), 1);
+        assert_eq!((replays[0].big_blind, replays[0].antes.len()), (40, 3));
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |query: &str| query.parse::<Query>().unwrap_err();
+        assert_eq!(
+            error("street >= moon").to_string(),
+            "Expected a street at character 11."
+        );
+        assert_eq!(error("hero and").position, 8);
+        assert_eq!(error("(showdown").message, "Expected \")\"");
+        assert_eq!(error("flop ~ \"rz\"").position, 9);
+        assert_eq!(
+            error("hero position < BTN").message,
+            "Expected \"=\" or \"in\""
+        );
+        assert_eq!(error("showdown showdown").message, "Unexpected input");
+    }
+}
@@ -0,0 +1,278 @@
+use crate::engine::ev::expected_values;
+use crate::engine::replay::{Replay, Stakes};
+use crate::engine::state::TableState;
+use crate::formats::civil_from_timestamp;
+use crate::game::{Decimal, Hand, Id, Session, Table, TableEvent, Timestamp};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+/// Totals over a set of hands for one player.
+///
+/// Amounts are in chips. Big blind totals convert each hand at its own table's big blind, so
+/// results at different stakes add up fairly.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Results {
+    pub hands: u64,
+    pub net: i64,
+    pub net_big_blinds: f64,
+    /// Rake attributed to the player in proportion to the chips they put in the pot.
+    pub rake: f64,
+    /// All-in adjusted expected result.
+    pub ev: f64,
+    pub ev_big_blinds: f64,
+}
+
+impl Results {
+    /// Big blinds won per 100 hands.
+    pub fn bb_per_100(&self) -> Option<f64> {
+        (self.hands > 0).then(|| self.net_big_blinds * 100.0 / self.hands as f64)
+    }
+
+    /// Expected big blinds won per 100 hands.
+    pub fn ev_bb_per_100(&self) -> Option<f64> {
+        (self.hands > 0).then(|| self.ev_big_blinds * 100.0 / self.hands as f64)
+    }
+
+    fn add(&mut self, other: &Results) {
+        self.hands += other.hands;
+        self.net += other.net;
+        self.net_big_blinds += other.net_big_blinds;
+        self.rake += other.rake;
+        self.ev += other.ev;
+        self.ev_big_blinds += other.ev_big_blinds;
+    }
+}
+
+/// A point of a player's cumulative results, after a hand.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Point {
+    /// The number of hands played so far.
+    pub hands: u64,
+    pub hand_id: Id,
+    pub timestamp: Timestamp,
+    pub net: i64,
+    pub ev: f64,
+}
+
+/// A player's [`Results`] in total and broken down by table, stake and date.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct PlayerReport {
+    pub total: Results,
+    pub tables: BTreeMap<Id, Results>,
+    /// Results by the blinds each hand was played at, which a
+    /// [`LevelChange`](crate::game::LevelChange) may have raised from [`Table::blinds`].
+    pub stakes: BTreeMap<Vec<Decimal>, Results>,
+    /// Results by UTC date, as `YYYY-MM-DD`.
+    pub dates: BTreeMap<String, Results>,
+    hands: Vec<(Timestamp, Id, Results)>,
+}
+
+impl PlayerReport {
+    /// Cumulative results after each hand, in the order the hands were played.
+    pub fn series(&self) -> Vec<Point> {
+        let mut hands: Vec<_> = self.hands.iter().collect();
+        hands.sort_by_key(|(timestamp, _, _)| *timestamp);
+        let mut total = Results::default();
+        hands
+            .into_iter()
+            .map(|(timestamp, hand_id, results)| {
+                total.add(results);
+                Point {
+                    hands: total.hands,
+                    hand_id: *hand_id,
+                    timestamp: *timestamp,
+                    net: total.net,
+                    ev: total.ev,
+                }
+            })
+            .collect()
+    }
+
+    fn add(&mut self, table: &Table, stakes: &Stakes, hand: &Hand, results: Results) {
+        let (year, month, day, ..) = civil_from_timestamp(hand.timestamp);
+        let date = format!("{:04}-{:02}-{:02}", year, month, day);
+        self.total.add(&results);
+        self.tables.entry(table.id).or_default().add(&results);
+        self.stakes
+            .entry(stakes.blinds.clone())
+            .or_default()
+            .add(&results);
+        self.dates.entry(date).or_default().add(&results);
+        self.hands.push((hand.timestamp, hand.id, results));
+    }
+}
+
+/// Win-rate and bankroll reports for every player, by [`Player`](crate::game::Player) id.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Report {
+    pub players: BTreeMap<Id, PlayerReport>,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Report::default()
+    }
+
+    pub fn player(&self, id: Id) -> Option<&PlayerReport> {
+        self.players.get(&id)
+    }
+
+    /// Adds the results of a finished hand played at the given stakes.
+    pub fn add_hand(&mut self, table: &Table, stakes: &Stakes, hand: &Hand, replay: &Replay) {
+        let big_blind = replay.big_blind.max(1) as f64;
+        let pot = replay.total_pot().max(1) as f64;
+        for (seat, expected) in replay.seats.iter().zip(expected_values(replay)) {
+            let net = expected.net;
+            let ev = expected.ev;
+            let results = Results {
+                hands: 1,
+                net,
+                net_big_blinds: net as f64 / big_blind,
+                rake: replay.rake as f64 * seat.committed as f64 / pot,
+                ev,
+                ev_big_blinds: ev / big_blind,
+            };
+            self.players
+                .entry(seat.player.id)
+                .or_default()
+                .add(table, stakes, hand, results);
+        }
+    }
+
+    /// Adds every hand of a session that replays.
+    pub fn add_session(&mut self, session: &Session) {
+        for table in &session.tables {
+            let mut state = TableState::new(table);
+            for event in &table.events {
+                if let (Ok(Some(replay)), TableEvent::Hand(hand)) =
+                    (state.apply(table, event), event)
+                {
+                    self.add_hand(table, &state.stakes, hand, &replay);
+                }
+            }
+        }
+    }
+
+    /// Writes every player's results as CSV, one row for their total and one for each table,
+    /// stake and date.
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "player,group,key,hands,net,net_bb,rake,ev,ev_bb,bb_per_100,ev_bb_per_100"
+        )?;
+        for (id, report) in &self.players {
+            let mut rows = vec![("total", String::new(), &report.total)];
+            rows.extend(
+                report
+                    .tables
+                    .iter()
+                    .map(|(t, r)| ("table", t.to_string(), r)),
+            );
+            rows.extend(report.stakes.iter().map(|(blinds, r)| {
+                let blinds: Vec<String> = blinds.iter().map(Decimal::to_string).collect();
+                ("stake", blinds.join("/"), r)
+            }));
+            rows.extend(report.dates.iter().map(|(d, r)| ("date", d.clone(), r)));
+            for (group, key, results) in rows {
+                let optional = |value: Option<f64>| value.map_or(String::new(), number);
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{},{},{},{},{},{}",
+                    id,
+                    group,
+                    key,
+                    results.hands,
+                    results.net,
+                    number(results.net_big_blinds),
+                    number(results.rake),
+                    number(results.ev),
+                    number(results.ev_big_blinds),
+                    optional(results.bb_per_100()),
+                    optional(results.ev_bb_per_100()),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes every player's cumulative results as CSV, one row per hand.
+    pub fn write_series_csv<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "player,hands,hand,timestamp,net,ev")?;
+        for (id, report) in &self.players {
+            for point in report.series() {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{}",
+                    id,
+                    point.hands,
+                    point.hand_id,
+                    point.timestamp,
+                    point.net,
+                    number(point.ev)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats a fractional amount with at most two decimals.
+fn number(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        text => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() -> io::Result<()> {
+        let mut report = Report::new();
+        report.add_session(&Session::exhaustive());
+
+        let p1002 = report.player(1002).expect("Dealt");
+        assert_eq!(p1002.total.hands, 2);
+        assert_eq!(p1002.total.net, 897 - 1700);
+        let bb_per_100 = p1002.total.bb_per_100().expect("Hands");
+        assert!((bb_per_100 + 401.5).abs() < 1e-9, "{}", bb_per_100);
+        assert_eq!(p1002.dates.keys().collect::<Vec<_>>(), ["2024-08-22"]);
+        assert_eq!(p1002.stakes[&vec![50, 100]].hands, 2);
+        let net: Vec<i64> = p1002.series().iter().map(|p| p.net).collect();
+        assert_eq!(net, [897, -803]);
+
+        let mut csv = Vec::new();
+        report.write_csv(&mut csv)?;
+        let csv = String::from_utf8(csv).expect("UTF-8");
+        assert!(
+            csv.contains("\n1002,total,,2,-803,-8.03,3,-803,"),
+            "{}",
+            csv
+        );
+        assert!(csv.contains("\n1002,stake,50/100,2,-803,"), "{}", csv);
+        assert!(csv.contains("\n1003,date,2024-08-22,1,"), "{}", csv);
+
+        let mut csv = Vec::new();
+        report.write_series_csv(&mut csv)?;
+        let csv = String::from_utf8(csv).expect("UTF-8");
+        assert!(
+            csv.contains("\n1002,2,9002,1724293500,-803,-803\n"),
+            "{}",
+            csv
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_report_stakes() {
+        let mut report = Report::new();
+        report.add_session(&Session::sit_and_go());
+        let hero = report.player(1).expect("Dealt");
+        let stakes: Vec<(&Vec<Decimal>, u64)> =
+            hero.stakes.iter().map(|(b, r)| (b, r.hands)).collect();
+        assert_eq!(stakes, [(&vec![10, 20], 1), (&vec![20, 40], 1)]);
+    }
+}
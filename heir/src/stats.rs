@@ -0,0 +1,199 @@
+use crate::engine::replay::{Replay, Street};
+use crate::engine::state::TableState;
+use crate::game::{ActionType, Id, Session};
+use std::collections::BTreeMap;
+use std::ops::AddAssign;
+
+/// A player's HUD counters: for each stat, the hands where they took the action and the hands
+/// where they had the chance to.
+///
+/// Counters only ever grow, so adding hands one at a time and merging counters gathered
+/// separately give the same totals.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Counters {
+    pub hands: u64,
+    /// Hands where the player acted preflop, which excludes walks in the big blind.
+    pub preflop_opportunities: u64,
+    /// Hands where the player called or raised preflop.
+    pub voluntary: u64,
+    pub preflop_raises: u64,
+    /// Hands where the player acted preflop facing a single raise.
+    pub three_bet_opportunities: u64,
+    pub three_bets: u64,
+    /// Bets and raises on the flop and later.
+    pub postflop_aggressive: u64,
+    pub postflop_calls: u64,
+    /// Hands where the player was still in when the flop was dealt.
+    pub saw_flop: u64,
+    pub showdowns: u64,
+    /// Showdowns where the player won at least part of a pot.
+    pub showdowns_won: u64,
+}
+
+impl Counters {
+    /// Voluntarily put money in pot, as a fraction of hands.
+    pub fn vpip(&self) -> Option<f64> {
+        ratio(self.voluntary, self.preflop_opportunities)
+    }
+
+    /// Preflop raise, as a fraction of hands.
+    pub fn pfr(&self) -> Option<f64> {
+        ratio(self.preflop_raises, self.preflop_opportunities)
+    }
+
+    pub fn three_bet(&self) -> Option<f64> {
+        ratio(self.three_bets, self.three_bet_opportunities)
+    }
+
+    /// Aggression factor, postflop bets and raises per call.
+    pub fn aggression_factor(&self) -> Option<f64> {
+        ratio(self.postflop_aggressive, self.postflop_calls)
+    }
+
+    /// Went to showdown, as a fraction of flops seen.
+    pub fn wtsd(&self) -> Option<f64> {
+        ratio(self.showdowns, self.saw_flop)
+    }
+
+    /// Won money at showdown, as a fraction of showdowns.
+    pub fn wsd(&self) -> Option<f64> {
+        ratio(self.showdowns_won, self.showdowns)
+    }
+}
+
+fn ratio(count: u64, opportunities: u64) -> Option<f64> {
+    (opportunities > 0).then(|| count as f64 / opportunities as f64)
+}
+
+impl AddAssign for Counters {
+    fn add_assign(&mut self, other: Counters) {
+        self.hands += other.hands;
+        self.preflop_opportunities += other.preflop_opportunities;
+        self.voluntary += other.voluntary;
+        self.preflop_raises += other.preflop_raises;
+        self.three_bet_opportunities += other.three_bet_opportunities;
+        self.three_bets += other.three_bets;
+        self.postflop_aggressive += other.postflop_aggressive;
+        self.postflop_calls += other.postflop_calls;
+        self.saw_flop += other.saw_flop;
+        self.showdowns += other.showdowns;
+        self.showdowns_won += other.showdowns_won;
+    }
+}
+
+/// [`Counters`] for every player, by [`Player`](crate::game::Player) id.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Stats {
+    pub players: BTreeMap<Id, Counters>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Stats::default()
+    }
+
+    pub fn player(&self, id: Id) -> Option<&Counters> {
+        self.players.get(&id)
+    }
+
+    /// Counts a finished hand.
+    pub fn add_hand(&mut self, replay: &Replay) {
+        for seat in &replay.seats {
+            let mut counters = Counters {
+                hands: 1,
+                ..Counters::default()
+            };
+            let mut level = 1;
+            for step in &replay.steps {
+                let aggressive = step.raise > 0;
+                if step.seat == seat.seat {
+                    if step.street == Street::Preflop {
+                        counters.preflop_opportunities = 1;
+                        counters.voluntary |= (step.amount > 0) as u64;
+                        counters.preflop_raises |= aggressive as u64;
+                        if level == 2 {
+                            counters.three_bet_opportunities = 1;
+                            counters.three_bets |= aggressive as u64;
+                        }
+                    } else if aggressive {
+                        counters.postflop_aggressive += 1;
+                    } else if step.amount > 0 {
+                        counters.postflop_calls += 1;
+                    }
+                }
+                if step.street == Street::Preflop && aggressive {
+                    level += 1;
+                }
+            }
+            let folded_preflop = replay.steps.iter().any(|s| {
+                s.seat == seat.seat
+                    && s.street == Street::Preflop
+                    && s.action.action_type == ActionType::Fold
+            });
+            counters.saw_flop = (replay.street >= Street::Flop && !folded_preflop) as u64;
+            if replay.showdown.len() > 1 && replay.showdown.iter().any(|(s, _)| *s == seat.seat) {
+                counters.showdowns = 1;
+                counters.showdowns_won = (replay.collected(seat.seat) > 0) as u64;
+            }
+            *self.players.entry(seat.player.id).or_default() += counters;
+        }
+    }
+
+    /// Counts every hand of a session that replays.
+    pub fn add_session(&mut self, session: &Session) {
+        for table in &session.tables {
+            let mut state = TableState::new(table);
+            for event in &table.events {
+                if let Ok(Some(replay)) = state.apply(table, event) {
+                    self.add_hand(&replay);
+                }
+            }
+        }
+    }
+
+    /// Adds the counters gathered by another [`Stats`].
+    pub fn merge(&mut self, other: &Stats) {
+        for (id, counters) in &other.players {
+            *self.players.entry(*id).or_default() += *counters;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let session = Session::exhaustive();
+        let mut stats = Stats::new();
+        stats.add_session(&session);
+
+        let p1002 = stats.player(1002).expect("Dealt");
+        assert_eq!(p1002.hands, 2);
+        assert_eq!(p1002.vpip(), Some(0.5));
+        assert_eq!(p1002.pfr(), Some(0.5));
+        assert_eq!(p1002.three_bet(), None);
+        assert_eq!(p1002.aggression_factor(), Some(2.0));
+        assert_eq!(p1002.wtsd(), Some(0.5));
+        assert_eq!(p1002.wsd(), Some(0.0));
+
+        let p1001 = stats.player(1001).expect("Dealt");
+        assert_eq!(p1001.vpip(), Some(1.0));
+        assert_eq!(p1001.pfr(), Some(0.0));
+        assert_eq!(p1001.three_bet(), Some(0.0));
+        assert_eq!(p1001.aggression_factor(), None);
+        assert_eq!(p1001.wtsd(), Some(0.0));
+
+        let p1003 = stats.player(1003).expect("Dealt");
+        assert_eq!(p1003.vpip(), Some(1.0));
+        assert_eq!(p1003.postflop_aggressive, 2);
+        assert_eq!(p1003.wsd(), Some(1.0));
+
+        let mut merged = Stats::new();
+        merged.merge(&stats);
+        merged.merge(&stats);
+        assert_eq!(merged.player(1002).unwrap().hands, 4);
+        assert_eq!(merged.player(1002).unwrap().vpip(), Some(0.5));
+    }
+}
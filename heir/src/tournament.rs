@@ -0,0 +1,246 @@
+use crate::engine::state::Tracker;
+use crate::game::{
+    Decimal, Finish, Id, Level, LevelChange, Session, Table, TableEvent, Timestamp, Tournament,
+};
+use std::cmp::Reverse;
+
+impl Tournament {
+    /// The buy-ins of every entrant, without fees.
+    pub fn prize_pool(&self) -> Decimal {
+        self.buy_in * self.entrants as Decimal
+    }
+
+    /// The prize paid for a finishing position, counting from 1.
+    pub fn prize(&self, position: u32) -> Decimal {
+        let index = position.checked_sub(1).map(|i| i as usize);
+        index
+            .and_then(|i| self.payouts.get(i))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// The level in play at a time, with its number counting from 1, or `None` before the
+    /// start. The last level goes on once the schedule runs out.
+    pub fn level_at(&self, timestamp: Timestamp) -> Option<(u32, &Level)> {
+        let mut elapsed = timestamp.checked_sub(self.start)?;
+        for (i, level) in self.levels.iter().enumerate() {
+            if elapsed < level.duration as u64 || i + 1 == self.levels.len() {
+                return Some((i as u32 + 1, level));
+            }
+            elapsed -= level.duration as u64;
+        }
+        None
+    }
+
+    /// Inserts a [`LevelChange`] before every hand of the table dealt in a different level
+    /// than the one in play, by the hand's timestamp and the schedule.
+    pub fn schedule_levels(&self, table: &mut Table) {
+        let mut current = None;
+        let mut events = Vec::with_capacity(table.events.len());
+        for event in table.events.drain(..) {
+            match &event {
+                TableEvent::LevelChange(change) => current = Some(change.level),
+                TableEvent::Hand(hand) => {
+                    if let Some((number, level)) = self.level_at(hand.timestamp) {
+                        if current != Some(number) {
+                            events.push(TableEvent::LevelChange(LevelChange {
+                                level: number,
+                                blinds: level.blinds.clone(),
+                                ante: level.ante,
+                            }));
+                            current = Some(number);
+                        }
+                    }
+                }
+                TableEvent::StackUpdate(_) | TableEvent::SeatUpdate(_) => {}
+            }
+            events.push(event);
+        }
+        table.events = events;
+    }
+}
+
+/// A player losing their last chips in a hand.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Elimination {
+    pub player_id: Id,
+    pub table_id: Id,
+    pub hand_id: Id,
+    pub timestamp: Timestamp,
+    /// The player's stack as the hand was dealt.
+    pub stack: Decimal,
+}
+
+/// Every player eliminated over a session's hands, first out first.
+///
+/// Players eliminated in the same hand go out in order of their stacks as it was dealt, the
+/// smaller stack first, as tournaments rank them.
+pub fn eliminations(session: &Session) -> Vec<Elimination> {
+    let mut eliminations = Vec::new();
+    for table in &session.tables {
+        for tracked in Tracker::new(table) {
+            let Ok(replay) = &tracked.replay else {
+                continue;
+            };
+            for seat in replay.seats.iter().filter(|s| s.stack == 0) {
+                let Some(Some(player)) = tracked.seats.get(seat.seat as usize) else {
+                    continue;
+                };
+                eliminations.push(Elimination {
+                    player_id: player.id,
+                    table_id: table.id,
+                    hand_id: tracked.hand.id,
+                    timestamp: tracked.hand.timestamp,
+                    stack: player.stack,
+                });
+            }
+        }
+    }
+    eliminations.sort_by_key(|e| (e.timestamp, e.stack));
+    eliminations
+}
+
+/// Finishing positions worked out from a session's hands, best first, with the prizes they
+/// pay.
+///
+/// Players still holding chips when their tables' events end place first, the larger stack
+/// higher, followed by the eliminated players, the last out highest.
+pub fn finishes(tournament: &Tournament, session: &Session) -> Vec<Finish> {
+    let eliminated = eliminations(session);
+    let mut survivors: Vec<(Id, Decimal)> = Vec::new();
+    for table in &session.tables {
+        let mut tracker = Tracker::new(table);
+        tracker.by_ref().for_each(drop);
+        for player in tracker.state().seats.iter().flatten() {
+            let out = eliminated.iter().any(|e| e.player_id == player.id);
+            if player.stack > 0 && !out && survivors.iter().all(|(id, _)| *id != player.id) {
+                survivors.push((player.id, player.stack));
+            }
+        }
+    }
+    survivors.sort_by_key(|&(id, stack)| (Reverse(stack), id));
+    survivors
+        .into_iter()
+        .map(|(id, _)| id)
+        .chain(eliminated.iter().rev().map(|e| e.player_id))
+        .enumerate()
+        .map(|(i, player_id)| Finish {
+            player_id,
+            position: i as u32 + 1,
+            prize: tournament.prize(i as u32 + 1),
+        })
+        .collect()
+}
+
+/// A player moved from one table to another by [`balance`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Move {
+    pub from: Id,
+    pub to: Id,
+}
+
+/// The moves that balance a tournament's tables, given each table's id and player count.
+///
+/// Tables are broken, the fullest kept, while the players fit at fewer tables of
+/// `table_size` seats, and players then move from the fullest table to the shortest until
+/// no two tables differ by more than one player. Without any players there is nothing to
+/// balance.
+pub fn balance(tables: &[(Id, usize)], table_size: usize) -> Vec<Move> {
+    let players: usize = tables.iter().map(|(_, n)| n).sum();
+    if players == 0 {
+        return Vec::new();
+    }
+    let needed = players.div_ceil(table_size.max(1)).max(1);
+    let mut kept: Vec<(Id, usize)> = tables.to_vec();
+    kept.sort_by_key(|&(_, n)| Reverse(n));
+    let broken = kept.split_off(needed.min(kept.len()));
+
+    let mut moves = Vec::new();
+    let shortest = |kept: &[(Id, usize)]| {
+        (0..kept.len())
+            .min_by_key(|&i| kept[i].1)
+            .expect("A table is kept")
+    };
+    for (from, n) in broken {
+        for _ in 0..n {
+            let to = shortest(&kept);
+            kept[to].1 += 1;
+            moves.push(Move {
+                from,
+                to: kept[to].0,
+            });
+        }
+    }
+    loop {
+        let to = shortest(&kept);
+        let from = (0..kept.len())
+            .max_by_key(|&i| (kept[i].1, Reverse(i)))
+            .expect("A table is kept");
+        if kept[from].1 <= kept[to].1 + 1 {
+            return moves;
+        }
+        kept[from].1 -= 1;
+        kept[to].1 += 1;
+        moves.push(Move {
+            from: kept[from].0,
+            to: kept[to].0,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        let session = Session::sit_and_go();
+        let tournament = session.tournament.as_ref().expect("Tournament");
+        assert_eq!(tournament.prize_pool(), 3000);
+        assert_eq!(tournament.prize(2), 1000);
+        assert_eq!(tournament.prize(3), 0);
+        assert_eq!(tournament.level_at(1724289999), None);
+        assert_eq!(tournament.level_at(1724290599).map(|(n, _)| n), Some(1));
+        assert_eq!(tournament.level_at(1724290600).map(|(n, _)| n), Some(2));
+        assert_eq!(tournament.level_at(1724299999).map(|(n, _)| n), Some(2));
+
+        let mut table = session.tables[0].clone();
+        table
+            .events
+            .retain(|e| !matches!(e, TableEvent::LevelChange(_)));
+        tournament.schedule_levels(&mut table);
+        assert_eq!(table, session.tables[0]);
+    }
+
+    #[test]
+    fn test_finishes() {
+        let session = Session::sit_and_go();
+        let out: Vec<(Id, Decimal)> = eliminations(&session)
+            .iter()
+            .map(|e| (e.player_id, e.stack))
+            .collect();
+        assert_eq!(out, [(3, 1480), (2, 1490)]);
+
+        let tournament = session.tournament.as_ref().expect("Tournament");
+        let finishes: Vec<(Id, u32, Decimal)> = finishes(tournament, &session)
+            .iter()
+            .map(|f| (f.player_id, f.position, f.prize))
+            .collect();
+        assert_eq!(finishes, [(1, 1, 2000), (2, 2, 1000), (3, 3, 0)]);
+    }
+
+    #[test]
+    fn test_balance() {
+        let moves = |tables: &[(Id, usize)]| {
+            let moves = balance(tables, 9);
+            moves.iter().map(|m| (m.from, m.to)).collect::<Vec<_>>()
+        };
+        assert_eq!(moves(&[(1, 9), (2, 9)]), []);
+        assert_eq!(moves(&[(1, 9), (2, 6)]), [(1, 2)]);
+        // Fourteen players fit at two tables, so the shortest table breaks.
+        assert_eq!(moves(&[(1, 6), (2, 5), (3, 3)]), [(3, 2), (3, 1), (3, 2)]);
+        assert_eq!(moves(&[(1, 2), (2, 1)]), [(2, 1)]);
+        assert_eq!(moves(&[]), []);
+        assert_eq!(moves(&[(1, 0), (2, 0)]), []);
+    }
+}
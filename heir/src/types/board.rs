@@ -77,10 +77,10 @@ impl Board {
     }
 
     /// Convert a [`Board`] to an array of five [`Card`]s.
-    pub fn to_array(&self) -> io::Result<[Card; 5]> {
+    pub fn to_array(self) -> io::Result<[Card; 5]> {
         let mut cards = [Card::Xx; 5];
-        for i in 0..5 {
-            cards[i] = self.get_card(i)?;
+        for (i, card) in cards.iter_mut().enumerate() {
+            *card = self.get_card(i)?;
         }
         Ok(cards)
     }
@@ -113,6 +113,12 @@ impl Board {
     }
 }
 
+impl Default for Board {
+    fn default() -> Self {
+        Board::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
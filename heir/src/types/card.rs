@@ -1,9 +1,10 @@
 use std::fmt;
 use std::io;
+use std::str::FromStr;
 
 /// A [`Card`] in a traditional 52-card deck.
 #[repr(u8)]
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
 pub enum Card {
     AceClubs = 0,
     AceDiamonds,
@@ -63,8 +64,8 @@ pub enum Card {
 
 impl Card {
     #[inline]
-    pub fn to_u8(&self) -> u8 {
-        *self as u8
+    pub fn to_u8(self) -> u8 {
+        self as u8
     }
 
     #[inline]
@@ -72,7 +73,7 @@ impl Card {
     pub fn from_u8(value: u8) -> io::Result<Self> {
         if value <= 53 {
             // Safety: all values 0..=53 are valid [`Card`]s.
-            Ok(unsafe { std::mem::transmute(value) })
+            Ok(unsafe { std::mem::transmute::<u8, Card>(value) })
         } else {
             Err(io::Error::new(
                 io::ErrorKind::InvalidData,
@@ -92,67 +93,251 @@ impl Card {
     /// valid enum instance.
     pub unsafe fn from_u8_unchecked(value: u8) -> Self {
         debug_assert!(value <= 53, "Value out of bounds.");
-        std::mem::transmute(value)
+        std::mem::transmute::<u8, Card>(value)
+    }
+
+    /// Constructs the [`Card`] of the given [`Rank`] and [`Suit`].
+    #[inline]
+    pub fn new(rank: Rank, suit: Suit) -> Self {
+        let index = (rank as u8 - 1) % 13;
+        // Safety: index < 13 and suit < 4, so the value is below 52.
+        unsafe { Card::from_u8_unchecked(index * 4 + suit as u8) }
+    }
+
+    /// Whether the [`Card`] is a real card rather than [`Card::Unknown`] or [`Card::Xx`].
+    #[inline]
+    pub fn is_known(self) -> bool {
+        self.to_u8() < 52
+    }
+
+    /// The [`Rank`] of a known [`Card`].
+    #[inline]
+    pub fn rank(self) -> Option<Rank> {
+        if !self.is_known() {
+            return None;
+        }
+        Some(match self.to_u8() / 4 {
+            0 => Rank::Ace,
+            1 => Rank::Two,
+            2 => Rank::Three,
+            3 => Rank::Four,
+            4 => Rank::Five,
+            5 => Rank::Six,
+            6 => Rank::Seven,
+            7 => Rank::Eight,
+            8 => Rank::Nine,
+            9 => Rank::Ten,
+            10 => Rank::Jack,
+            11 => Rank::Queen,
+            _ => Rank::King,
+        })
+    }
+
+    /// The [`Suit`] of a known [`Card`].
+    #[inline]
+    pub fn suit(self) -> Option<Suit> {
+        if !self.is_known() {
+            return None;
+        }
+        Some(match self.to_u8() % 4 {
+            0 => Suit::Clubs,
+            1 => Suit::Diamonds,
+            2 => Suit::Hearts,
+            _ => Suit::Spades,
+        })
+    }
+
+    /// Iterates over the 52 known [`Card`]s in encoding order.
+    pub fn deck() -> impl Iterator<Item = Card> {
+        // Safety: all values 0..52 are valid [`Card`]s.
+        (0..52).map(|value| unsafe { Card::from_u8_unchecked(value) })
+    }
+}
+
+/// The rank of a [`Card`], ordered from deuce to ace.
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Hash)]
+pub enum Rank {
+    Two = 2,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    /// All ranks from deuce to ace.
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    /// The single character used for the rank in card notation (e.g. `T` for ten).
+    pub fn to_char(self) -> char {
+        b"23456789TJQKA"[self as usize - 2] as char
+    }
+
+    /// Parses a rank character, accepting either case.
+    pub fn from_char(c: char) -> Option<Self> {
+        let index = "23456789TJQKA".find(c.to_ascii_uppercase())?;
+        Some(Rank::ALL[index])
+    }
+
+    /// The English name of the rank (e.g. "Seven").
+    pub fn name(self) -> &'static str {
+        [
+            "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack",
+            "Queen", "King", "Ace",
+        ][self as usize - 2]
+    }
+
+    /// The plural English name of the rank (e.g. "Sixes").
+    pub fn plural(self) -> &'static str {
+        [
+            "Deuces", "Threes", "Fours", "Fives", "Sixes", "Sevens", "Eights", "Nines", "Tens",
+            "Jacks", "Queens", "Kings", "Aces",
+        ][self as usize - 2]
+    }
+}
+
+/// The suit of a [`Card`], in encoding order.
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Hash)]
+pub enum Suit {
+    Clubs = 0,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl Suit {
+    /// The lowercase character used for the suit in card notation.
+    pub fn to_char(self) -> char {
+        b"cdhs"[self as usize] as char
+    }
+
+    /// Parses a suit character, accepting either case.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_lowercase() {
+            'c' => Some(Suit::Clubs),
+            'd' => Some(Suit::Diamonds),
+            'h' => Some(Suit::Hearts),
+            's' => Some(Suit::Spades),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Card {
+    type Err = io::Error;
+
+    /// Parses the two character notation produced by [`Card`]'s `Display` (e.g. "Ac", "Th", "??").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid card notation: {:?}.", s),
+            )
+        };
+        match s {
+            "??" => return Ok(Card::Unknown),
+            "Xx" => return Ok(Card::Xx),
+            _ => {}
+        }
+        // Some sites write tens as "10".
+        let (rank, suit) = match s.strip_prefix("10") {
+            Some(suit) => (Rank::Ten, suit),
+            None => {
+                let mut chars = s.chars();
+                let rank = chars.next().and_then(Rank::from_char).ok_or_else(invalid)?;
+                (rank, chars.as_str())
+            }
+        };
+        let mut chars = suit.chars();
+        let suit = chars.next().and_then(Suit::from_char).ok_or_else(invalid)?;
+        if chars.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(Card::new(rank, suit))
     }
 }
 
 impl std::fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Card::AceClubs => write!(f, "{}", "Ac"),
-            Card::AceDiamonds => write!(f, "{}", "Ad"),
-            Card::AceHearts => write!(f, "{}", "Ah"),
-            Card::AceSpades => write!(f, "{}", "As"),
-            Card::TwoClubs => write!(f, "{}", "2c"),
-            Card::TwoDiamonds => write!(f, "{}", "2d"),
-            Card::TwoHearts => write!(f, "{}", "2h"),
-            Card::TwoSpades => write!(f, "{}", "2s"),
-            Card::ThreeClubs => write!(f, "{}", "3c"),
-            Card::ThreeDiamonds => write!(f, "{}", "3d"),
-            Card::ThreeHearts => write!(f, "{}", "3h"),
-            Card::ThreeSpades => write!(f, "{}", "3s"),
-            Card::FourClubs => write!(f, "{}", "4c"),
-            Card::FourDiamonds => write!(f, "{}", "4d"),
-            Card::FourHearts => write!(f, "{}", "4h"),
-            Card::FourSpades => write!(f, "{}", "4s"),
-            Card::FiveClubs => write!(f, "{}", "5c"),
-            Card::FiveDiamonds => write!(f, "{}", "5d"),
-            Card::FiveHearts => write!(f, "{}", "5h"),
-            Card::FiveSpades => write!(f, "{}", "5s"),
-            Card::SixClubs => write!(f, "{}", "6c"),
-            Card::SixDiamonds => write!(f, "{}", "6d"),
-            Card::SixHearts => write!(f, "{}", "6h"),
-            Card::SixSpades => write!(f, "{}", "6s"),
-            Card::SevenClubs => write!(f, "{}", "7c"),
-            Card::SevenDiamonds => write!(f, "{}", "7d"),
-            Card::SevenHearts => write!(f, "{}", "7h"),
-            Card::SevenSpades => write!(f, "{}", "7s"),
-            Card::EightClubs => write!(f, "{}", "8c"),
-            Card::EightDiamonds => write!(f, "{}", "8d"),
-            Card::EightHearts => write!(f, "{}", "8h"),
-            Card::EightSpades => write!(f, "{}", "8s"),
-            Card::NineClubs => write!(f, "{}", "9c"),
-            Card::NineDiamonds => write!(f, "{}", "9d"),
-            Card::NineHearts => write!(f, "{}", "9h"),
-            Card::NineSpades => write!(f, "{}", "9s"),
-            Card::TenClubs => write!(f, "{}", "Tc"),
-            Card::TenDiamonds => write!(f, "{}", "Td"),
-            Card::TenHearts => write!(f, "{}", "Th"),
-            Card::TenSpades => write!(f, "{}", "Ts"),
-            Card::JackClubs => write!(f, "{}", "Jc"),
-            Card::JackDiamonds => write!(f, "{}", "Jd"),
-            Card::JackHearts => write!(f, "{}", "Jh"),
-            Card::JackSpades => write!(f, "{}", "Js"),
-            Card::QueenClubs => write!(f, "{}", "Qc"),
-            Card::QueenDiamonds => write!(f, "{}", "Qd"),
-            Card::QueenHearts => write!(f, "{}", "Qh"),
-            Card::QueenSpades => write!(f, "{}", "Qs"),
-            Card::KingClubs => write!(f, "{}", "Kc"),
-            Card::KingDiamonds => write!(f, "{}", "Kd"),
-            Card::KingHearts => write!(f, "{}", "Kh"),
-            Card::KingSpades => write!(f, "{}", "Ks"),
-            Card::Unknown => write!(f, "{}", "??"),
-            Card::Xx => write!(f, "{}", "Xx"),
+            Card::AceClubs => write!(f, "Ac"),
+            Card::AceDiamonds => write!(f, "Ad"),
+            Card::AceHearts => write!(f, "Ah"),
+            Card::AceSpades => write!(f, "As"),
+            Card::TwoClubs => write!(f, "2c"),
+            Card::TwoDiamonds => write!(f, "2d"),
+            Card::TwoHearts => write!(f, "2h"),
+            Card::TwoSpades => write!(f, "2s"),
+            Card::ThreeClubs => write!(f, "3c"),
+            Card::ThreeDiamonds => write!(f, "3d"),
+            Card::ThreeHearts => write!(f, "3h"),
+            Card::ThreeSpades => write!(f, "3s"),
+            Card::FourClubs => write!(f, "4c"),
+            Card::FourDiamonds => write!(f, "4d"),
+            Card::FourHearts => write!(f, "4h"),
+            Card::FourSpades => write!(f, "4s"),
+            Card::FiveClubs => write!(f, "5c"),
+            Card::FiveDiamonds => write!(f, "5d"),
+            Card::FiveHearts => write!(f, "5h"),
+            Card::FiveSpades => write!(f, "5s"),
+            Card::SixClubs => write!(f, "6c"),
+            Card::SixDiamonds => write!(f, "6d"),
+            Card::SixHearts => write!(f, "6h"),
+            Card::SixSpades => write!(f, "6s"),
+            Card::SevenClubs => write!(f, "7c"),
+            Card::SevenDiamonds => write!(f, "7d"),
+            Card::SevenHearts => write!(f, "7h"),
+            Card::SevenSpades => write!(f, "7s"),
+            Card::EightClubs => write!(f, "8c"),
+            Card::EightDiamonds => write!(f, "8d"),
+            Card::EightHearts => write!(f, "8h"),
+            Card::EightSpades => write!(f, "8s"),
+            Card::NineClubs => write!(f, "9c"),
+            Card::NineDiamonds => write!(f, "9d"),
+            Card::NineHearts => write!(f, "9h"),
+            Card::NineSpades => write!(f, "9s"),
+            Card::TenClubs => write!(f, "Tc"),
+            Card::TenDiamonds => write!(f, "Td"),
+            Card::TenHearts => write!(f, "Th"),
+            Card::TenSpades => write!(f, "Ts"),
+            Card::JackClubs => write!(f, "Jc"),
+            Card::JackDiamonds => write!(f, "Jd"),
+            Card::JackHearts => write!(f, "Jh"),
+            Card::JackSpades => write!(f, "Js"),
+            Card::QueenClubs => write!(f, "Qc"),
+            Card::QueenDiamonds => write!(f, "Qd"),
+            Card::QueenHearts => write!(f, "Qh"),
+            Card::QueenSpades => write!(f, "Qs"),
+            Card::KingClubs => write!(f, "Kc"),
+            Card::KingDiamonds => write!(f, "Kd"),
+            Card::KingHearts => write!(f, "Kh"),
+            Card::KingSpades => write!(f, "Ks"),
+            Card::Unknown => write!(f, "??"),
+            Card::Xx => write!(f, "Xx"),
         }
     }
 }
@@ -217,6 +402,33 @@ mod tests {
         assert_eq!(format!("{}", card), "Xx");
     }
 
+    #[test]
+    fn test_card_rank_and_suit() {
+        assert_eq!(Card::AceClubs.rank(), Some(Rank::Ace));
+        assert_eq!(Card::AceClubs.suit(), Some(Suit::Clubs));
+        assert_eq!(Card::TenHearts.rank(), Some(Rank::Ten));
+        assert_eq!(Card::KingSpades.suit(), Some(Suit::Spades));
+        assert_eq!(Card::Unknown.rank(), None);
+        assert_eq!(Card::Xx.suit(), None);
+
+        for card in Card::deck() {
+            let rebuilt = Card::new(card.rank().unwrap(), card.suit().unwrap());
+            assert_eq!(rebuilt, card);
+        }
+    }
+
+    #[test]
+    fn test_card_from_str() {
+        for card in Card::deck().chain([Card::Unknown, Card::Xx]) {
+            assert_eq!(card.to_string().parse::<Card>().unwrap(), card);
+        }
+        assert_eq!("10d".parse::<Card>().unwrap(), Card::TenDiamonds);
+        assert_eq!("tS".parse::<Card>().unwrap(), Card::TenSpades);
+        assert!("1c".parse::<Card>().is_err());
+        assert!("Acc".parse::<Card>().is_err());
+        assert!("".parse::<Card>().is_err());
+    }
+
     #[test]
     fn test_card_from_u8_unchecked() {
         unsafe {
@@ -5,6 +5,9 @@ use std::io::{self, Read, Write};
 pub struct Version(u8);
 
 impl Version {
+    /// The format version written by this crate.
+    pub const CURRENT: Version = Version(0x08);
+
     /// Constructs a [`Version`].
     pub fn new(major: u8, minor: u8) -> Result<Self, VersionError> {
         if major >= 16 {